@@ -29,7 +29,9 @@ pub use shape::{AbstractShape, Edge, Face, Shell, Solid, Vertex, Wire};
 /// the building model utility API
 pub mod builder;
 mod polygon;
-/// the boolean operators: `and`, `or`, `not`.
+/// the boolean operators: `and`, `or`, `difference`, `symmetric_difference`, `not`.
+///
+/// The binary operators throw the error message of the failed operation.
 pub mod shapeops;
 pub use polygon::{PolygonBuffer, PolygonMesh, StlType};
 /// STEP IO
@@ -1,20 +1,46 @@
-use crate::{wasm_bindgen, IntoWasm, Solid};
+use crate::{wasm_bindgen, IntoWasm, JsValue, Solid};
 use truck_shapeops as shapeops;
 
 const SHAPEOPS_TOLERANCE: f64 = 0.05;
 
 /// and operator
 #[wasm_bindgen]
-pub fn and(solid0: &Solid, solid1: &Solid, tol: Option<f64>) -> Option<Solid> {
+pub fn and(solid0: &Solid, solid1: &Solid, tol: Option<f64>) -> Result<Solid, JsValue> {
     let tol = tol.unwrap_or(SHAPEOPS_TOLERANCE);
-    shapeops::and(solid0, solid1, tol).map(IntoWasm::into_wasm)
+    shapeops::and(solid0, solid1, tol)
+        .map(IntoWasm::into_wasm)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 /// or operator
 #[wasm_bindgen]
-pub fn or(solid0: &Solid, solid1: &Solid, tol: Option<f64>) -> Option<Solid> {
+pub fn or(solid0: &Solid, solid1: &Solid, tol: Option<f64>) -> Result<Solid, JsValue> {
     let tol = tol.unwrap_or(SHAPEOPS_TOLERANCE);
-    shapeops::or(solid0, solid1, tol).map(IntoWasm::into_wasm)
+    shapeops::or(solid0, solid1, tol)
+        .map(IntoWasm::into_wasm)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// difference operator: the part of `solid0` outside `solid1`
+#[wasm_bindgen]
+pub fn difference(solid0: &Solid, solid1: &Solid, tol: Option<f64>) -> Result<Solid, JsValue> {
+    let tol = tol.unwrap_or(SHAPEOPS_TOLERANCE);
+    shapeops::difference(solid0, solid1, tol)
+        .map(IntoWasm::into_wasm)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// symmetric difference operator
+#[wasm_bindgen]
+pub fn symmetric_difference(
+    solid0: &Solid,
+    solid1: &Solid,
+    tol: Option<f64>,
+) -> Result<Solid, JsValue> {
+    let tol = tol.unwrap_or(SHAPEOPS_TOLERANCE);
+    shapeops::symmetric_difference(solid0, solid1, tol)
+        .map(IntoWasm::into_wasm)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 /// not operator
@@ -255,6 +255,612 @@ pub fn try_wire_homotopy(wire0: &Wire, wire1: &Wire) -> Result<Shell> {
     Ok(shell)
 }
 
+/// Options of [`loft`] and [`loft_solid`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoftOptions {
+    /// If `true`, the last section is connected to the first one, and the loft has no ends.
+    pub closed: bool,
+    /// If `true`, the adjacent sections are connected by the ruled surfaces.
+    pub ruled: bool,
+    /// The degree of the surfaces in the direction of the loft, bounded by the number of the sections.
+    /// Ignored if `ruled` is `true`.
+    pub degree: usize,
+}
+
+impl Default for LoftOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            closed: false,
+            ruled: false,
+            degree: 3,
+        }
+    }
+}
+
+/// Returns a shell skinning through the sections `wires` in order.
+/// # Details
+/// The `i`-th edges of the wires are connected by a NURBS surface interpolating the sections.
+/// The degrees and the knot vectors of the curves of the edges are synchronized in advance,
+/// and the sections are parametrized by the mean distances between the adjacent wires.
+/// - If neither `options.closed` nor `options.ruled`, each face spans all the sections,
+///   and the edges of the first and the last wires remain as the boundary of the shell.
+/// - Otherwise, the faces are divided at each section. The closed loft is interpolated cyclically,
+///   so that the surfaces are smooth also at the first section.
+/// # Examples
+/// ```
+/// // a transition duct from a square to a circle
+/// use truck_modeling::*;
+///
+/// let square: Wire = {
+///     let v = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+///         .map(|(x, y)| builder::vertex(Point3::new(x, y, 0.0)));
+///     (0..4).map(|i| builder::line(&v[i], &v[(i + 1) % 4])).collect()
+/// };
+/// let circle = |z: f64| -> Wire {
+///     let r = f64::sqrt(0.5);
+///     let v = [(-r, -r), (r, -r), (r, r), (-r, r)]
+///         .map(|(x, y)| builder::vertex(Point3::new(x, y, z)));
+///     let transits = [(0.0, -1.0), (1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)]
+///         .map(|(x, y)| Point3::new(x, y, z));
+///     (0..4)
+///         .map(|i| builder::circle_arc(&v[i], &v[(i + 1) % 4], transits[i]))
+///         .collect()
+/// };
+/// let wires = [square, circle(1.0), circle(2.0)];
+///
+/// let shell = builder::loft(&wires, builder::LoftOptions::default()).unwrap();
+/// assert_eq!(shell.len(), 4);
+/// assert_eq!(shell.extract_boundaries().len(), 2);
+/// assert!(shell.is_geometric_consistent());
+///
+/// // the ruled loft is divided at the middle circle.
+/// let options = builder::LoftOptions {
+///     ruled: true,
+///     ..Default::default()
+/// };
+/// let shell = builder::loft(&wires, options).unwrap();
+/// assert_eq!(shell.len(), 8);
+/// assert_eq!(shell.shell_condition(), ShellCondition::Oriented);
+/// ```
+/// # Failures
+/// - If the wires have different numbers of edges, returns `Error::NotSameNumberOfEdges`.
+/// - If there are less than two wires, two adjacent wires coincide, or the wires contain
+///   intersection curves, returns `Error::InvalidLoftSections`.
+/// ```
+/// use truck_modeling::{*, errors::Error};
+///
+/// let v = builder::vertex(Point3::origin());
+/// let wire: Wire = builder::rsweep(&v, Point3::new(1.0, 0.0, 0.0), Vector3::unit_z(), Rad(7.0));
+/// assert_eq!(
+///     builder::loft(&[wire.clone()], builder::LoftOptions::default()).unwrap_err(),
+///     Error::InvalidLoftSections,
+/// );
+/// assert_eq!(
+///     builder::loft(&[wire.clone(), wire], builder::LoftOptions::default()).unwrap_err(),
+///     Error::InvalidLoftSections,
+/// );
+/// ```
+pub fn loft(wires: &[Wire], options: LoftOptions) -> Result<Shell> {
+    const SAMPLE_DIVISION: usize = 4;
+    let LoftOptions {
+        closed,
+        ruled,
+        degree,
+    } = options;
+    let n = wires.len();
+    let has_intersection_curve = wires
+        .iter()
+        .flat_map(Wire::edge_iter)
+        .any(|edge| matches!(edge.curve(), Curve::IntersectionCurve(_)));
+    if n < 2 || has_intersection_curve {
+        return Err(Error::InvalidLoftSections);
+    }
+    if wires.iter().any(|wire| wire.len() != wires[0].len()) {
+        return Err(Error::NotSameNumberOfEdges);
+    }
+    let degree = match ruled {
+        true => 1,
+        false => usize::max(degree, 1),
+    };
+
+    // the indices of the interpolated wires, padded cyclically for the closed loft.
+    // The influence of the ends of the padding decays geometrically, so that the interpolation
+    // is periodic up to the tolerance at the first section.
+    let pad = match closed && !ruled {
+        true => 4 * degree,
+        false => 0,
+    };
+    let seq = (0..n + closed as usize + 2 * pad)
+        .map(|k| (k + n * pad - pad) % n)
+        .collect::<Vec<_>>();
+    let samples = seq
+        .iter()
+        .map(|&s| {
+            wires[s]
+                .edge_iter()
+                .flat_map(|edge| {
+                    let curve = edge.oriented_curve();
+                    let (t0, t1) = curve.range_tuple();
+                    (0..SAMPLE_DIVISION).map(move |i| {
+                        curve.subs(t0 + (t1 - t0) * i as f64 / SAMPLE_DIVISION as f64)
+                    })
+                })
+                .collect()
+        })
+        .collect::<Vec<_>>();
+    let params = geom_impls::loft_parameters(&samples).ok_or(Error::InvalidLoftSections)?;
+    let knot_vec = geom_impls::averaging_knot_vec(&params, usize::min(degree, seq.len() - 1));
+    let interpole = |points: Vec<Vector4>| {
+        let parameter_points = params.iter().copied().zip(points).collect::<Vec<_>>();
+        BSplineCurve::try_interpole(knot_vec.clone(), parameter_points).ok()
+    };
+    let spans = match closed || ruled {
+        true => (pad..pad + n - 1 + closed as usize)
+            .map(|k| (k, k + 1))
+            .collect::<Vec<_>>(),
+        false => vec![(0, n - 1)],
+    };
+
+    let section_vertex = |s: usize, i: usize, back: bool| match back {
+        true => wires[s][i].back(),
+        false => wires[s][i].front(),
+    };
+    let mut side_edges = truck_base::entry_map::FxEntryMap::new(
+        |(k, i, back): (usize, usize, bool)| (k, section_vertex(seq[spans[k].0], i, back).id()),
+        |(k, i, back)| {
+            let (a, b) = spans[k];
+            let v0 = section_vertex(seq[a], i, back);
+            let v1 = section_vertex(seq[b], i, back);
+            if ruled {
+                return Some(line(v0, v1));
+            }
+            let points = seq
+                .iter()
+                .map(|&s| section_vertex(s, i, back).point().to_homogeneous())
+                .collect();
+            let curve = geom_impls::sub_curve(&interpole(points)?, (params[a], params[b]));
+            Some(Edge::new(v0, v1, NurbsCurve::new(curve).into()))
+        },
+    );
+    let mut shell = Shell::new();
+    for i in 0..wires[0].len() {
+        let mut curves = wires
+            .iter()
+            .map(|wire| wire[i].oriented_curve().lift_up())
+            .collect::<Vec<_>>();
+        let (first, others) = curves.split_first_mut().unwrap();
+        // the second passes propagate the maximum degree and all the knots to the others.
+        (0..2).for_each(|_| others.iter_mut().for_each(|c| first.syncro_degree(c)));
+        (0..2).for_each(|_| others.iter_mut().for_each(|c| first.syncro_knots(c)));
+        let columns = (0..curves[0].control_points().len())
+            .map(|j| interpole(seq.iter().map(|&s| *curves[s].control_point(j)).collect()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::InvalidLoftSections)?;
+        for (k, &(a, b)) in spans.iter().enumerate() {
+            let columns = columns
+                .iter()
+                .map(|column| geom_impls::sub_curve(column, (params[a], params[b])))
+                .collect::<Vec<_>>();
+            let knot_vecs = (curves[0].knot_vec().clone(), columns[0].knot_vec().clone());
+            let control_points = columns.into_iter().map(|c| c.destruct().1).collect();
+            let surface = BSplineSurface::new(knot_vecs, control_points);
+            let mut side_edge = |back: bool| {
+                let edge = side_edges.entry_or_insert((k, i, back)).clone();
+                edge.ok_or(Error::InvalidLoftSections)
+            };
+            let (front, back) = (side_edge(false)?, side_edge(true)?);
+            let wire: Wire = vec![
+                wires[seq[a]][i].clone(),
+                back,
+                wires[seq[b]][i].inverse(),
+                front.inverse(),
+            ]
+            .into();
+            shell.push(Face::new(
+                vec![wire],
+                Surface::NurbsSurface(NurbsSurface::new(surface)),
+            ));
+        }
+    }
+    Ok(shell)
+}
+
+/// Returns a solid skinning through the sections `wires` in order, capped by planes at the ends.
+/// # Details
+/// The side faces are the same as [`loft`]. If `options.closed` is `false`, the first and the last
+/// wires are capped by [`try_attach_plane`], so that they should be closed and planar. The wires
+/// should be oriented counterclockwise seen from the direction of the loft, so that the solid is
+/// not inside out.
+/// # Examples
+/// ```
+/// // a frustum of the square pyramid
+/// use truck_modeling::*;
+///
+/// let square = |a: f64, z: f64| -> Wire {
+///     let v = [(-a, -a), (a, -a), (a, a), (-a, a)]
+///         .map(|(x, y)| builder::vertex(Point3::new(x, y, z)));
+///     (0..4).map(|i| builder::line(&v[i], &v[(i + 1) % 4])).collect()
+/// };
+/// let wires = [square(1.0, 0.0), square(0.5, 1.0)];
+/// let solid = builder::loft_solid(&wires, builder::LoftOptions::default()).unwrap();
+/// assert_eq!(solid.boundaries()[0].len(), 6);
+/// assert_eq!(solid.boundaries()[0].shell_condition(), ShellCondition::Closed);
+/// ```
+/// # Failures
+/// In addition to [`loft`], if the first or the last wire is not closed or not planar, returns
+/// the error of [`try_attach_plane`].
+pub fn loft_solid(wires: &[Wire], options: LoftOptions) -> Result<Solid> {
+    let mut shell = loft(wires, options)?;
+    if !options.closed {
+        shell.push(try_attach_plane(&[wires[0].inverse()])?);
+        shell.push(try_attach_plane(&[wires[wires.len() - 1].clone()])?);
+    }
+    Ok(Solid::try_new(vec![shell])?)
+}
+
+/// The frames of the profile along the path of [`path_sweep`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SweepFrame {
+    /// The Frenet frame by the tangent and the curvature direction of the path.
+    /// On the straight parts of the path, the frame is carried without rotation.
+    Frenet,
+    /// The frame whose normal is the projection of the fixed vector, e.g. the up direction of a rail.
+    Fixed(Vector3),
+    /// The rotation minimizing frame by the double reflection. On a closed path,
+    /// the remaining twist is distributed along the path.
+    RotationMinimizing,
+}
+
+/// The profiles of [`path_sweep`]: a wire is swept to a shell, and a face to a solid.
+pub trait PathSweep {
+    /// The swept shape
+    type Swept;
+    /// Sweeps `self` along `path` with `frame`. cf. [`path_sweep`]
+    fn path_sweep(&self, path: &Wire, frame: SweepFrame) -> Result<Self::Swept>;
+}
+
+/// Sweeps a wire or a face `profile` along the wire `path`.
+/// # Details
+/// The profile is placed in the space as it is, and moved by the frames relative to the frame at
+/// the front of the path. Along each edge of the path, the profile is transformed at the stations
+/// dividing the edge, and skinned by [`loft`], so that the surfaces are fitted B-spline surfaces.
+/// - The sections at the vertices of the path are shared by the adjacent edges, so that the faces are
+///   continuous at the tangent-continuous vertices. At the corners, the sections are projected to
+///   the bisector planes of the tangents, like mitered pipes.
+/// - A face is swept to a solid capped by the face and its copy at the end of the path. If the path
+///   is closed and the profile returns to its first place, the result is closed without the caps.
+/// # Examples
+/// ```
+/// // a pipe bent by a quarter of the circle
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+///
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.2));
+/// let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_x(), Rad(7.0));
+/// let disk = builder::try_attach_plane(&[circle]).unwrap();
+///
+/// let p = [(0.0, 0.0), (1.0, 0.0), (2.0, 1.0), (2.0, 2.0)]
+///     .map(|(x, y)| builder::vertex(Point3::new(x, y, 0.0)));
+/// let path: Wire = vec![
+///     builder::line(&p[0], &p[1]),
+///     builder::circle_arc(&p[1], &p[2], Point3::new(1.0 + f64::sqrt(0.5), 1.0 - f64::sqrt(0.5), 0.0)),
+///     builder::line(&p[2], &p[3]),
+/// ]
+/// .into();
+///
+/// let pipe = builder::path_sweep(&disk, &path, builder::SweepFrame::RotationMinimizing).unwrap();
+/// let shell = &pipe.boundaries()[0];
+/// assert_eq!(shell.len(), 11);
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+///
+/// // the end of the pipe is perpendicular to the path.
+/// let end = shell.iter().find(|face| face.vertex_iter().all(|v| v.point().y.near(&2.0)));
+/// assert!(end.is_some());
+/// ```
+/// # Failures
+/// If the path is not continuous, has a vanishing derivative or a U-turn, or the fixed vector is
+/// parallel to the path, returns `Error::InvalidSweepPath`. The errors of [`loft`] are also returned.
+#[inline(always)]
+pub fn path_sweep<T: PathSweep>(profile: &T, path: &Wire, frame: SweepFrame) -> Result<T::Swept> {
+    profile.path_sweep(path, frame)
+}
+
+impl PathSweep for Wire {
+    type Swept = Shell;
+    fn path_sweep(&self, path: &Wire, frame: SweepFrame) -> Result<Shell> {
+        let transforms = geom_impls::path_transforms(path, frame).ok_or(Error::InvalidSweepPath)?;
+        let wires = [self.clone()];
+        let last = returned_sections(&wires, path, &transforms);
+        sweep_sections(&wires, &transforms, last)
+    }
+}
+
+impl PathSweep for Face {
+    type Swept = Solid;
+    fn path_sweep(&self, path: &Wire, frame: SweepFrame) -> Result<Solid> {
+        let transforms = geom_impls::path_transforms(path, frame).ok_or(Error::InvalidSweepPath)?;
+        // the face is inverted if its boundary turns clockwise around the path.
+        let curve = path
+            .front()
+            .ok_or(Error::InvalidSweepPath)?
+            .oriented_curve();
+        let tangent = curve.der(curve.range_tuple().0);
+        let points = self.boundaries()[0]
+            .edge_iter()
+            .flat_map(|edge| {
+                let curve = edge.oriented_curve();
+                let (t0, t1) = curve.range_tuple();
+                (0..4).map(move |i| curve.subs(t0 + (t1 - t0) * i as f64 / 4.0))
+            })
+            .collect::<Vec<_>>();
+        let area = (0..points.len()).fold(Vector3::zero(), |sum, i| {
+            let (p, q) = (points[i], points[(i + 1) % points.len()]);
+            sum + p.to_vec().cross(q.to_vec())
+        });
+        let face = match area.dot(tangent) < 0.0 {
+            true => self.inverse(),
+            false => self.clone(),
+        };
+        let wires = face.boundaries();
+        if let Some(last) = returned_sections(&wires, path, &transforms) {
+            let shell = sweep_sections(&wires, &transforms, Some(last))?;
+            return Ok(Solid::try_new(vec![shell])?);
+        }
+        let mat = *transforms.last().and_then(|m| m.last()).unwrap();
+        let end = transformed(&face, mat);
+        let mut shell = sweep_sections(&wires, &transforms, Some(end.boundaries()))?;
+        shell.push(face.inverse());
+        shell.push(end);
+        Ok(Solid::try_new(vec![shell])?)
+    }
+}
+
+/// Returns `wires` if the path is closed and the profile returns to its first place.
+fn returned_sections(
+    wires: &[Wire],
+    path: &Wire,
+    transforms: &[Vec<Matrix4>],
+) -> Option<Vec<Wire>> {
+    let mat = transforms.last()?.last()?;
+    let returned = wires
+        .iter()
+        .flat_map(Wire::vertex_iter)
+        .all(|v| mat.transform_point(v.point()).near(&v.point()));
+    (path.is_closed() && returned).then(|| wires.to_vec())
+}
+
+/// Skins the sections transformed by `transforms` along the edges of the path.
+fn sweep_sections(
+    wires: &[Wire],
+    transforms: &[Vec<Matrix4>],
+    last: Option<Vec<Wire>>,
+) -> Result<Shell> {
+    let transform_wires =
+        |mat: Matrix4| -> Vec<Wire> { wires.iter().map(|wire| transformed(wire, mat)).collect() };
+    let mut shell = Shell::new();
+    let mut start = wires.to_vec();
+    for (k, mats) in transforms.iter().enumerate() {
+        let (inner, end) = mats.split_at(mats.len() - 1);
+        let end = match (k + 1 == transforms.len(), &last) {
+            (true, Some(last)) => last.clone(),
+            _ => transform_wires(end[0]),
+        };
+        let inner = inner
+            .iter()
+            .map(|mat| transform_wires(*mat))
+            .collect::<Vec<_>>();
+        for (i, wire) in start.iter().enumerate() {
+            let sections = std::iter::once(wire.clone())
+                .chain(inner.iter().map(|wires| wires[i].clone()))
+                .chain(std::iter::once(end[i].clone()))
+                .collect::<Vec<_>>();
+            shell.append(&mut loft(&sections, LoftOptions::default())?);
+        }
+        start = end;
+    }
+    Ok(shell)
+}
+
+/// Returns a helix around the axis through `axis_origin` with the direction `axis`.
+/// # Details
+/// The helix turns counterclockwise around `axis` and advances by `pitch` along `axis` for each turn,
+/// so that a negative `pitch` gives the left-handed helix. The helix is divided into three edges per turn,
+/// whose curves are the cubic B-spline curves interpolating the helix. If `turns` is not positive,
+/// returns the empty wire.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let helix = builder::helix(Point3::origin(), Vector3::unit_z(), 2.0, 0.5, 4.0);
+/// assert_eq!(helix.len(), 12);
+/// assert!(helix.is_continuous());
+/// let (front, back) = (helix.front_vertex().unwrap(), helix.back_vertex().unwrap());
+/// assert_near!(back.point() - front.point(), Vector3::new(0.0, 0.0, 2.0));
+/// # helix.edge_iter().for_each(|edge| {
+/// #     let curve = edge.curve();
+/// #     (0..=10).for_each(|i| {
+/// #         let p = curve.subs(i as f64 / 10.0);
+/// #         assert!(f64::abs(p.x * p.x + p.y * p.y - 4.0) < 1.0e-4);
+/// #     });
+/// # });
+/// ```
+pub fn helix(axis_origin: Point3, axis: Vector3, radius: f64, pitch: f64, turns: f64) -> Wire {
+    let axis = axis.normalize();
+    let radial = geom_impls::take_one_axis_by_normal(axis) * radius;
+    helix_wire(axis_origin, axis, radial, pitch, turns)
+}
+
+fn helix_wire(origin: Point3, axis: Vector3, radial: Vector3, pitch: f64, turns: f64) -> Wire {
+    let division = f64::ceil(3.0 * turns - TOLERANCE).max(0.0) as usize;
+    let mut vertex0 = vertex(origin + radial);
+    (0..division)
+        .map(|i| {
+            let range = (turns * i as f64, turns * (i + 1) as f64);
+            let range = (range.0 / division as f64, range.1 / division as f64);
+            let curve = geom_impls::helix_curve(origin, axis, radial, pitch, range);
+            let vertex1 = vertex(curve.back());
+            let edge = Edge::new(&vertex0, &vertex1, curve.into());
+            vertex0 = vertex1;
+            edge
+        })
+        .collect()
+}
+
+/// Sweeps the face `profile` helically around the axis through `axis_origin` with the direction `axis`.
+/// # Details
+/// The profile is moved by the screw motion, turning counterclockwise around `axis` and advancing
+/// by `pitch` for each turn, by [`path_sweep`] along the helix through the center of the profile
+/// with the Frenet frame. Springs are swept by the disks, and threads by the profiles in the planes
+/// containing the axis.
+/// # Examples
+/// ```
+/// // a spring
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::new(2.2, 0.0, 0.0));
+/// let circle = builder::rsweep(&v, Point3::new(2.0, 0.0, 0.0), Vector3::unit_y(), Rad(7.0));
+/// let disk = builder::try_attach_plane(&[circle]).unwrap();
+/// let spring = builder::helical_sweep(&disk, Point3::origin(), Vector3::unit_z(), 1.0, 2.0).unwrap();
+/// let shell = &spring.boundaries()[0];
+/// assert_eq!(shell.len(), 20);
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+/// ```
+/// # Failures
+/// If the center of the profile is on the axis or `turns` is not positive, returns
+/// `Error::InvalidSweepPath`. The errors of [`path_sweep`] are also returned.
+pub fn helical_sweep(
+    profile: &Face,
+    axis_origin: Point3,
+    axis: Vector3,
+    pitch: f64,
+    turns: f64,
+) -> Result<Solid> {
+    let axis = axis.normalize();
+    let points = profile.vertex_iter().map(|v| v.point()).collect::<Vec<_>>();
+    let center = points
+        .iter()
+        .fold(Point3::origin(), |sum, p| sum + p.to_vec())
+        / points.len() as f64;
+    let vec = center - axis_origin;
+    let radial = vec - axis * vec.dot(axis);
+    if radial.so_small() || turns < TOLERANCE {
+        return Err(Error::InvalidSweepPath);
+    }
+    let origin = axis_origin + axis * vec.dot(axis);
+    let path = helix_wire(origin, axis, radial, pitch, turns);
+    path_sweep(profile, &path, SweepFrame::Frenet)
+}
+
+/// The pitches of the threads by the standards, in the model of millimeters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThreadPitch {
+    /// ISO metric thread by the pitch in millimeters.
+    Metric(f64),
+    /// Unified thread, e.g. UNC or UNF, by the number of the threads per inch.
+    Unified(f64),
+}
+
+impl ThreadPitch {
+    /// Returns the pitch in millimeters.
+    #[inline(always)]
+    pub fn pitch(self) -> f64 {
+        match self {
+            Self::Metric(pitch) => pitch,
+            Self::Unified(threads_per_inch) => 25.4 / threads_per_inch,
+        }
+    }
+}
+
+/// Returns the solid of the groove of the thread on the cylindrical face `face`.
+/// # Details
+/// The metric and the unified threads have the same basic profile with the flank angle of 60°.
+/// The height of the fundamental triangle is `H = √3 / 2 * pitch`, and the depth of the thread is `5H / 8`.
+/// - If the normal of `face` is outward, the face is the major cylinder of an external thread, e.g. a bolt.
+///   The groove is from the major cylinder to the minor one, with the flat of `pitch / 4` at the root.
+/// - If the normal is inward, the face is the minor cylinder of an internal thread, e.g. a nut.
+///   The groove is from the minor cylinder to the major one, with the flat of `pitch / 8` at the root.
+///
+/// The groove slightly sticks out of the face so that it is cleanly subtracted from the solid by
+/// the boolean operations, e.g. `truck_shapeops::difference`. The groove is the right-handed thread
+/// over the length of the face, and the profile is centered at the ends.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// // M10 x 1.5 over the length of 3 mm
+/// let v = builder::vertex(Point3::new(5.0, 0.0, 0.0));
+/// let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+/// let disk = builder::try_attach_plane(&[circle]).unwrap();
+/// let shaft = builder::tsweep(&disk, Vector3::unit_z() * 3.0);
+/// let side = &shaft.boundaries()[0][1];
+/// let groove = builder::thread(side, builder::ThreadPitch::Metric(1.5)).unwrap();
+/// let shell = &groove.boundaries()[0];
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+///
+/// let depth = 5.0 / 8.0 * f64::sqrt(3.0) / 2.0 * 1.5;
+/// shell.vertex_iter().for_each(|v| {
+///     let r = f64::hypot(v.point().x, v.point().y);
+///     assert!(r > 5.0 - depth - 1.0e-4 && r < 5.0 + depth);
+/// });
+/// ```
+/// # Failures
+/// If the boundary of `face` is not on a cylinder, or the pitch is not positive, returns `Error::InvalidThread`.
+pub fn thread(face: &Face, pitch: ThreadPitch) -> Result<Solid> {
+    let pitch = pitch.pitch();
+    let surface = face.oriented_surface();
+    let samples = face
+        .boundary_iters()
+        .into_iter()
+        .flatten()
+        .flat_map(|edge| {
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            (0..4).map(move |i| curve.subs(t0 + (t1 - t0) * i as f64 / 4.0))
+        })
+        .map(|point| {
+            let (u, v) = surface.search_parameter(point, None, 100)?;
+            Some((point, surface.normal(u, v)))
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::InvalidThread)?;
+    let (origin, axis) = geom_impls::fit_cylinder(&samples).ok_or(Error::InvalidThread)?;
+    if pitch < TOLERANCE {
+        return Err(Error::InvalidThread);
+    }
+    let (point, normal) = samples[0];
+    let radial = (point - origin) - axis * (point - origin).dot(axis);
+    let heights = samples.iter().map(|(p, _)| (p - origin).dot(axis));
+    let (h0, h1) = heights.fold((f64::INFINITY, f64::NEG_INFINITY), |(h0, h1), h| {
+        (f64::min(h0, h), f64::max(h1, h))
+    });
+    // the thread is external if the face looks outward from the axis.
+    let external = normal.dot(radial) > 0.0;
+
+    // the groove in the coordinates of the depth and the height
+    let (radius, unit) = (radial.magnitude(), radial.normalize());
+    let height = f64::sqrt(3.0) / 2.0 * pitch;
+    let (depth, margin) = (height * 5.0 / 8.0, height / 16.0);
+    let (top, root) = match external {
+        true => (pitch * 7.0 / 8.0, pitch / 4.0),
+        false => (pitch * 3.0 / 4.0, pitch / 8.0),
+    };
+    let top = (top + 2.0 * margin / f64::sqrt(3.0)) / 2.0;
+    let sign = if external { -1.0 } else { 1.0 };
+    let profile = [
+        (-margin, -top),
+        (depth, -root / 2.0),
+        (depth, root / 2.0),
+        (-margin, top),
+    ]
+    .map(|(d, h)| vertex(origin + axis * (h0 + h) + unit * (radius + sign * d)));
+    let wire: Wire = (0..4)
+        .map(|i| line(&profile[i], &profile[(i + 1) % 4]))
+        .collect();
+    let profile = try_attach_plane(&[wire])?;
+    helical_sweep(&profile, origin, axis, pitch, (h1 - h0) / pitch)
+}
+
 /// Creates a cone by R-sweeping.
 /// # Examples
 /// ```
@@ -350,6 +956,248 @@ pub fn cone<R: Into<Rad<f64>>>(wire: &Wire, axis: Vector3, angle: R) -> Shell {
     shell
 }
 
+/// Returns the box with the opposite corners `corner0` and `corner1` whose faces are parallel to the coordinate planes.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let cuboid = builder::cuboid(Point3::new(-1.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0)).unwrap();
+/// let shell = &cuboid.boundaries()[0];
+/// assert_eq!(shell.len(), 6);
+/// assert!(cuboid.is_geometric_consistent());
+/// # let face = &shell[5];
+/// # assert_near!(face.oriented_surface().normal(0.5, 0.5), Vector3::unit_z());
+/// ```
+/// # Failures
+/// If the corners have the same coordinate, that is, the box is flat, returns `Error::InvalidPrimitive`.
+pub fn cuboid(corner0: Point3, corner1: Point3) -> Result<Solid> {
+    let min = Point3::new(
+        f64::min(corner0.x, corner1.x),
+        f64::min(corner0.y, corner1.y),
+        f64::min(corner0.z, corner1.z),
+    );
+    let diag = (corner1 - corner0).map(f64::abs);
+    if diag.x < TOLERANCE || diag.y < TOLERANCE || diag.z < TOLERANCE {
+        return Err(Error::InvalidPrimitive);
+    }
+    let v = vertex(min);
+    let edge = tsweep(&v, diag.x * Vector3::unit_x());
+    let face = tsweep(&edge, diag.y * Vector3::unit_y());
+    Ok(tsweep(&face, diag.z * Vector3::unit_z()))
+}
+
+/// Returns the cylinder whose bottom is the disk with the center `origin` and the normal `-axis`,
+/// extended by `height` along `axis`.
+/// # Details
+/// The solid is created by revolving the rectangle around the axis by [`cone_solid`],
+/// so all the faces are [`RevolutedCurve`]s.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let cylinder = builder::cylinder(Point3::origin(), Vector3::unit_y(), 1.0, 2.0).unwrap();
+/// let shell = &cylinder.boundaries()[0];
+/// assert!(cylinder.is_geometric_consistent());
+/// assert!(shell.iter().all(|face| matches!(face.surface(), Surface::RevolutedCurve(_))));
+/// ```
+/// # Failures
+/// If `axis` is zero, or `radius` or `height` is not positive, returns `Error::InvalidPrimitive`.
+#[inline(always)]
+pub fn cylinder(origin: Point3, axis: Vector3, radius: f64, height: f64) -> Result<Solid> {
+    if radius < TOLERANCE {
+        return Err(Error::InvalidPrimitive);
+    }
+    cone_solid(origin, axis, radius, radius, height)
+}
+
+/// Returns the truncated cone whose bottom is the disk with the center `origin` and the radius `radius0`,
+/// and whose top is the disk with the radius `radius1` at `height` along `axis`.
+/// # Details
+/// If one of the radii is zero, the solid is the cone with the apex on the axis.
+/// The solid is created by revolving the profile around the axis by [`cone`],
+/// so all the faces are [`RevolutedCurve`]s.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let cone = builder::cone_solid(Point3::origin(), Vector3::unit_z(), 1.0, 0.0, 2.0).unwrap();
+/// let shell = &cone.boundaries()[0];
+/// assert_eq!(shell.len(), 6);
+/// assert!(cone.is_geometric_consistent());
+///
+/// let frustum = builder::cone_solid(Point3::origin(), Vector3::unit_z(), 1.0, 0.5, 2.0).unwrap();
+/// let shell = &frustum.boundaries()[0];
+/// assert_eq!(shell.len(), 9);
+/// assert!(frustum.is_geometric_consistent());
+/// ```
+/// # Failures
+/// If `axis` is zero, `height` is not positive, one of the radii is negative, or both radii are zero,
+/// returns `Error::InvalidPrimitive`.
+pub fn cone_solid(
+    origin: Point3,
+    axis: Vector3,
+    radius0: f64,
+    radius1: f64,
+    height: f64,
+) -> Result<Solid> {
+    let valid_radii = radius0 >= 0.0 && radius1 >= 0.0 && f64::max(radius0, radius1) >= TOLERANCE;
+    if axis.so_small() || height < TOLERANCE || !valid_radii {
+        return Err(Error::InvalidPrimitive);
+    }
+    let axis = axis.normalize();
+    let radial = geom_impls::take_one_axis_by_normal(axis);
+    let top = origin + axis * height;
+    let points = [
+        top,
+        top + radial * radius1,
+        origin + radial * radius0,
+        origin,
+    ];
+    let vertices = points
+        .iter()
+        .enumerate()
+        .filter(|&(i, p)| i == 0 || !p.near(&points[i - 1]))
+        .map(|(_, p)| vertex(*p))
+        .collect::<Vec<_>>();
+    let wire = vertices
+        .windows(2)
+        .map(|v| line(&v[0], &v[1]))
+        .collect::<Wire>();
+    Ok(Solid::new(vec![cone(&wire, axis, Rad(7.0))]))
+}
+
+/// Returns the sphere with the center `center` and the radius `radius`.
+/// # Details
+/// The solid is created by revolving the semicircle around the axis parallel to the z-axis by [`cone`],
+/// so all the faces are [`RevolutedCurve`]s.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let sphere = builder::sphere(Point3::new(1.0, 2.0, 3.0), 2.0).unwrap();
+/// let shell = &sphere.boundaries()[0];
+/// assert!(sphere.is_geometric_consistent());
+/// shell.vertex_iter().for_each(|v| assert_near!(v.point().distance(Point3::new(1.0, 2.0, 3.0)), 2.0));
+/// ```
+/// # Failures
+/// If `radius` is not positive, returns `Error::InvalidPrimitive`.
+pub fn sphere(center: Point3, radius: f64) -> Result<Solid> {
+    if radius < TOLERANCE {
+        return Err(Error::InvalidPrimitive);
+    }
+    let v = vertex(center + radius * Vector3::unit_z());
+    let wire: Wire = rsweep(&v, center, Vector3::unit_y(), PI);
+    Ok(Solid::new(vec![cone(&wire, Vector3::unit_z(), Rad(7.0))]))
+}
+
+/// Returns the torus around the axis through `center` with the direction `axis`.
+/// # Details
+/// `major_radius` is the distance from the axis to the center of the tube, and `minor_radius`
+/// is the radius of the tube. The solid is created by revolving the circle by [`rsweep`],
+/// so all the faces are [`RevolutedCurve`]s.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let torus = builder::torus(Point3::origin(), Vector3::unit_z(), 0.75, 0.25).unwrap();
+/// let shell = &torus.boundaries()[0];
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+/// assert!(torus.is_geometric_consistent());
+/// ```
+/// # Failures
+/// If `axis` is zero, `minor_radius` is not positive, or `major_radius` is not larger than `minor_radius`,
+/// that is, the tube intersects itself, returns `Error::InvalidPrimitive`.
+pub fn torus(center: Point3, axis: Vector3, major_radius: f64, minor_radius: f64) -> Result<Solid> {
+    if axis.so_small() || minor_radius < TOLERANCE || major_radius < minor_radius + TOLERANCE {
+        return Err(Error::InvalidPrimitive);
+    }
+    let axis = axis.normalize();
+    let radial = geom_impls::take_one_axis_by_normal(axis);
+    let tube_center = center + radial * major_radius;
+    let v = vertex(tube_center + axis * minor_radius);
+    let circle = rsweep(&v, tube_center, axis.cross(radial), Rad(7.0));
+    Ok(Solid::new(vec![rsweep(&circle, center, axis, Rad(7.0))]))
+}
+
+/// Returns the wedge, the box whose top face is shrunk along the x-axis.
+/// # Details
+/// The bottom of the wedge is the rectangle `[0, size.x] x [0, size.z]` in the plane `y = origin.y`,
+/// and the top is the rectangle `[0, top_length] x [0, size.z]` in the plane `y = origin.y + size.y`,
+/// relative to `origin`. If `top_length` is zero, the wedge is the triangular prism.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let wedge = builder::wedge(Point3::origin(), Vector3::new(2.0, 1.0, 1.0), 0.5).unwrap();
+/// assert_eq!(wedge.boundaries()[0].len(), 6);
+/// assert!(wedge.is_geometric_consistent());
+///
+/// let prism = builder::wedge(Point3::origin(), Vector3::new(2.0, 1.0, 1.0), 0.0).unwrap();
+/// assert_eq!(prism.boundaries()[0].len(), 5);
+/// ```
+/// # Failures
+/// If the sizes are not positive or `top_length` is not in `[0, size.x]`, returns `Error::InvalidPrimitive`.
+pub fn wedge(origin: Point3, size: Vector3, top_length: f64) -> Result<Solid> {
+    if size.x < TOLERANCE || size.y < TOLERANCE || size.z < TOLERANCE {
+        return Err(Error::InvalidPrimitive);
+    }
+    if top_length < 0.0 || top_length > size.x + TOLERANCE {
+        return Err(Error::InvalidPrimitive);
+    }
+    let mut polygon = vec![
+        origin,
+        origin + Vector3::new(size.x, 0.0, 0.0),
+        origin + Vector3::new(top_length, size.y, 0.0),
+    ];
+    if top_length > TOLERANCE {
+        polygon.push(origin + Vector3::new(0.0, size.y, 0.0));
+    }
+    prism(&polygon, size.z)
+}
+
+/// Returns the prism extruding the polygon by `height` along its normal.
+/// # Details
+/// The normal of the polygon is the direction around which the polygon turns counterclockwise.
+/// If `height` is negative, the polygon is extruded to the opposite side.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let polygon = [
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0),
+///     Point3::new(0.5, 0.5, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// ];
+/// let prism = builder::prism(&polygon, 2.0).unwrap();
+/// let shell = &prism.boundaries()[0];
+/// assert_eq!(shell.len(), 7);
+/// assert!(prism.is_geometric_consistent());
+/// # shell.vertex_iter().for_each(|v| assert!(v.point().z > -1.0e-6 && v.point().z < 2.0 + 1.0e-6));
+/// ```
+/// # Failures
+/// - If the polygon has less than three points or `height` is zero, returns `Error::InvalidPrimitive`.
+/// - If the polygon is not in one plane or is degenerate, returns `Error::WireNotInOnePlane`.
+pub fn prism(polygon: &[Point3], height: f64) -> Result<Solid> {
+    if polygon.len() < 3 || height.so_small() {
+        return Err(Error::InvalidPrimitive);
+    }
+    let vertices = polygon.iter().map(|p| vertex(*p)).collect::<Vec<_>>();
+    let wire = (0..vertices.len())
+        .map(|i| line(&vertices[i], &vertices[(i + 1) % vertices.len()]))
+        .collect::<Wire>();
+    let face = try_attach_plane(&[wire])?;
+    let Surface::Plane(plane) = face.oriented_surface() else {
+        return Err(Error::WireNotInOnePlane);
+    };
+    let face = match height > 0.0 {
+        true => face,
+        false => face.inverse(),
+    };
+    Ok(tsweep(&face, plane.normal() * height))
+}
+
 /// Try attatiching a plane whose boundary is `wire`.
 /// # Examples
 /// ```
@@ -757,3 +1605,380 @@ mod partial_torus {
         assert!(torus.is_geometric_consistent());
     }
 }
+
+#[cfg(test)]
+mod loft {
+    use super::*;
+
+    fn circle(origin: Point3, axis: Vector3, radius: Vector3) -> Wire {
+        rsweep(&vertex(origin + radius), origin, axis, Rad(7.0))
+    }
+
+    #[test]
+    fn interpolate_sections() {
+        let wires = [
+            circle(Point3::origin(), Vector3::unit_z(), Vector3::unit_x()),
+            circle(
+                Point3::new(0.5, 0.0, 1.0),
+                Vector3::unit_z(),
+                Vector3::unit_x() * 0.5,
+            ),
+            circle(
+                Point3::new(0.0, 0.0, 2.0),
+                Vector3::unit_z(),
+                Vector3::unit_x() * 2.0,
+            ),
+            circle(
+                Point3::new(0.0, 0.0, 3.0),
+                Vector3::unit_z(),
+                Vector3::unit_x(),
+            ),
+        ];
+        let solid = loft_solid(&wires, LoftOptions::default()).unwrap();
+        let shell = &solid.boundaries()[0];
+        assert_eq!(shell.len(), 5);
+        assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+        assert!(shell.is_geometric_consistent());
+        // the middle sections are on the surfaces.
+        wires[1..3]
+            .iter()
+            .flat_map(Wire::edge_iter)
+            .for_each(|edge| {
+                let curve = edge.curve();
+                let (t0, t1) = curve.range_tuple();
+                (0..=4).for_each(|i| {
+                    let p = curve.subs(t0 + (t1 - t0) * i as f64 / 4.0);
+                    assert!(shell.iter().any(|face| {
+                        let surface = face.surface();
+                        surface
+                            .search_parameter(p, None, 100)
+                            .is_some_and(|(u, v)| surface.subs(u, v).near(&p))
+                    }));
+                });
+            });
+    }
+
+    #[test]
+    fn closed_loft() {
+        let wires = (0..4)
+            .map(|k| {
+                let angle = Rad(std::f64::consts::PI / 2.0 * k as f64);
+                let mat = Matrix4::from_angle_z(angle);
+                let origin = mat.transform_point(Point3::new(2.0, 0.0, 0.0));
+                let axis = mat.transform_vector(Vector3::unit_y());
+                circle(origin, axis, Vector3::new(0.0, 0.0, 0.5))
+            })
+            .collect::<Vec<_>>();
+        let options = LoftOptions {
+            closed: true,
+            ..Default::default()
+        };
+        let solid = loft_solid(&wires, options).unwrap();
+        let shell = &solid.boundaries()[0];
+        assert_eq!(shell.len(), 12);
+        assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+        assert!(shell.is_geometric_consistent());
+        // the surfaces before and after the first section are tangent.
+        let (face0, face1) = (&shell[0], &shell[3]);
+        let (surface0, surface1) = (face0.surface(), face1.surface());
+        (0..=4).for_each(|i| {
+            let u = i as f64 / 4.0;
+            assert_near!(surface0.subs(u, 0.0), surface1.subs(u, 1.0));
+            let (d0, d1) = (surface0.vder(u, 0.0), surface1.vder(u, 1.0));
+            assert!(d0.normalize().near(&d1.normalize()));
+        });
+    }
+
+    #[test]
+    fn ruled_loft() {
+        let wires = [
+            circle(Point3::origin(), Vector3::unit_z(), Vector3::unit_x()),
+            circle(
+                Point3::new(0.0, 0.0, 1.0),
+                Vector3::unit_z(),
+                Vector3::unit_x() * 2.0,
+            ),
+            circle(
+                Point3::new(0.0, 0.0, 2.0),
+                Vector3::unit_z(),
+                Vector3::unit_x(),
+            ),
+        ];
+        let options = LoftOptions {
+            ruled: true,
+            ..Default::default()
+        };
+        let shell = loft(&wires, options).unwrap();
+        assert_eq!(shell.len(), 6);
+        assert!(shell.is_geometric_consistent());
+        // the cones between the circles
+        shell.iter().for_each(|face| {
+            let surface = face.surface();
+            let p = surface.subs(0.3, 0.4);
+            let r = f64::sqrt(p.x * p.x + p.y * p.y);
+            assert_near!(r, 2.0 - f64::abs(p.z - 1.0));
+        });
+
+        // the loft of two sections is also ruled.
+        let shell = loft(&wires[..2], LoftOptions::default()).unwrap();
+        assert_eq!(shell.len(), 3);
+        let p = shell[0].surface().subs(0.3, 0.4);
+        assert_near!(f64::sqrt(p.x * p.x + p.y * p.y), 1.0 + p.z);
+    }
+}
+
+#[cfg(test)]
+mod path_sweep {
+    use super::*;
+
+    fn polyline(points: &[Point3]) -> Wire {
+        let v = points.iter().map(|p| vertex(*p)).collect::<Vec<_>>();
+        v.windows(2).map(|v| line(&v[0], &v[1])).collect()
+    }
+
+    fn square(half: f64) -> Face {
+        let v = [(-half, -half), (half, -half), (half, half), (-half, half)]
+            .map(|(y, z)| vertex(Point3::new(0.0, y, z)));
+        let wire: Wire = (0..4).map(|i| line(&v[i], &v[(i + 1) % 4])).collect();
+        try_attach_plane(&[wire]).unwrap()
+    }
+
+    fn sample_points(shell: &Shell) -> Vec<Point3> {
+        shell
+            .iter()
+            .flat_map(|face| {
+                let surface = face.surface();
+                (0..=4).flat_map(move |i| {
+                    let surface = surface.clone();
+                    (0..=4).map(move |j| surface.subs(i as f64 / 4.0, j as f64 / 4.0))
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn straight_path() {
+        let path = polyline(&[Point3::origin(), Point3::new(2.0, 0.0, 0.0)]);
+        let solid = path_sweep(&square(0.5), &path, SweepFrame::Frenet).unwrap();
+        let shell = &solid.boundaries()[0];
+        assert_eq!(shell.len(), 6);
+        assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+        assert!(shell.is_geometric_consistent());
+        sample_points(shell).into_iter().for_each(|p| {
+            assert!(
+                f64::abs(p.y).near(&0.5)
+                    || f64::abs(p.z).near(&0.5)
+                    || p.x.so_small()
+                    || p.x.near(&2.0)
+            );
+        });
+    }
+
+    #[test]
+    fn closed_path() {
+        let v = vertex(Point3::new(1.2, 0.0, 0.0));
+        let profile = try_attach_plane(&[rsweep(
+            &v,
+            Point3::new(1.0, 0.0, 0.0),
+            Vector3::unit_y(),
+            Rad(7.0),
+        )])
+        .unwrap();
+        let path = rsweep(
+            &vertex(Point3::new(1.0, 0.0, 0.0)),
+            Point3::origin(),
+            Vector3::unit_z(),
+            Rad(7.0),
+        );
+        for frame in [
+            SweepFrame::Frenet,
+            SweepFrame::RotationMinimizing,
+            SweepFrame::Fixed(Vector3::unit_z()),
+        ] {
+            let torus = path_sweep(&profile, &path, frame).unwrap();
+            let shell = &torus.boundaries()[0];
+            assert_eq!(shell.len(), 9);
+            assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+            sample_points(shell).into_iter().for_each(|p| {
+                let r = f64::sqrt(p.x * p.x + p.y * p.y) - 1.0;
+                assert!(f64::abs(r * r + p.z * p.z - 0.04) < 1.0e-4);
+            });
+        }
+    }
+
+    #[test]
+    fn fixed_frame() {
+        let (v0, v1) = (vertex(Point3::origin()), vertex(Point3::new(2.0, 2.0, 0.0)));
+        let path: Wire = vec![circle_arc(
+            &v0,
+            &v1,
+            Point3::new(2.0 - f64::sqrt(2.0), f64::sqrt(2.0), 0.0),
+        )]
+        .into();
+        let profile = polyline(&[Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 1.0)]);
+        let shell = path_sweep(&profile, &path, SweepFrame::Fixed(Vector3::unit_z())).unwrap();
+        assert_eq!(shell.len(), 1);
+        // the profile stands upright along the path.
+        sample_points(&shell).into_iter().for_each(|p| {
+            let (x, y) = (p.x - 2.0, p.y);
+            assert!(f64::abs(x * x + y * y - 4.0) < 1.0e-4);
+        });
+        shell.vertex_iter().for_each(|v| {
+            assert!(v.point().z.so_small() || v.point().z.near(&1.0));
+        });
+    }
+
+    #[test]
+    fn mitered_corner() {
+        let path = polyline(&[
+            Point3::origin(),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+        ]);
+        let solid = path_sweep(&square(0.1), &path, SweepFrame::RotationMinimizing).unwrap();
+        let shell = &solid.boundaries()[0];
+        assert_eq!(shell.len(), 10);
+        assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+        assert!(shell.is_geometric_consistent());
+        let corner = shell
+            .vertex_iter()
+            .map(|v| v.point())
+            .filter(|p| !p.x.so_small() && !p.y.near(&1.0))
+            .collect::<Vec<_>>();
+        assert!(!corner.is_empty());
+        corner
+            .into_iter()
+            .for_each(|p| assert_near!(p.x - 1.0 + p.y, 0.0));
+    }
+
+    #[test]
+    fn path_sweep_errors() {
+        let path = polyline(&[Point3::origin(), Point3::new(0.0, 0.0, 1.0)]);
+        assert_eq!(
+            path_sweep(&square(0.1), &path, SweepFrame::Fixed(Vector3::unit_z())).unwrap_err(),
+            Error::InvalidSweepPath,
+        );
+        let path = polyline(&[
+            Point3::origin(),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::origin(),
+        ]);
+        assert_eq!(
+            path_sweep(&square(0.1), &path, SweepFrame::Frenet).unwrap_err(),
+            Error::InvalidSweepPath,
+        );
+    }
+}
+
+#[cfg(test)]
+mod primitives {
+    use super::*;
+
+    /// Checks that the normals at the middle points of the edges look away from `inner(point)`.
+    fn test_outward(solid: &Solid, inner: impl Fn(Point3) -> Point3) {
+        assert!(solid.is_geometric_consistent());
+        let shell = &solid.boundaries()[0];
+        assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+        shell.face_iter().for_each(|face| {
+            let surface = face.oriented_surface();
+            face.boundaries()[0].edge_iter().for_each(|edge| {
+                let curve = edge.curve();
+                let (t0, t1) = curve.range_tuple();
+                let p = curve.subs((t0 + t1) / 2.0);
+                let (u, v) = surface.search_parameter(p, None, 100).unwrap();
+                let n = surface.normal(u, v);
+                assert!(n.dot(p - inner(p)) > 0.0, "{p:?} {n:?}");
+            })
+        })
+    }
+
+    #[test]
+    fn convex_primitives() {
+        let center = Point3::new(1.0, 2.0, 3.0);
+        let axis = Vector3::new(1.0, -1.0, 2.0);
+        let solids = [
+            cuboid(Point3::new(2.0, 3.0, 4.0), Point3::new(0.0, 1.0, 2.0)).unwrap(),
+            cylinder(center - axis, axis, 1.0, 2.0 * axis.magnitude()).unwrap(),
+            cone_solid(center - axis, axis, 1.0, 0.0, 2.0 * axis.magnitude()).unwrap(),
+            cone_solid(center - axis, axis, 0.0, 1.0, 2.0 * axis.magnitude()).unwrap(),
+            cone_solid(center - axis, axis, 1.5, 0.5, 2.0 * axis.magnitude()).unwrap(),
+            sphere(center, 1.5).unwrap(),
+            wedge(Point3::new(0.0, 1.0, 2.0), Vector3::new(2.0, 2.0, 2.0), 1.0).unwrap(),
+            prism(
+                &[
+                    Point3::new(0.0, 1.0, 2.0),
+                    Point3::new(2.0, 1.0, 2.0),
+                    Point3::new(2.0, 3.0, 2.0),
+                    Point3::new(0.0, 3.0, 2.0),
+                ],
+                2.0,
+            )
+            .unwrap(),
+            prism(
+                &[
+                    Point3::new(0.0, 1.0, 4.0),
+                    Point3::new(2.0, 1.0, 4.0),
+                    Point3::new(1.0, 3.0, 4.0),
+                ],
+                -2.0,
+            )
+            .unwrap(),
+        ];
+        solids
+            .iter()
+            .for_each(|solid| test_outward(solid, |_| center));
+    }
+
+    #[test]
+    fn torus_primitive() {
+        let center = Point3::new(1.0, 2.0, 3.0);
+        let axis = Vector3::new(1.0, -1.0, 2.0).normalize();
+        let torus = torus(center, axis, 2.0, 0.5).unwrap();
+        test_outward(&torus, |p| {
+            let vec = p - center;
+            let radial = vec - axis * vec.dot(axis);
+            center + radial.normalize() * 2.0
+        });
+    }
+
+    #[test]
+    fn invalid_primitives() {
+        let (origin, axis) = (Point3::origin(), Vector3::unit_z());
+        let flat = Point3::new(1.0, 1.0, 0.0);
+        let results = [
+            cuboid(origin, flat),
+            cylinder(origin, axis, 0.0, 1.0),
+            cylinder(origin, axis, 1.0, -1.0),
+            cylinder(origin, Vector3::zero(), 1.0, 1.0),
+            cone_solid(origin, axis, 0.0, 0.0, 1.0),
+            cone_solid(origin, axis, -1.0, 1.0, 1.0),
+            cone_solid(origin, axis, 1.0, 0.5, 0.0),
+            sphere(origin, 0.0),
+            sphere(origin, -1.0),
+            torus(origin, axis, 1.0, 0.0),
+            torus(origin, axis, 1.0, 1.0),
+            torus(origin, axis, 0.5, 1.0),
+            torus(origin, Vector3::zero(), 2.0, 1.0),
+        ];
+        results
+            .into_iter()
+            .for_each(|res| assert_eq!(res.unwrap_err(), Error::InvalidPrimitive));
+
+        let size = Vector3::new(1.0, 1.0, 1.0);
+        let invalid = Error::InvalidPrimitive;
+        assert_eq!(wedge(origin, size, 2.0).unwrap_err(), invalid);
+        assert_eq!(wedge(origin, size, -0.5).unwrap_err(), invalid);
+        assert_eq!(wedge(origin, -size, 0.5).unwrap_err(), invalid);
+        let polygon = [origin, Point3::new(1.0, 0.0, 0.0)];
+        assert_eq!(prism(&polygon, 1.0).unwrap_err(), invalid);
+        let polygon = [origin, Point3::new(1.0, 0.0, 0.0), flat];
+        assert_eq!(prism(&polygon, 0.0).unwrap_err(), invalid);
+        let polygon = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(0.0, 1.0, 1.0),
+        ];
+        assert_eq!(prism(&polygon, 1.0).unwrap_err(), Error::WireNotInOnePlane);
+    }
+}
@@ -14,6 +14,22 @@ pub enum Error {
     /// cf. [`builder::try_wire_homotopy`](../builder/fn.try_wire_homotopy.html)
     #[error("The wires must contain the same number of edges to create a homotopy.")]
     NotSameNumberOfEdges,
+    /// tried to loft less than two wires, two coincident adjacent wires, or intersection curves.
+    /// cf. [`builder::loft`](../builder/fn.loft.html)
+    #[error("The loft needs at least two distinct adjacent wires without intersection curves.")]
+    InvalidLoftSections,
+    /// tried to sweep along a path on which the frames of the profile are not defined.
+    /// cf. [`builder::path_sweep`](../builder/fn.path_sweep.html)
+    #[error("The sweep path must be a continuous wire on which the frames are defined.")]
+    InvalidSweepPath,
+    /// tried to create a thread on a face that is not a part of a cylinder, or with a non-positive pitch.
+    /// cf. [`builder::thread`](../builder/fn.thread.html)
+    #[error("The thread must be on a cylindrical face with a positive pitch.")]
+    InvalidThread,
+    /// tried to create a primitive solid with non-positive sizes or degenerate parameters.
+    /// cf. [`builder::cuboid`](../builder/fn.cuboid.html), [`builder::torus`](../builder/fn.torus.html)
+    #[error("The sizes of the primitive solid must be positive and must not make it degenerate.")]
+    InvalidPrimitive,
 }
 
 #[test]
@@ -1,4 +1,4 @@
-use crate::*;
+use crate::{builder::SweepFrame, *};
 use std::f64::consts::PI;
 
 pub(super) fn circle_arc_by_three_points(
@@ -63,7 +63,7 @@ fn closed_polyline_orientation<'a>(pts: impl IntoIterator<Item = &'a Vec<Point3>
         >= 0.0
 }
 
-fn take_one_axis_by_normal(n: Vector3) -> Vector3 {
+pub(super) fn take_one_axis_by_normal(n: Vector3) -> Vector3 {
     let a = n.map(f64::abs);
     if a.x > a.z || a.y > a.z {
         Vector3::new(-n.y, n.x, 0.0).normalize()
@@ -111,6 +111,262 @@ pub(super) fn attach_plane(mut pts: Vec<Vec<Point3>>) -> Option<Plane> {
     Some(plane)
 }
 
+/// The parameters of the sections of loft by the mean distances between the adjacent sections.
+pub(super) fn loft_parameters(samples: &[Vec<Point3>]) -> Option<Vec<f64>> {
+    let mut params = vec![0.0];
+    for pair in samples.windows(2) {
+        let dist = pair[0]
+            .iter()
+            .zip(&pair[1])
+            .map(|(p, q)| p.distance(*q))
+            .sum::<f64>()
+            / pair[0].len() as f64;
+        if dist.so_small() {
+            return None;
+        }
+        params.push(params.last().unwrap() + dist);
+    }
+    let length = *params.last().unwrap();
+    params.iter_mut().for_each(|t| *t /= length);
+    Some(params)
+}
+
+/// The knot vector for interpolation at `params`, by the averages of the parameters.
+pub(super) fn averaging_knot_vec(params: &[f64], degree: usize) -> KnotVec {
+    let n = params.len() - 1;
+    let inner = (1..=n - degree).map(|j| params[j..j + degree].iter().sum::<f64>() / degree as f64);
+    let knots = std::iter::repeat_n(0.0, degree + 1)
+        .chain(inner)
+        .chain(std::iter::repeat_n(1.0, degree + 1))
+        .collect::<Vec<_>>();
+    KnotVec::from(knots)
+}
+
+/// The part of `curve` in the range `(t0, t1)`, with the normalized knot vector.
+pub(super) fn sub_curve(
+    curve: &BSplineCurve<Vector4>,
+    (t0, t1): (f64, f64),
+) -> BSplineCurve<Vector4> {
+    let mut curve = curve.clone();
+    let (s0, s1) = curve.range_tuple();
+    if t1 < s1 - TOLERANCE {
+        curve.cut(t1);
+    }
+    if t0 > s0 + TOLERANCE {
+        curve = curve.cut(t0);
+    }
+    curve.knot_normalize();
+    curve
+}
+
+/// The cubic B-spline curve interpolating the helix around `axis` from `origin + radial` in the range of turns.
+pub(super) fn helix_curve(
+    origin: Point3,
+    axis: Vector3,
+    radial: Vector3,
+    pitch: f64,
+    (t0, t1): (f64, f64),
+) -> BSplineCurve<Point3> {
+    const DIVISION: usize = 16;
+    let params = (0..=DIVISION)
+        .map(|i| i as f64 / DIVISION as f64)
+        .collect::<Vec<_>>();
+    let parameter_points = params
+        .iter()
+        .map(|&s| {
+            let t = t0 + (t1 - t0) * s;
+            let (sin, cos) = f64::sin_cos(2.0 * PI * t);
+            let point = origin + radial * cos + axis.cross(radial) * sin + axis * (pitch * t);
+            (s, point)
+        })
+        .collect::<Vec<_>>();
+    BSplineCurve::interpole(averaging_knot_vec(&params, 3), parameter_points)
+}
+
+/// Returns a point on the axis and the unit axis of the cylinder through the points with the normals.
+pub(super) fn fit_cylinder(samples: &[(Point3, Vector3)]) -> Option<(Point3, Vector3)> {
+    let (p0, n0) = *samples.first()?;
+    let (pj, nj) = *samples.iter().max_by(|(_, n), (_, m)| {
+        f64::total_cmp(&n0.cross(*n).magnitude2(), &n0.cross(*m).magnitude2())
+    })?;
+    let axis = n0.cross(nj);
+    if axis.magnitude() < 1.0e-3 {
+        return None;
+    }
+    let axis = axis.normalize();
+    // the closest point on the normal line of `p0` to that of `pj`
+    let w = p0 - pj;
+    let (b, d, e) = (n0.dot(nj), n0.dot(w), nj.dot(w));
+    let t = (b * e - d) / (1.0 - b * b);
+    let origin = p0 + n0 * t;
+    let radius = f64::abs(t);
+    let on_cylinder = samples.iter().all(|&(p, n)| {
+        let radial = (p - origin) - axis * (p - origin).dot(axis);
+        n.dot(axis).so_small()
+            && f64::abs(radial.magnitude() - radius) < TOLERANCE * radius.max(1.0)
+    });
+    on_cylinder.then_some((origin, axis))
+}
+
+fn perpendicular(tangent: Vector3, vec: Vector3) -> Option<Vector3> {
+    let vec = vec - tangent * vec.dot(tangent);
+    (!vec.so_small()).then(|| vec.normalize())
+}
+
+fn any_perpendicular(tangent: Vector3) -> Vector3 {
+    perpendicular(tangent, Vector3::unit_x())
+        .or_else(|| perpendicular(tangent, Vector3::unit_y()))
+        .unwrap()
+}
+
+/// The normal at the next station transported by the double reflection.
+fn rotation_minimizing(
+    (p0, t0, r0): (Point3, Vector3, Vector3),
+    (p1, t1): (Point3, Vector3),
+) -> Vector3 {
+    let reflect = |v: Vector3, x: Vector3| x - v * (2.0 * v.dot(x) / v.dot(v));
+    // At a corner, the first reflection is by the tangent, so that the two reflections are a rotation.
+    let v1 = match (p1 - p0).so_small() {
+        true => t0,
+        false => p1 - p0,
+    };
+    let (r, t) = (reflect(v1, r0), reflect(v1, t0));
+    let v2 = t1 - t;
+    let r = match v2.so_small() {
+        true => r,
+        false => reflect(v2, r),
+    };
+    perpendicular(t1, r).unwrap_or_else(|| any_perpendicular(t1))
+}
+
+/// The transformations of the profile at the stations along `path`, for each edge.
+/// The first station of each edge is omitted, since it is the last one of the previous edge.
+/// At the corners of the path, the sections are projected to the bisector planes.
+pub(super) fn path_transforms(path: &Wire, frame: SweepFrame) -> Option<Vec<Vec<Matrix4>>> {
+    const DIVISION_TOLERANCE: f64 = 1.0e-3;
+    if path.is_empty() || !path.is_continuous() {
+        return None;
+    }
+    // the points, the tangents, and the curvature directions at the stations
+    let stations = path
+        .edge_iter()
+        .map(|edge| {
+            let curve = edge.oriented_curve();
+            let range = curve.range_tuple();
+            let params = match curve {
+                Curve::Line(_) => vec![range.0, range.1],
+                _ => curve.parameter_division(range, DIVISION_TOLERANCE).0,
+            };
+            params
+                .into_iter()
+                .map(|t| {
+                    let (der, der2) = (curve.der(t), curve.der2(t));
+                    let tangent = (!der.so_small()).then(|| der.normalize())?;
+                    Some((curve.subs(t), tangent, der2 - tangent * der2.dot(tangent)))
+                })
+                .collect::<Option<Vec<_>>>()
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let flat = stations.iter().flatten().copied().collect::<Vec<_>>();
+    let mut normals = Vec::<Vector3>::with_capacity(flat.len());
+    match frame {
+        SweepFrame::Fixed(vec) => flat.iter().try_for_each(|&(_, t, _)| {
+            normals.push(perpendicular(t, vec)?);
+            Some(())
+        })?,
+        SweepFrame::Frenet => {
+            let frenet = |&(_, t, c): &(Point3, Vector3, Vector3)| perpendicular(t, c);
+            let first = flat.iter().find_map(frenet);
+            flat.iter().enumerate().for_each(|(i, station)| {
+                let normal = match (frenet(station), first, i) {
+                    (Some(normal), _, _) => normal,
+                    (None, Some(first), 0) => perpendicular(station.1, first)
+                        .unwrap_or_else(|| any_perpendicular(station.1)),
+                    (None, None, 0) => any_perpendicular(station.1),
+                    (None, _, _) => {
+                        let (p, t, _) = flat[i - 1];
+                        rotation_minimizing((p, t, normals[i - 1]), (station.0, station.1))
+                    }
+                };
+                normals.push(normal);
+            });
+        }
+        SweepFrame::RotationMinimizing => {
+            normals.push(any_perpendicular(flat[0].1));
+            flat.windows(2).enumerate().for_each(|(i, pair)| {
+                let ((p0, t0, _), (p1, t1, _)) = (pair[0], pair[1]);
+                normals.push(rotation_minimizing((p0, t0, normals[i]), (p1, t1)));
+            });
+            // distribute the twist of the closed path along the length
+            let (t0, t1) = (flat[0].1, flat[flat.len() - 1].1);
+            if path.is_closed() && t0.near(&t1) {
+                let (r0, r1) = (normals[0], normals[normals.len() - 1]);
+                let angle = f64::atan2(r1.cross(r0).dot(t0), r1.dot(r0));
+                let mut lengths = vec![0.0];
+                flat.windows(2).for_each(|pair| {
+                    let length = lengths.last().unwrap() + pair[0].0.distance(pair[1].0);
+                    lengths.push(length);
+                });
+                let total = *lengths.last().unwrap();
+                normals
+                    .iter_mut()
+                    .zip(&flat)
+                    .zip(lengths)
+                    .for_each(|((r, &(_, t, _)), s)| {
+                        *r = Matrix3::from_axis_angle(t, Rad(angle * s / total)) * *r;
+                    });
+            }
+        }
+    }
+
+    let frame_matrix = |(p, t, _): (Point3, Vector3, Vector3), r: Vector3| {
+        Matrix4::from_cols(
+            r.extend(0.0),
+            t.cross(r).extend(0.0),
+            t.extend(0.0),
+            p.to_homogeneous(),
+        )
+    };
+    let inverse = frame_matrix(flat[0], normals[0]).invert()?;
+    let mut normals = normals.into_iter();
+    let mut transforms = stations
+        .iter()
+        .map(|stations| {
+            stations
+                .iter()
+                .zip(&mut normals)
+                .map(|(station, r)| frame_matrix(*station, r) * inverse)
+                .skip(1)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    stations
+        .windows(2)
+        .zip(&mut transforms)
+        .try_for_each(|(pair, transforms)| {
+            let (p, t0, _) = pair[0][pair[0].len() - 1];
+            let t1 = pair[1][0].1;
+            if t0.near(&t1) {
+                return Some(());
+            }
+            // the projection along the incoming tangent to the bisector plane
+            let n = t0 + t1;
+            if n.so_small() {
+                return None;
+            }
+            let n = n.normalize();
+            let d = t0.dot(n);
+            let linear = Matrix3::identity() - Matrix3::from_cols(t0 * n.x, t0 * n.y, t0 * n.z) / d;
+            let projection = Matrix4::from_translation(p.to_vec())
+                * Matrix4::from(linear)
+                * Matrix4::from_translation(-p.to_vec());
+            let last = transforms.last_mut()?;
+            *last = projection * *last;
+            Some(())
+        })?;
+    Some(transforms)
+}
+
 #[cfg(test)]
 mod test_geom_impl {
     use super::*;
@@ -181,7 +181,19 @@ impl IncludeCurve<Curve> for Surface {
                 Curve::IntersectionCurve(_) => unimplemented!(),
             },
             Surface::RevolutedCurve(surface) => match surface.entity_curve() {
-                Curve::Line(curve) => self.include(&Curve::BSplineCurve(curve.to_bspline())),
+                Curve::Line(entity_curve) => {
+                    let surface = RevolutedCurve::by_revolution(
+                        entity_curve.to_bspline(),
+                        surface.origin(),
+                        surface.axis(),
+                    );
+                    match curve {
+                        Curve::Line(curve) => surface.include(&curve.to_bspline()),
+                        Curve::BSplineCurve(curve) => surface.include(curve),
+                        Curve::NurbsCurve(curve) => surface.include(curve),
+                        Curve::IntersectionCurve(_) => unimplemented!(),
+                    }
+                }
                 Curve::BSplineCurve(entity_curve) => {
                     let surface = RevolutedCurve::by_revolution(
                         entity_curve,
@@ -241,3 +253,21 @@ impl SearchNearestParameter<D2> for Surface {
         }
     }
 }
+
+#[test]
+fn include_in_revoluted_line() {
+    use crate::builder;
+    // the side of the cylinder, the revolution of the line parallel to the axis
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let line = builder::tsweep(&v, Vector3::unit_z());
+    let shell: Shell = builder::rsweep(&line, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let face = &shell[0];
+    let Surface::RevolutedCurve(_) = face.surface() else {
+        panic!("the surface is not a revolution.");
+    };
+    let surface = face.surface();
+    face.edge_iter()
+        .for_each(|edge| assert!(surface.include(&edge.curve())));
+    let line = Curve::Line(Line(Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 0.0, 1.0)));
+    assert!(!surface.include(&line));
+}
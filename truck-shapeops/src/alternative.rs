@@ -180,3 +180,11 @@ where
         }
     }
 }
+
+impl<S0, S1> crate::AnalyticSurface for Alternative<S0, S1>
+where
+    S0: crate::AnalyticSurface,
+    S1: crate::AnalyticSurface,
+{
+    derive_method!(elementary_surface, Option<crate::ElementarySurface>,);
+}
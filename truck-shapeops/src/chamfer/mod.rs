@@ -0,0 +1,483 @@
+use crate::{
+    classify_point, difference,
+    errors::Error,
+    fillet::geometry::curve_to_nurbs,
+    offset::geometry::{
+        approximate_curve, common_point, normal, search_parameter, similar_curve,
+        TANGENT_ANGLE_TOLERANCE,
+    },
+    PointClassification, Result,
+};
+use itertools::Itertools;
+use std::f64::consts::PI;
+use truck_modeling::*;
+
+// The number of the divisions of each edge at which the corners of the cutting tool are sampled.
+const SAMPLE_DIVISION: usize = 4;
+// The cutting tools are wider than the chamfers by this ratio of the widths of the chamfers on each side.
+const CUTTER_MARGIN: f64 = 0.5;
+// The tolerance of the boolean operations and of the approximations, as the ratio to the widths of the chamfers.
+const TOLERANCE_RATIO: f64 = 0.01;
+// The limit of the iterations searching the points at the distances of the chamfer.
+const SEARCH_TRIALS: usize = 10;
+
+/// The shape of chamfers: the distances from the edges on the adjacent faces, or the angle between the chamfer and
+/// the first face.
+///
+/// The first face is the first one adjacent to the first edge of each chain of chamfered edges in the faces of the
+/// solid, and the faces on the same side of the chain are the first faces of the other edges.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Chamfer {
+    /// the same distance on the both faces
+    Symmetric(f64),
+    /// the distances on the first and the second faces
+    TwoDistances(f64, f64),
+    /// the distance on the first face, and the angle between the chamfer and the first face
+    DistanceAngle(f64, Rad<f64>),
+}
+
+impl Chamfer {
+    fn is_valid(self) -> bool {
+        match self {
+            Chamfer::Symmetric(d) => d > TOLERANCE,
+            Chamfer::TwoDistances(d0, d1) => d0 > TOLERANCE && d1 > TOLERANCE,
+            Chamfer::DistanceAngle(d, angle) => d > TOLERANCE && 0.0 < angle.0 && angle.0 < PI,
+        }
+    }
+
+    // The distances on the first and the second faces, which make the angle `angle`.
+    fn distances(self, angle: Rad<f64>) -> Option<(f64, f64)> {
+        match self {
+            Chamfer::Symmetric(d) => Some((d, d)),
+            Chamfer::TwoDistances(d0, d1) => Some((d0, d1)),
+            // the law of sines in the triangle of the edge and the both ends of the chamfer.
+            Chamfer::DistanceAngle(d, alpha) => {
+                let sin = f64::sin(alpha.0 + angle.0);
+                (sin > TOLERANCE).then(|| (d, d * f64::sin(alpha.0) / sin))
+            }
+        }
+    }
+}
+
+/// Chamfers the edges of the solid.
+///
+/// The edges which are tangentially connected each other, with the tangent faces on the both sides, are chamfered at
+/// once along the chain of edges. The chamfer of each chain is the ruled surface between the curves on the adjacent
+/// faces at the distances from the edges, which is cut out of the solid with the tool extended beyond the ends of the
+/// chain. The faces at the ends are trimmed by the chamfer, and the chamfers meeting at a vertex are trimmed by each
+/// other. The curves on the planes and the surfaces of revolution around the edges are the similar copies of the
+/// edges, and the others are approximated by B-spline curves.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_shapeops::{chamfer_edges, Chamfer};
+///
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let edge = cube.edge_iter().next().unwrap();
+/// let chamfered = chamfer_edges(&cube, &[edge.id()], Chamfer::Symmetric(0.2)).unwrap();
+/// assert_eq!(chamfered.face_iter().count(), 7);
+/// ```
+///
+/// # Errors
+/// - If the distances are not positive, or the angle is not in `(0, π)`, returns [`Error::InvalidChamfer`].
+/// - If one of `edges` is not contained in `solid`, returns [`Error::EdgeNotFound`].
+/// - If an edge is not convex, the chamfer does not fit on the faces, the tool beyond the ends of a chain cuts the
+///   solid, or the boolean operations fail, returns [`Error::ChamferFailed`], whose source is the error of the
+///   failed boolean operation.
+pub fn chamfer_edges(solid: &Solid, edges: &[EdgeID], chamfer: Chamfer) -> Result<Solid> {
+    if !chamfer.is_valid() {
+        return Err(Error::InvalidChamfer);
+    }
+    let contained = |id: &EdgeID| solid.edge_iter().any(|edge| edge.id() == *id);
+    if !edges.iter().all(contained) {
+        return Err(Error::EdgeNotFound);
+    }
+    let cutters = solid
+        .boundaries()
+        .iter()
+        .map(|shell| chains(shell, edges))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::ChamferFailed(None))?
+        .into_iter()
+        .flatten()
+        .map(|chain| cutter(solid, &chain, chamfer))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::ChamferFailed(None))?;
+    let tol = cutters
+        .iter()
+        .map(|(_, tol)| *tol)
+        .fold(f64::INFINITY, f64::min);
+    cutters
+        .iter()
+        .try_fold(solid.clone(), |solid, (cutter, _)| {
+            difference(&solid, cutter, tol).map_err(|e| Error::ChamferFailed(Some(e)))
+        })
+}
+
+/// A chamfered edge oriented along its chain, with the adjacent faces. The first face contains the edge with the
+/// same orientation, and the second face contains the inverse.
+#[derive(Clone, Debug)]
+struct ChainEdge {
+    edge: Edge,
+    faces: [Face; 2],
+}
+
+impl ChainEdge {
+    fn inverse(&self) -> Self {
+        let [face0, face1] = self.faces.clone();
+        Self {
+            edge: self.edge.inverse(),
+            faces: [face1, face0],
+        }
+    }
+
+    fn tangent(&self, t: f64) -> Vector3 { self.edge.oriented_curve().der(t).normalize() }
+
+    // Whether `next` follows `self` tangentially, with the tangent faces on the both sides.
+    fn connected(&self, next: &ChainEdge) -> bool {
+        if self.edge.back() != next.edge.front() {
+            return false;
+        }
+        let (t0, t1) = (
+            self.edge.oriented_curve().range_tuple(),
+            next.edge.oriented_curve().range_tuple(),
+        );
+        let point = next.edge.front().point();
+        let tangent = |a: Vector3, b: Vector3| a.angle(b) < Rad(TANGENT_ANGLE_TOLERANCE);
+        let normals = (0..2).all(|i| {
+            let n0 = face_normal(&self.faces[i], point);
+            let n1 = face_normal(&next.faces[i], point);
+            matches!((n0, n1), (Some(n0), Some(n1)) if tangent(n0, n1))
+        });
+        tangent(self.tangent(t0.1), next.tangent(t1.0)) && normals
+    }
+}
+
+/// The chains of the edges of `shell` in `edges`. Returns `None` if an edge is not shared by two faces.
+fn chains(shell: &Shell, edges: &[EdgeID]) -> Option<Vec<Vec<ChainEdge>>> {
+    let mut remaining = Vec::new();
+    for id in edges.iter().unique() {
+        let adjacent = shell
+            .face_iter()
+            .filter_map(|face| Some((face, face.edge_iter().find(|edge| edge.id() == *id)?)))
+            .collect::<Vec<_>>();
+        match adjacent.as_slice() {
+            [] => continue,
+            [(face0, edge), (face1, _)] => remaining.push(ChainEdge {
+                edge: edge.clone(),
+                faces: [(*face0).clone(), (*face1).clone()],
+            }),
+            _ => return None,
+        }
+    }
+    // takes the edge satisfying `pred` in either orientation out of `remaining`.
+    let take = |remaining: &mut Vec<ChainEdge>, pred: &dyn Fn(&ChainEdge) -> bool| {
+        remaining
+            .iter()
+            .enumerate()
+            .find_map(|(i, edge)| {
+                let found = [edge.clone(), edge.inverse()].into_iter().find(pred)?;
+                Some((i, found))
+            })
+            .map(|(i, found)| {
+                remaining.remove(i);
+                found
+            })
+    };
+    let mut chains = Vec::new();
+    while !remaining.is_empty() {
+        let mut chain = vec![remaining.remove(0)];
+        let closed =
+            |chain: &Vec<ChainEdge>| chain[0].edge.front() == chain[chain.len() - 1].edge.back();
+        while !closed(&chain) {
+            let back = chain[chain.len() - 1].clone();
+            match take(&mut remaining, &|edge| back.connected(edge)) {
+                Some(edge) => chain.push(edge),
+                None => break,
+            }
+        }
+        while !closed(&chain) {
+            let front = chain[0].clone();
+            match take(&mut remaining, &|edge| edge.connected(&front)) {
+                Some(edge) => chain.insert(0, edge),
+                None => break,
+            }
+        }
+        chains.push(chain);
+    }
+    Some(chains)
+}
+
+/// The outward normal of the face at the point on the face.
+fn face_normal(face: &Face, point: Point3) -> Option<Vector3> {
+    let surface = face.oriented_surface();
+    let uv = search_parameter(&surface, point, None)?;
+    Some(normal(&surface, uv)?.normalize())
+}
+
+/// The point on the face at the distance `distance` from `origin` in the plane perpendicular to `tangent`, searched
+/// from `point`.
+fn point_on_face(
+    face: &Face,
+    origin: Point3,
+    tangent: Vector3,
+    mut point: Point3,
+    distance: f64,
+) -> Option<Point3> {
+    let surface = face.surface();
+    let mut hints = [None];
+    for _ in 0..SEARCH_TRIALS {
+        // the sphere around the origin is approximated by the tangent plane at the current point.
+        let dir = (point - origin).normalize();
+        let planes = [(origin, tangent), (origin + dir * distance, dir)];
+        let next = common_point(&[&surface], &planes, point, &mut hints, TOLERANCE)?;
+        if next.near(&point) {
+            return Some(next);
+        }
+        point = next;
+    }
+    None
+}
+
+/// The cross section of the chamfer at the parameter `t` of the edge: the point on the edge, and the points on the
+/// first and the second faces. Returns `None` if the edge is not convex.
+fn cross_section(chain_edge: &ChainEdge, t: f64, chamfer: Chamfer) -> Option<[Point3; 3]> {
+    let p = chain_edge.edge.oriented_curve().subs(t);
+    let tangent = chain_edge.tangent(t);
+    let n0 = face_normal(&chain_edge.faces[0], p)?;
+    let n1 = face_normal(&chain_edge.faces[1], p)?;
+    // the faces are on the left sides of the edges in their boundaries.
+    let (w0, w1) = (n0.cross(tangent).normalize(), tangent.cross(n1).normalize());
+    let (d0, d1) = chamfer.distances(w0.angle(w1))?;
+    let a = point_on_face(&chain_edge.faces[0], p, tangent, p + w0 * d0, d0)?;
+    let b = point_on_face(&chain_edge.faces[1], p, tangent, p + w1 * d1, d1)?;
+    // the chamfer of the convex edge is inside the solid.
+    ((a.midpoint(b) - p).dot(n0 + n1) < 0.0).then_some([p, a, b])
+}
+
+/// The corners of the cross section of the cutting tool, containing the triangle of the cross section of the chamfer.
+/// The side between the first two corners is on the chamfer, and the others are outside the solid.
+fn cutter_section([p, a, b]: [Point3; 3]) -> [Point3; 4] {
+    let margin = (b - a) * CUTTER_MARGIN;
+    let (a, b) = (a - margin, b + margin);
+    let w = (p - a.midpoint(b)) * 2.0;
+    [a, b, b + w, a + w]
+}
+
+/// The curves of the corners of the cutting tool along the edge. The curves are the similar copies of the edge if the
+/// samples of the corners are, and are approximated within `tol` otherwise.
+fn corner_curves(
+    chain_edge: &ChainEdge,
+    section: &dyn Fn(f64) -> Option<[Point3; 4]>,
+    tol: f64,
+) -> Option<[Curve; 4]> {
+    let curve = chain_edge.edge.oriented_curve();
+    let (t0, t1) = curve.range_tuple();
+    // the intersection curves are not transformed into the curves on the faces, but approximated.
+    let exact = !matches!(curve, Curve::IntersectionCurve(_));
+    let similar = |k: usize| {
+        let corner = |t: f64| Some(section(t)?[k]);
+        exact.then(|| similar_curve(&curve, corner, SAMPLE_DIVISION))?
+    };
+    let approximated = |k: usize| {
+        let corner = |t: f64| Some(section(t)?[k]);
+        approximate_curve(corner, (t0, t1), tol).map(Curve::BSplineCurve)
+    };
+    let curves = (0..4)
+        .map(similar)
+        .collect::<Option<Vec<_>>>()
+        .or_else(|| (0..4).map(approximated).collect())?;
+    curves.try_into().ok()
+}
+
+/// The ruled surface from `curve0` to `curve1`, which is the plane if the curves are the lines in a plane.
+fn ruled_surface(curve0: &Curve, curve1: &Curve) -> Surface {
+    if let (Curve::Line(line0), Curve::Line(line1)) = (curve0, curve1) {
+        let plane = Plane::new(line0.0, line0.1, line1.0);
+        let n = plane.normal();
+        if n.magnitude2().is_finite() && f64::abs(n.dot(line1.1 - line0.0)) < TOLERANCE {
+            return plane.into();
+        }
+    }
+    let (nurbs0, nurbs1) = (curve_to_nurbs(curve0), curve_to_nurbs(curve1));
+    let surface = BSplineSurface::homotopy(
+        nurbs0.non_rationalized().clone(),
+        nurbs1.non_rationalized().clone(),
+    );
+    NurbsSurface::new(surface).into()
+}
+
+/// Divides `curve` into `parts` pieces of the same parameter lengths.
+fn divide_curve(curve: &Curve, parts: usize) -> Vec<Curve> {
+    let mut rest = curve.clone();
+    let mut pieces = (1..parts)
+        .map(|i| {
+            // the range is taken at each cut, since the cut lines are reparameterized.
+            let (t0, t1) = rest.range_tuple();
+            let latter = rest.cut(t0 + (t1 - t0) / (parts - i + 1) as f64);
+            std::mem::replace(&mut rest, latter)
+        })
+        .collect::<Vec<_>>();
+    pieces.push(rest);
+    pieces
+}
+
+/// Concatenates the curves of each corner in turn. The collinear lines are concatenated into a line, and the others
+/// into a NURBS curve.
+fn concat_corners(curves: &[[Curve; 4]]) -> Option<[Curve; 4]> {
+    let concat = |k: usize| {
+        let lines = curves
+            .iter()
+            .map(|curves| match &curves[k] {
+                Curve::Line(line) => Some(*line),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(lines) = lines {
+            let line = Line(lines[0].0, lines[lines.len() - 1].1);
+            let dir = (line.1 - line.0).normalize();
+            let on_line = |pt: Point3| (pt - line.0).cross(dir).so_small();
+            if lines.iter().all(|Line(p, q)| on_line(*p) && on_line(*q)) {
+                return Some(Curve::Line(line));
+            }
+        }
+        let mut iter = curves.iter().map(|curves| curve_to_nurbs(&curves[k]));
+        let init = iter.next()?;
+        let nurbs = iter.try_fold(init, |mut nurbs, mut next| {
+            nurbs.syncro_degree(&mut next);
+            next.knot_translate(nurbs.range_tuple().1 - next.range_tuple().0);
+            nurbs.try_concat(&next).ok()
+        })?;
+        Some(Curve::NurbsCurve(nurbs))
+    };
+    (0..4)
+        .map(concat)
+        .collect::<Option<Vec<_>>>()?
+        .try_into()
+        .ok()
+}
+
+/// The cutting tool of the chain, the sweep of the cross section of the tool along the chain, with the tolerance of
+/// the approximations. The tool is extended along the tangents beyond the ends of the open chain, where it has to be
+/// outside `solid`.
+fn cutter(solid: &Solid, chain: &[ChainEdge], chamfer: Chamfer) -> Option<(Solid, f64)> {
+    let closed = chain[0].edge.front() == chain[chain.len() - 1].edge.back();
+    let range = |chain_edge: &ChainEdge| chain_edge.edge.oriented_curve().range_tuple();
+    let (t0, t1) = (range(&chain[0]).0, range(&chain[chain.len() - 1]).1);
+    let first = cross_section(&chain[0], t0, chamfer)?;
+    let tol = first[1].distance(first[2]) * TOLERANCE_RATIO;
+    // the corners are clockwise around the tangent, so that the normals of the sides are outward.
+    let [c0, c1, c2, _] = cutter_section(first);
+    let reverse = (c1 - c0).cross(c2 - c1).dot(chain[0].tangent(t0)) > 0.0;
+    let section = |chain_edge: &ChainEdge, t: f64| {
+        let mut corners = cutter_section(cross_section(chain_edge, t, chamfer)?);
+        if reverse {
+            corners.reverse();
+        }
+        Some(corners)
+    };
+
+    let pieces = chain
+        .iter()
+        .map(|chain_edge| corner_curves(chain_edge, &|t| section(chain_edge, t), tol))
+        .collect::<Option<Vec<_>>>()?;
+    // the stations, the ends of the segments, are not at the vertices of the chain, where the tool would touch the
+    // edges and the faces of the solid.
+    let segments = if closed {
+        // the loop is divided at the middles of the edges, or at the quarters of the only edge.
+        let parts = if chain.len() == 1 { 4 } else { 2 };
+        let mut divided = pieces
+            .iter()
+            .flat_map(|curves| {
+                let divided = curves.each_ref().map(|curve| divide_curve(curve, parts));
+                (0..parts).map(move |i| divided.each_ref().map(|pieces| pieces[i].clone()))
+            })
+            .collect::<Vec<_>>();
+        divided.rotate_left(1);
+        divided
+            .chunks(2)
+            .map(concat_corners)
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        let last = &pieces[pieces.len() - 1];
+        let length = 2.0 * last[0].back().distance(last[2].back());
+        let (tangent0, tangent1) = (chain[0].tangent(t0), chain[chain.len() - 1].tangent(t1));
+        let extension0 = pieces[0].each_ref().map(|curve| {
+            let pt = curve.front();
+            Curve::Line(Line(pt - tangent0 * length, pt))
+        });
+        let extension1 = last.each_ref().map(|curve| {
+            let pt = curve.back();
+            Curve::Line(Line(pt, pt + tangent1 * length))
+        });
+        // the tool beyond the ends of the chain is outside the solid.
+        let probe = |[p, a, b]: [Point3; 3], dir: Vector3| {
+            let centroid = Point3::centroid(&[p, a, b]) + dir * (length / 2.0);
+            let classified = classify_point(solid, centroid, tol);
+            matches!(classified, Some(PointClassification::Outside))
+        };
+        let last_section = cross_section(&chain[chain.len() - 1], t1, chamfer)?;
+        if !probe(first, -tangent0) || !probe(last_section, tangent1) {
+            return None;
+        }
+        let mut extended = vec![extension0];
+        extended.extend(pieces);
+        extended.push(extension1);
+        vec![concat_corners(&extended)?]
+    };
+
+    let mut stations = segments
+        .iter()
+        .map(|curves| curves.each_ref().map(|curve| curve.front()))
+        .collect::<Vec<_>>();
+    if !closed {
+        stations.push(segments[0].each_ref().map(|curve| curve.back()));
+    }
+    let vertices = stations
+        .iter()
+        .map(|corners| corners.map(builder::vertex))
+        .collect::<Vec<_>>();
+    let transverses = vertices
+        .iter()
+        .map(|v| [0, 1, 2, 3].map(|k| builder::line(&v[k], &v[(k + 1) % 4])))
+        .collect::<Vec<_>>();
+    let mut faces = segments
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, curves)| {
+            let j = (i + 1) % vertices.len();
+            let longitudes = [0, 1, 2, 3]
+                .map(|k| Edge::new(&vertices[i][k], &vertices[j][k], curves[k].clone()));
+            let (transverse0, transverse1) = (&transverses[i], &transverses[j]);
+            (0..4)
+                .map(|k| {
+                    let l = (k + 1) % 4;
+                    let wire = wire![
+                        longitudes[k].clone(),
+                        transverse1[k].clone(),
+                        longitudes[l].inverse(),
+                        transverse0[k].inverse(),
+                    ];
+                    Face::new(vec![wire], ruled_surface(&curves[k], &curves[l]))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    if !closed {
+        let cap = |transverse: &[Edge; 4]| transverse.iter().cloned().collect::<Wire>();
+        let front = cap(&transverses[0]);
+        let back = cap(&transverses[transverses.len() - 1]).inverse();
+        faces.push(builder::try_attach_plane(&[front]).ok()?);
+        faces.push(builder::try_attach_plane(&[back]).ok()?);
+    }
+    let solid = Solid::try_new(vec![faces.into()]).ok()?;
+    Some((solid, tol))
+}
+
+#[cfg(test)]
+mod tests;
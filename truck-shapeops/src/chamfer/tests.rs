@@ -0,0 +1,152 @@
+use super::{chamfer_edges, Chamfer};
+use crate::errors::Error;
+use crate::test_util::{cube, cylinder, find_edge, volume};
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+#[test]
+fn chamfer_one_edge() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(&cube, Point3::origin(), Point3::new(1.0, 0.0, 0.0));
+    let chamfered = chamfer_edges(&cube, &[edge], Chamfer::Symmetric(0.2)).unwrap();
+    assert_eq!(chamfered.face_iter().count(), 7);
+    assert!(f64::abs(volume(&chamfered, 0.01) - 0.98) < 1.0e-6);
+    chamfered.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        assert!([pt.x, pt.y, pt.z]
+            .iter()
+            .all(|x| x.near(&0.0) || x.near(&0.2) || x.near(&1.0)));
+    });
+}
+
+#[test]
+fn chamfer_asymmetric() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(&cube, Point3::origin(), Point3::new(1.0, 0.0, 0.0));
+    let chamfered = chamfer_edges(&cube, &[edge], Chamfer::TwoDistances(0.1, 0.3)).unwrap();
+    assert!(f64::abs(volume(&chamfered, 0.01) - (1.0 - 0.015)) < 1.0e-6);
+    let mut distances = chamfered
+        .vertex_iter()
+        .filter(|v| v.point().x.near(&0.0))
+        .map(|v| v.point().y + v.point().z)
+        .filter(|d| d < &0.5)
+        .collect::<Vec<_>>();
+    distances.sort_by(f64::total_cmp);
+    distances.dedup_by(|a, b| a.near(&b));
+    assert_eq!(distances.len(), 2);
+    assert_near!(distances[0], 0.1);
+    assert_near!(distances[1], 0.3);
+
+    // the equal angles with the both faces
+    let chamfered =
+        chamfer_edges(&cube, &[edge], Chamfer::DistanceAngle(0.2, Rad(PI / 4.0))).unwrap();
+    assert!(f64::abs(volume(&chamfered, 0.01) - 0.98) < 1.0e-6);
+    let chamfered =
+        chamfer_edges(&cube, &[edge], Chamfer::DistanceAngle(0.2, Rad(PI / 3.0))).unwrap();
+    let d1 = 0.2 * f64::tan(PI / 3.0);
+    assert!(f64::abs(volume(&chamfered, 0.01) - (1.0 - 0.1 * d1)) < 1.0e-6);
+}
+
+#[test]
+fn chamfer_corner() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edges = [
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(0.0, 0.0, 1.0),
+    ]
+    .map(|pt| find_edge(&cube, Point3::origin(), pt));
+    let chamfered = chamfer_edges(&cube, &edges, Chamfer::Symmetric(0.2)).unwrap();
+    assert_eq!(chamfered.face_iter().count(), 9);
+    // the wedges of the edges overlap at the corner.
+    let removed = 3.0 * 0.02 - 3.0 * 0.008 / 3.0 + 0.008 / 4.0;
+    assert!(f64::abs(volume(&chamfered, 0.01) - (1.0 - removed)) < 1.0e-6);
+}
+
+#[test]
+fn chamfer_all_edges_of_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edges = cube
+        .edge_iter()
+        .map(|edge| edge.id())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let chamfered = chamfer_edges(&cube, &edges, Chamfer::Symmetric(0.2)).unwrap();
+    assert_eq!(chamfered.face_iter().count(), 18);
+    let removed = 12.0 * 0.02 - 24.0 * 0.008 / 3.0 + 8.0 * 0.008 / 4.0;
+    assert!(f64::abs(volume(&chamfered, 0.01) - (1.0 - removed)) < 1.0e-6);
+}
+
+#[test]
+fn chamfer_cylinder() {
+    let cylinder = cylinder(Point3::origin(), 1.0, 1.0);
+    let top_edges = cylinder
+        .edge_iter()
+        .filter(|edge| edge.front().point().z.near(&1.0) && edge.back().point().z.near(&1.0))
+        .map(|edge| edge.id())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let chamfered = chamfer_edges(&cylinder, &top_edges, Chamfer::Symmetric(0.2)).unwrap();
+    // the ring of the triangle by the theorem of Pappus
+    let removed = 2.0 * PI * (1.0 - 0.2 / 3.0) * 0.02;
+    let ratio = volume(&chamfered, 0.01) / (volume(&cylinder, 0.01) - removed);
+    assert!(f64::abs(ratio - 1.0) < 1.0e-3, "{ratio}");
+    // the chamfer is the cone between the circles.
+    chamfered
+        .face_iter()
+        .filter(|face| matches!(face.surface(), Surface::NurbsSurface(_)))
+        .for_each(|face| {
+            let surface = face.surface();
+            let pt = surface.subs(0.3, 0.4);
+            let r = f64::sqrt(pt.x * pt.x + pt.y * pt.y);
+            assert!(r.near(&1.0) || f64::abs(r + pt.z - 1.8) < 1.0e-6);
+        });
+}
+
+#[test]
+fn chamfer_errors() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let edge = find_edge(&cube0, Point3::origin(), Point3::new(1.0, 0.0, 0.0));
+    assert_eq!(
+        chamfer_edges(&cube0, &[edge], Chamfer::Symmetric(0.0)).unwrap_err(),
+        Error::InvalidChamfer
+    );
+    assert_eq!(
+        chamfer_edges(&cube0, &[edge], Chamfer::DistanceAngle(0.2, Rad(PI))).unwrap_err(),
+        Error::InvalidChamfer
+    );
+    let cube1 = builder::translated(&cube0, Vector3::unit_x() * 2.0);
+    let other = cube1.edge_iter().next().unwrap().id();
+    assert_eq!(
+        chamfer_edges(&cube0, &[other], Chamfer::Symmetric(0.2)).unwrap_err(),
+        Error::EdgeNotFound
+    );
+    // the concave edge of the L-shaped solid
+    let v = [
+        (0.0, 0.0),
+        (2.0, 0.0),
+        (2.0, 1.0),
+        (1.0, 1.0),
+        (1.0, 2.0),
+        (0.0, 2.0),
+    ]
+    .map(|(x, y)| builder::vertex(Point3::new(x, y, 0.0)));
+    let wire = (0..6)
+        .map(|i| builder::line(&v[i], &v[(i + 1) % 6]))
+        .collect::<Wire>();
+    let face = builder::try_attach_plane(&[wire]).unwrap();
+    let l_shape: Solid = builder::tsweep(&face, Vector3::unit_z());
+    let concave = find_edge(
+        &l_shape,
+        Point3::new(1.0, 1.0, 0.0),
+        Point3::new(1.0, 1.0, 1.0),
+    );
+    assert_eq!(
+        chamfer_edges(&l_shape, &[concave], Chamfer::Symmetric(0.2)).unwrap_err(),
+        Error::ChamferFailed(None)
+    );
+}
@@ -0,0 +1,360 @@
+use rustc_hash::FxHashSet as HashSet;
+use std::f64::consts::PI;
+use truck_geometry::prelude::*;
+use truck_topology::*;
+
+/// The position of a point relative to a solid, cf. [`classify_point`].
+#[derive(Debug)]
+pub enum PointClassification<C, S> {
+    /// in the interior of the solid
+    Inside,
+    /// in the exterior of the solid
+    Outside,
+    /// on the face, apart from the boundary of the face
+    OnFace(FaceID<S>),
+    /// on the edge, apart from the end vertices
+    OnEdge(EdgeID<C>),
+    /// on the vertex
+    OnVertex(VertexID<Point3>),
+}
+
+impl<C, S> Clone for PointClassification<C, S> {
+    #[inline(always)]
+    fn clone(&self) -> Self { *self }
+}
+
+impl<C, S> Copy for PointClassification<C, S> {}
+
+impl<C, S> PartialEq for PointClassification<C, S> {
+    fn eq(&self, other: &Self) -> bool {
+        use PointClassification::*;
+        match (self, other) {
+            (Inside, Inside) | (Outside, Outside) => true,
+            (OnFace(id0), OnFace(id1)) => id0 == id1,
+            (OnEdge(id0), OnEdge(id1)) => id0 == id1,
+            (OnVertex(id0), OnVertex(id1)) => id0 == id1,
+            _ => false,
+        }
+    }
+}
+
+impl<C, S> Eq for PointClassification<C, S> {}
+
+// The nearest feature of the boundary of the solid, with the nearest point.
+enum Feature<C, S> {
+    Face(FaceID<S>, Point3, Vector3),
+    Edge(EdgeID<C>, Point3),
+    Vertex(VertexID<Point3>, Point3),
+}
+
+/// Classifies `point` with respect to `solid`.
+///
+/// The point is projected onto the surfaces and the curves of the boundary by [`SearchNearestParameter`], and the
+/// projections onto the surfaces are trimmed by the boundaries of the faces in the parameter spaces, without
+/// tessellating the faces. The point within the distance `tol` from the boundary is on the nearest vertex, edge, or
+/// face in this order. Otherwise, the point is inside if it is behind the nearest part of the boundary, where the
+/// normals of the faces meeting at the nearest edge or vertex are averaged with the weights of the angles.
+///
+/// The boundaries of the faces must be closed in the parameter spaces of the surfaces, as the faces created by
+/// `truck-modeling` or split by [`SplitClosedEdgesAndFaces`](crate::SplitClosedEdgesAndFaces).
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_shapeops::PointClassification;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let classify = |pt| truck_shapeops::classify_point(&cube, pt, 1.0e-3).unwrap();
+/// assert_eq!(classify(Point3::new(0.5, 0.5, 0.5)), PointClassification::Inside);
+/// assert_eq!(classify(Point3::new(1.5, 0.5, 0.5)), PointClassification::Outside);
+/// assert!(matches!(
+///     classify(Point3::new(0.5, 0.5, 1.0)),
+///     PointClassification::OnFace(_),
+/// ));
+/// assert!(matches!(
+///     classify(Point3::new(0.5, 1.0, 1.0)),
+///     PointClassification::OnEdge(_),
+/// ));
+/// assert!(matches!(
+///     classify(Point3::new(1.0, 1.0, 1.0)),
+///     PointClassification::OnVertex(_),
+/// ));
+/// ```
+///
+/// # Failures
+/// Returns `None` if the boundary of a face cannot be projected onto the surface, or if the boundary of `solid` is
+/// empty.
+pub fn classify_point<C, S>(
+    solid: &Solid<Point3, C, S>,
+    point: Point3,
+    tol: f64,
+) -> Option<PointClassification<C, S>>
+where
+    C: ParametricCurve3D
+        + BoundedCurve
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3>
+        + Invertible,
+    S: ParametricSurface3D
+        + SearchParameter<D2, Point = Point3>
+        + SearchNearestParameter<D2, Point = Point3>,
+{
+    nonpositive_tolerance!(tol);
+    let mut nearest = [None, None, None];
+    let mut update = |dist: f64, feature: Feature<C, S>| {
+        let index = match feature {
+            Feature::Face(..) => 0,
+            Feature::Edge(..) => 1,
+            Feature::Vertex(..) => 2,
+        };
+        match nearest[index] {
+            Some((dist0, _)) if dist0 <= dist => {}
+            _ => nearest[index] = Some((dist, feature)),
+        }
+    };
+    for face in solid.face_iter() {
+        if let Some((pt, normal)) = TrimmedFace::try_new(face, tol)?.projection(point) {
+            update(pt.distance(point), Feature::Face(face.id(), pt, normal));
+        }
+    }
+    let mut edge_ids = HashSet::default();
+    for edge in solid.edge_iter() {
+        if !edge_ids.insert(edge.id()) {
+            continue;
+        }
+        let curve = edge.curve();
+        let (t0, t1) = curve.range_tuple();
+        let hint = SPHint1D::Range(t0, t1);
+        if let Some(t) = curve.search_nearest_parameter(point, hint, 100) {
+            if t0 < t && t < t1 {
+                let pt = curve.subs(t);
+                update(pt.distance(point), Feature::Edge(edge.id(), pt));
+            }
+        }
+    }
+    for vertex in solid.vertex_iter() {
+        let pt = vertex.point();
+        update(pt.distance(point), Feature::Vertex(vertex.id(), pt));
+    }
+    // the point near the boundary is on the lowest dimensional feature.
+    let on = nearest
+        .iter()
+        .rev()
+        .flatten()
+        .find(|(dist, _)| *dist <= tol);
+    match on {
+        Some((_, Feature::Vertex(id, _))) => return Some(PointClassification::OnVertex(*id)),
+        Some((_, Feature::Edge(id, _))) => return Some(PointClassification::OnEdge(*id)),
+        Some((_, Feature::Face(id, _, _))) => return Some(PointClassification::OnFace(*id)),
+        None => {}
+    }
+    // the higher dimensional features are preferred if the distances are the same.
+    let closer = |x: (f64, _), y: (f64, _)| match y.0 + TOLERANCE < x.0 {
+        true => y,
+        false => x,
+    };
+    let (_, feature) = nearest.into_iter().flatten().reduce(closer)?;
+    let (pt, normal) = match feature {
+        Feature::Face(_, pt, normal) => (pt, normal),
+        Feature::Edge(id, pt) => (pt, edge_pseudo_normal(solid, id, pt)?),
+        Feature::Vertex(id, pt) => (pt, vertex_pseudo_normal(solid, id, pt)?),
+    };
+    match (point - pt).dot(normal) < 0.0 {
+        true => Some(PointClassification::Inside),
+        false => Some(PointClassification::Outside),
+    }
+}
+
+// The surface of the face with the boundary in the parameter space.
+#[derive(Clone, Debug)]
+pub(crate) struct TrimmedFace<S> {
+    surface: S,
+    orientation: bool,
+    domain: Vec<Vec<Point2>>,
+    range: ((f64, f64), (f64, f64)),
+}
+
+impl<S> TrimmedFace<S>
+where S: ParametricSurface3D
+        + SearchParameter<D2, Point = Point3>
+        + SearchNearestParameter<D2, Point = Point3>
+{
+    // Projects the boundary of `face` onto the surface point by point along the boundary. Returns `None` if the
+    // boundary cannot be projected onto the surface.
+    pub(crate) fn try_new<C>(face: &Face<Point3, C, S>, tol: f64) -> Option<Self>
+    where C: ParametricCurve3D + BoundedCurve + ParameterDivision1D<Point = Point3> + Invertible
+    {
+        let surface = face.surface();
+        let (up, vp) = (surface.u_period(), surface.v_period());
+        let domain = face
+            .absolute_boundaries()
+            .iter()
+            .map(|wire| {
+                let mut previous: Option<(f64, f64)> = None;
+                wire.edge_iter()
+                    .flat_map(|edge| {
+                        let curve = edge.oriented_curve();
+                        let (_, pts) = curve.parameter_division(curve.range_tuple(), tol);
+                        pts.into_iter().skip(1)
+                    })
+                    .map(|pt| {
+                        let (u, v) = surface
+                            .search_parameter(pt, previous, 100)
+                            .or_else(|| surface.search_parameter(pt, None, 100))?;
+                        let (u, v) = match previous {
+                            Some((u0, v0)) => {
+                                (periodic_nearest(u, u0, up), periodic_nearest(v, v0, vp))
+                            }
+                            None => (u, v),
+                        };
+                        previous = Some((u, v));
+                        Some(Point2::new(u, v))
+                    })
+                    .collect()
+            })
+            .collect::<Option<Vec<Vec<_>>>>()?;
+        let range = domain
+            .iter()
+            .flatten()
+            .fold(((f64::MAX, f64::MIN), (f64::MAX, f64::MIN)), |(u, v), p| {
+                ((u.0.min(p.x), u.1.max(p.x)), (v.0.min(p.y), v.1.max(p.y)))
+            });
+        Some(Self {
+            surface,
+            orientation: face.orientation(),
+            domain,
+            range,
+        })
+    }
+
+    #[inline(always)]
+    pub(crate) fn subs(&self, (u, v): (f64, f64)) -> Point3 { self.surface.subs(u, v) }
+
+    // The normal oriented by the face.
+    pub(crate) fn normal(&self, (u, v): (f64, f64)) -> Vector3 {
+        match self.orientation {
+            true => self.surface.normal(u, v),
+            false => -self.surface.normal(u, v),
+        }
+    }
+
+    // The parameter of the nearest point on the surface, searched in the range of the boundary if there is no hint.
+    pub(crate) fn search_nearest_parameter(
+        &self,
+        point: Point3,
+        hint: Option<(f64, f64)>,
+    ) -> Option<(f64, f64)> {
+        let hint = match hint {
+            Some((u, v)) => SPHint2D::Parameter(u, v),
+            None => SPHint2D::Range(self.range.0, self.range.1),
+        };
+        let surface = &self.surface;
+        surface
+            .search_nearest_parameter(point, hint, 100)
+            .or_else(|| surface.search_nearest_parameter(point, None, 100))
+    }
+
+    // Whether the point of the parameter is in the face.
+    pub(crate) fn includes(&self, (u, v): (f64, f64)) -> bool {
+        let ((u0, u1), (v0, v1)) = self.range;
+        let uv = Point2::new(
+            periodic_nearest(u, (u0 + u1) / 2.0, self.surface.u_period()),
+            periodic_nearest(v, (v0 + v1) / 2.0, self.surface.v_period()),
+        );
+        winding_number(&self.domain, uv) != 0
+    }
+
+    // The nearest point on the face and the oriented normal there, if the projection of `point` onto the surface
+    // is in the face.
+    pub(crate) fn projection(&self, point: Point3) -> Option<(Point3, Vector3)> {
+        let uv = self.search_nearest_parameter(point, None)?;
+        match self.includes(uv) {
+            true => Some((self.subs(uv), self.normal(uv))),
+            false => None,
+        }
+    }
+}
+
+// The parameter equivalent to `x` modulo `period` nearest to `x0`.
+fn periodic_nearest(x: f64, x0: f64, period: Option<f64>) -> f64 {
+    match period {
+        Some(period) => x - f64::round((x - x0) / period) * period,
+        None => x,
+    }
+}
+
+fn winding_number(domain: &[Vec<Point2>], c: Point2) -> i32 {
+    let angle = domain
+        .iter()
+        .flat_map(|polygon| polygon.iter().zip(polygon.iter().cycle().skip(1)))
+        .map(|(p, q)| {
+            let (a, b) = (p - c, q - c);
+            f64::atan2(a.x * b.y - a.y * b.x, a.dot(b))
+        })
+        .sum::<f64>();
+    f64::round(angle / (2.0 * PI)) as i32
+}
+
+// The sum of the oriented normals of the faces at `pt` on the edge, that is, the pseudo-normal of the edge.
+fn edge_pseudo_normal<C, S>(
+    solid: &Solid<Point3, C, S>,
+    id: EdgeID<C>,
+    pt: Point3,
+) -> Option<Vector3>
+where
+    C: ParametricCurve3D + BoundedCurve + Invertible,
+    S: ParametricSurface3D + SearchParameter<D2, Point = Point3>,
+{
+    solid
+        .face_iter()
+        .filter(|face| face.edge_iter().any(|edge| edge.id() == id))
+        .try_fold(Vector3::zero(), |sum, face| {
+            Some(sum + face_normal(face, pt)?)
+        })
+}
+
+// The sum of the oriented normals of the faces at the vertex, weighted by the angles of the corners of the faces.
+fn vertex_pseudo_normal<C, S>(
+    solid: &Solid<Point3, C, S>,
+    id: VertexID<Point3>,
+    pt: Point3,
+) -> Option<Vector3>
+where
+    C: ParametricCurve3D + BoundedCurve + Invertible,
+    S: ParametricSurface3D + SearchParameter<D2, Point = Point3>,
+{
+    let mut sum = Vector3::zero();
+    for face in solid.face_iter() {
+        for wire in face.boundaries() {
+            let corners = wire.iter().zip(wire.iter().cycle().skip(1));
+            for (edge0, edge1) in corners.filter(|(edge, _)| edge.back().id() == id) {
+                let (curve0, curve1) = (edge0.oriented_curve(), edge1.oriented_curve());
+                let incoming = -curve0.der(curve0.range_tuple().1);
+                let outgoing = curve1.der(curve1.range_tuple().0);
+                let normal = face_normal(face, pt)?;
+                let angle =
+                    f64::atan2(outgoing.cross(incoming).dot(normal), outgoing.dot(incoming));
+                let angle = if angle < 0.0 { angle + 2.0 * PI } else { angle };
+                sum += normal * angle;
+            }
+        }
+    }
+    Some(sum)
+}
+
+fn face_normal<C, S>(face: &Face<Point3, C, S>, pt: Point3) -> Option<Vector3>
+where S: ParametricSurface3D + SearchParameter<D2, Point = Point3> {
+    let surface = face.surface();
+    let (u, v) = surface.search_parameter(pt, None, 100)?;
+    match face.orientation() {
+        true => Some(surface.normal(u, v)),
+        false => Some(-surface.normal(u, v)),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,151 @@
+use super::*;
+use crate::test_util::{cube, cylinder};
+use truck_modeling::Solid;
+
+#[test]
+fn classify_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let classify = |pt| classify_point(&cube, pt, 1.0e-4).unwrap();
+    assert_eq!(
+        classify(Point3::new(0.5, 0.5, 0.5)),
+        PointClassification::Inside
+    );
+    assert_eq!(
+        classify(Point3::new(0.99, 0.01, 0.99)),
+        PointClassification::Inside
+    );
+    assert_eq!(
+        classify(Point3::new(1.5, 0.5, 0.5)),
+        PointClassification::Outside
+    );
+    // the nearest features are the edge and the vertex.
+    assert_eq!(
+        classify(Point3::new(1.5, 1.5, 0.5)),
+        PointClassification::Outside
+    );
+    assert_eq!(
+        classify(Point3::new(1.5, -0.5, 1.5)),
+        PointClassification::Outside
+    );
+
+    let top = cube
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().z.near(&1.0)))
+        .unwrap();
+    assert_eq!(
+        classify(Point3::new(0.3, 0.6, 1.0)),
+        PointClassification::OnFace(top.id())
+    );
+    let PointClassification::OnEdge(id) = classify(Point3::new(0.3, 0.0, 0.0)) else {
+        panic!("not on the edge");
+    };
+    let edge = cube.edge_iter().find(|edge| edge.id() == id).unwrap();
+    assert!(edge.front().point().y.near(&0.0) && edge.front().point().z.near(&0.0));
+    let PointClassification::OnVertex(id) = classify(Point3::new(1.0, 0.0, 1.0)) else {
+        panic!("not on the vertex");
+    };
+    let vertex = cube.vertex_iter().find(|v| v.id() == id).unwrap();
+    assert_near!(vertex.point(), Point3::new(1.0, 0.0, 1.0));
+}
+
+#[test]
+fn classify_hollow_cube() {
+    let outer = cube(Point3::origin(), 3.0);
+    let mut cavity = cube(Point3::new(1.0, 1.0, 1.0), 1.0);
+    cavity.not();
+    let hollow = Solid::new(vec![
+        outer.into_boundaries().pop().unwrap(),
+        cavity.into_boundaries().pop().unwrap(),
+    ]);
+    let classify = |pt| classify_point(&hollow, pt, 1.0e-4).unwrap();
+    assert_eq!(
+        classify(Point3::new(1.5, 1.5, 1.5)),
+        PointClassification::Outside
+    );
+    assert_eq!(
+        classify(Point3::new(0.5, 1.5, 1.5)),
+        PointClassification::Inside
+    );
+    assert_eq!(
+        classify(Point3::new(2.2, 2.2, 2.2)),
+        PointClassification::Inside
+    );
+    assert!(matches!(
+        classify(Point3::new(1.0, 1.5, 1.5)),
+        PointClassification::OnFace(_)
+    ));
+}
+
+#[test]
+fn classify_cylinder() {
+    let cylinder = cylinder(Point3::origin(), 1.0, 1.0);
+    let classify = |pt| classify_point(&cylinder, pt, 1.0e-4).unwrap();
+    assert_eq!(
+        classify(Point3::new(0.0, 0.0, 0.5)),
+        PointClassification::Inside
+    );
+    assert_eq!(
+        classify(Point3::new(0.7, -0.7, 0.9)),
+        PointClassification::Inside
+    );
+    // inside the bounding box but outside the cylinder
+    assert_eq!(
+        classify(Point3::new(0.8, 0.8, 0.5)),
+        PointClassification::Outside
+    );
+    assert_eq!(
+        classify(Point3::new(0.0, 0.0, 1.5)),
+        PointClassification::Outside
+    );
+    let (s, c) = f64::sin_cos(2.0);
+    assert!(matches!(
+        classify(Point3::new(c, s, 0.5)),
+        PointClassification::OnFace(_)
+    ));
+    assert!(matches!(
+        classify(Point3::new(c, s, 1.0)),
+        PointClassification::OnEdge(_)
+    ));
+    assert!(matches!(
+        classify(Point3::new(0.2, -0.3, 0.0)),
+        PointClassification::OnFace(_)
+    ));
+}
+
+#[test]
+fn classify_difference() {
+    // the cube whose corner is removed
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let difference = crate::difference(&cube0, &cube1, 0.05).unwrap();
+    let classify = |pt| classify_point(&difference, pt, 1.0e-4).unwrap();
+    assert_eq!(
+        classify(Point3::new(0.6, 0.6, 0.6)),
+        PointClassification::Outside
+    );
+    assert_eq!(
+        classify(Point3::new(0.55, 0.55, 0.7)),
+        PointClassification::Outside
+    );
+    assert_eq!(
+        classify(Point3::new(0.75, 0.75, 0.45)),
+        PointClassification::Inside
+    );
+    // the nearest features are the concave edge and the concave vertex.
+    assert_eq!(
+        classify(Point3::new(0.45, 0.45, 0.7)),
+        PointClassification::Inside
+    );
+    assert_eq!(
+        classify(Point3::new(0.4, 0.4, 0.4)),
+        PointClassification::Inside
+    );
+    assert!(matches!(
+        classify(Point3::new(0.5, 0.5, 0.5)),
+        PointClassification::OnVertex(_)
+    ));
+    assert!(matches!(
+        classify(Point3::new(0.5, 0.5, 0.8)),
+        PointClassification::OnEdge(_)
+    ));
+}
@@ -0,0 +1,156 @@
+use super::*;
+use crate::offset::geometry::{range_tuple, search_parameter};
+use truck_base::cgmath_extend_traits::control_point::ControlPoint;
+
+// The extended ranges have the margins of this ratio of the original ranges.
+const EXTENSION_MARGIN: f64 = 0.1;
+
+/// The blossom of the polynomial of the `k`-th span at `args`, by the de Boor algorithm with the different
+/// parameters at each level.
+fn blossom<P: ControlPoint<f64>>(knots: &[f64], points: &[P], k: usize, args: &[f64]) -> P {
+    let degree = args.len();
+    let mut d = points[k - degree..=k].to_vec();
+    for (r, x) in (1..=degree).zip(args) {
+        for j in (r..=degree).rev() {
+            let i = k - degree + j;
+            let alpha = inv_or_zero(knots[i + degree + 1 - r] - knots[i]) * (x - knots[i]);
+            d[j] = d[j - 1] + (d[j] - d[j - 1]) * alpha;
+        }
+    }
+    d[degree]
+}
+
+/// Extends the B-spline with `knot_vec` and `points` to the range `(t0, t1)` containing the original one. The
+/// polynomials of the first and the last spans are extrapolated, i.e. the end knots are replaced, and the control
+/// points depending on them are the blossoms of the polynomials at the new knots.
+fn extend_control_points<P: ControlPoint<f64>>(
+    knot_vec: &KnotVec,
+    points: &[P],
+    (t0, t1): (f64, f64),
+) -> (KnotVec, Vec<P>) {
+    let mut knots = knot_vec.to_vec();
+    let mut points = points.to_vec();
+    let (n, degree) = (points.len() - 1, knots.len() - points.len() - 1);
+    if t0 < knots[degree] {
+        let mut new_knots = knots.clone();
+        new_knots[..=degree].iter_mut().for_each(|t| *t = t0);
+        let new_points = (0..degree)
+            .map(|i| blossom(&knots, &points, degree, &new_knots[i + 1..=i + degree]))
+            .collect::<Vec<_>>();
+        points[..degree].copy_from_slice(&new_points);
+        knots = new_knots;
+    }
+    if t1 > knots[n + 1] {
+        let mut new_knots = knots.clone();
+        new_knots[n + 1..].iter_mut().for_each(|t| *t = t1);
+        let new_points = (n + 1 - degree..=n)
+            .map(|i| blossom(&knots, &points, n, &new_knots[i + 1..=i + degree]))
+            .collect::<Vec<_>>();
+        points[n + 1 - degree..].copy_from_slice(&new_points);
+        knots = new_knots;
+    }
+    (KnotVec::from(knots), points)
+}
+
+fn extend_bspline_curve<P: ControlPoint<f64>>(
+    curve: &BSplineCurve<P>,
+    range: (f64, f64),
+) -> BSplineCurve<P> {
+    let (knot_vec, points) = extend_control_points(curve.knot_vec(), curve.control_points(), range);
+    BSplineCurve::new(knot_vec, points)
+}
+
+fn extend_bspline_surface<P: ControlPoint<f64>>(
+    surface: &BSplineSurface<P>,
+    urange: (f64, f64),
+    vrange: (f64, f64),
+) -> BSplineSurface<P> {
+    let (uknot_vec, vknot_vec) = surface.knot_vecs();
+    let control_points = surface.control_points();
+    let columns = (0..control_points[0].len())
+        .map(|j| {
+            let column = control_points.iter().map(|row| row[j]).collect::<Vec<_>>();
+            extend_control_points(uknot_vec, &column, urange)
+        })
+        .collect::<Vec<_>>();
+    let new_uknot_vec = columns[0].0.clone();
+    let rows = (0..columns[0].1.len())
+        .map(|i| {
+            let row = columns
+                .iter()
+                .map(|(_, column)| column[i])
+                .collect::<Vec<_>>();
+            extend_control_points(vknot_vec, &row, vrange)
+        })
+        .collect::<Vec<_>>();
+    let new_vknot_vec = rows[0].0.clone();
+    let control_points = rows.into_iter().map(|(_, row)| row).collect();
+    BSplineSurface::new((new_uknot_vec, new_vknot_vec), control_points)
+}
+
+/// Extends the profile of the surface of revolution to the range `(t0, t1)` of the parameter of the profile.
+fn extend_profile(curve: &Curve, (t0, t1): (f64, f64)) -> Option<Curve> {
+    match curve {
+        Curve::Line(line) => Some(Curve::Line(Line(line.subs(t0), line.subs(t1)))),
+        Curve::BSplineCurve(bsp) => Some(Curve::BSplineCurve(extend_bspline_curve(bsp, (t0, t1)))),
+        Curve::NurbsCurve(nurbs) => {
+            let extended = extend_bspline_curve(nurbs.non_rationalized(), (t0, t1));
+            Some(Curve::NurbsCurve(NurbsCurve::new(extended)))
+        }
+        Curve::IntersectionCurve(_) => None,
+    }
+}
+
+/// Extends `surface` so that its parameter range contains the parameters of `points`, the points on the
+/// extension of the surface. Planes are not bounded, the B-spline and NURBS surfaces are extended by extrapolating
+/// the polynomials of the spans at the ends, and the surfaces of revolution by extending the revolved curves. The
+/// parameters of the extended surfaces are not the same as the original ones in general.
+pub(super) fn extend_surface(surface: &Surface, points: &[Point3]) -> Option<Surface> {
+    if matches!(surface, Surface::Plane(_)) {
+        return Some(surface.clone());
+    }
+    let ((u0, u1), (v0, v1)) = range_tuple(surface)?;
+    let mut hint = None;
+    let (mut urange, mut vrange) = ((u0, u1), (v0, v1));
+    for point in points {
+        let (u, v) = search_parameter(surface, *point, hint)?;
+        hint = Some((u, v));
+        urange = (f64::min(urange.0, u), f64::max(urange.1, u));
+        vrange = (f64::min(vrange.0, v), f64::max(vrange.1, v));
+    }
+    let extended = |(s0, s1): (f64, f64), (t0, t1): (f64, f64)| {
+        let margin = (t1 - t0) * EXTENSION_MARGIN;
+        (
+            if s0 < t0 - TOLERANCE { s0 - margin } else { t0 },
+            if s1 > t1 + TOLERANCE { s1 + margin } else { t1 },
+        )
+    };
+    let (urange, vrange) = (extended(urange, (u0, u1)), extended(vrange, (v0, v1)));
+    if urange == (u0, u1) && vrange == (v0, v1) {
+        return Some(surface.clone());
+    }
+    match surface {
+        Surface::BSplineSurface(bsp) => Some(extend_bspline_surface(bsp, urange, vrange).into()),
+        Surface::NurbsSurface(nurbs) => {
+            let extended = extend_bspline_surface(nurbs.non_rationalized(), urange, vrange);
+            Some(NurbsSurface::new(extended).into())
+        }
+        Surface::RevolutedCurve(revolution) => {
+            // the angles are periodic, and only the revolved curve is extended.
+            let (range, original) = match revolution.orientation() {
+                true => (urange, (u0, u1)),
+                false => (vrange, (v0, v1)),
+            };
+            if range == original {
+                return Some(surface.clone());
+            }
+            let entity = revolution.entity();
+            let curve = extend_profile(entity.entity_curve(), range)?;
+            let extended = revolution.map_ref(|entity| {
+                RevolutedCurve::by_revolution(curve, entity.origin(), entity.axis())
+            });
+            Some(Surface::RevolutedCurve(extended))
+        }
+        Surface::Plane(_) => Some(surface.clone()),
+    }
+}
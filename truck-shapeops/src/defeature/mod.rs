@@ -0,0 +1,774 @@
+use crate::{
+    errors::Error,
+    offset::geometry::{
+        common_point, intersection_curve, normal, search_parameter, TANGENT_ANGLE_TOLERANCE,
+    },
+    Result,
+};
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use truck_modeling::*;
+
+mod extension;
+use extension::extend_surface;
+// The number of the divisions of the leaders of the new edges.
+const LEADER_DIVISION: usize = 8;
+
+/// Removes `faces` from `solid` and heals the gaps by extending the neighboring faces until they meet again.
+///
+/// The removed faces sharing edges are removed together as a feature. If every boundary of a feature is a whole
+/// boundary wire of a remaining face which has the other boundaries, e.g. a hole or a boss on a face, the wires are
+/// removed from the faces. Otherwise, the feature has to be a strip between two sides, e.g. a fillet or a chamfer
+/// along a chain of edges. The faces on the two sides are extended to the intersection curves of their surfaces,
+/// and the faces at the ends of the strip are extended to the meeting points of the three faces. A closed strip,
+/// e.g. a fillet around a face, has no ends. The surfaces of the extended faces are extended if needed.
+///
+/// `tol` is the tolerance of the new vertices and of the approximations of the new edges.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_shapeops::{fillet_edges, remove_faces, FilletRadius};
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let edge = cube.edge_iter().next().unwrap();
+/// let filleted = fillet_edges(&cube, &[edge.id()], FilletRadius::Constant(0.2)).unwrap();
+/// let cube_vertices = cube.vertex_iter().map(|v| v.point()).collect::<Vec<_>>();
+/// let fillet = filleted
+///     .face_iter()
+///     .find(|face| !matches!(face.surface(), Surface::Plane(_)))
+///     .unwrap();
+///
+/// let defeatured = remove_faces(&filleted, &[fillet.id()], 1.0e-4).unwrap();
+/// assert_eq!(defeatured.face_iter().count(), 6);
+/// defeatured.vertex_iter().for_each(|v| {
+///     assert!(cube_vertices.iter().any(|p| p.near(&v.point())));
+/// });
+/// ```
+///
+/// # Errors
+/// - If one of `faces` is not contained in `solid`, returns [`Error::FaceNotFound`].
+/// - If a feature is neither of the above, or the extended faces do not meet, returns
+///   [`Error::RemoveFacesFailed`].
+pub fn remove_faces(solid: &Solid, faces: &[FaceID], tol: f64) -> Result<Solid> {
+    let contained = |id: &FaceID| solid.face_iter().any(|face| face.id() == *id);
+    if !faces.iter().all(contained) {
+        return Err(Error::FaceNotFound);
+    }
+    let boundaries = solid
+        .boundaries()
+        .iter()
+        .map(|shell| {
+            // the features do not share the edges, so the faces of the latter features are kept by the former.
+            features(shell, faces)
+                .into_iter()
+                .try_fold(shell.clone(), |shell, feature| {
+                    remove_feature(&shell, &feature, tol).ok_or(Error::RemoveFacesFailed)
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Solid::try_new(boundaries)?)
+}
+
+/// The connected components of the removed faces of `shell`.
+fn features(shell: &Shell, faces: &[FaceID]) -> Vec<HashSet<FaceID>> {
+    let removed = shell
+        .face_iter()
+        .filter(|face| faces.contains(&face.id()))
+        .collect::<Vec<_>>();
+    let mut visited = HashSet::default();
+    let mut features = Vec::new();
+    for face in &removed {
+        if !visited.insert(face.id()) {
+            continue;
+        }
+        let mut feature = HashSet::default();
+        feature.insert(face.id());
+        let mut stack = vec![*face];
+        while let Some(face) = stack.pop() {
+            let edges = face
+                .edge_iter()
+                .map(|edge| edge.id())
+                .collect::<HashSet<_>>();
+            for other in &removed {
+                let adjacent = other.edge_iter().any(|edge| edges.contains(&edge.id()));
+                if adjacent && visited.insert(other.id()) {
+                    feature.insert(other.id());
+                    stack.push(other);
+                }
+            }
+        }
+        features.push(feature);
+    }
+    features
+}
+
+/// A maximal sequence of the edges of a boundary wire of a remaining face which are shared with the removed faces.
+#[derive(Clone, Copy, Debug)]
+struct Run {
+    face: usize,
+    wire: usize,
+    start: usize,
+    len: usize,
+}
+
+impl Run {
+    fn edges<'a>(&self, wires: &'a [Vec<Wire>]) -> impl Iterator<Item = &'a Edge> {
+        let wire = &wires[self.face][self.wire];
+        wire.iter().cycle().skip(self.start).take(self.len)
+    }
+    fn is_closed(&self, wires: &[Vec<Wire>]) -> bool {
+        self.len == wires[self.face][self.wire].len()
+    }
+    fn front(&self, wires: &[Vec<Wire>]) -> Vertex {
+        self.edges(wires).next().unwrap().front().clone()
+    }
+    fn back(&self, wires: &[Vec<Wire>]) -> Vertex {
+        self.edges(wires).last().unwrap().back().clone()
+    }
+    // the vertices from the front to the back.
+    fn vertices(&self, wires: &[Vec<Wire>]) -> Vec<Vertex> {
+        let mut vertices = vec![self.front(wires)];
+        vertices.extend(self.edges(wires).map(|edge| edge.back().clone()));
+        vertices
+    }
+    // the polyline through the vertices and the middle points of the edges.
+    fn polyline(&self, wires: &[Vec<Wire>]) -> Vec<Point3> {
+        let mut points = vec![self.front(wires).point()];
+        self.edges(wires).for_each(|edge| {
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            points.push(curve.subs((t0 + t1) / 2.0));
+            points.push(edge.back().point());
+        });
+        points
+    }
+}
+
+/// Finds the runs of the boundary wires of the remaining faces.
+fn find_runs(wires: &[Vec<Wire>], removed_edges: &HashSet<EdgeID>) -> Vec<Run> {
+    let mut runs = Vec::new();
+    for (face, face_wires) in wires.iter().enumerate() {
+        for (wire, edges) in face_wires.iter().enumerate() {
+            let n = edges.len();
+            let removed = edges
+                .iter()
+                .map(|edge| removed_edges.contains(&edge.id()))
+                .collect::<Vec<_>>();
+            if removed.iter().all(|removed| *removed) {
+                runs.push(Run {
+                    face,
+                    wire,
+                    start: 0,
+                    len: n,
+                });
+                continue;
+            }
+            for start in (0..n).filter(|i| removed[*i] && !removed[(i + n - 1) % n]) {
+                let len = (0..n).take_while(|k| removed[(start + k) % n]).count();
+                runs.push(Run {
+                    face,
+                    wire,
+                    start,
+                    len,
+                });
+            }
+        }
+    }
+    runs
+}
+
+/// Connects the runs into the loops around the removed faces. The next run starts at the back of the previous run.
+fn connect_runs(runs: &[Run], wires: &[Vec<Wire>]) -> Option<Vec<Vec<usize>>> {
+    let mut starts = HashMap::<VertexID, usize>::default();
+    for (i, run) in runs.iter().enumerate() {
+        if !run.is_closed(wires) && starts.insert(run.front(wires).id(), i).is_some() {
+            return None;
+        }
+    }
+    let mut visited = vec![false; runs.len()];
+    let mut loops = Vec::new();
+    for i in 0..runs.len() {
+        if visited[i] {
+            continue;
+        }
+        visited[i] = true;
+        let mut runs_loop = vec![i];
+        if !runs[i].is_closed(wires) {
+            let mut next = *starts.get(&runs[i].back(wires).id())?;
+            while next != i {
+                if visited[next] {
+                    return None;
+                }
+                visited[next] = true;
+                runs_loop.push(next);
+                next = *starts.get(&runs[next].back(wires).id())?;
+            }
+        }
+        loops.push(runs_loop);
+    }
+    Some(loops)
+}
+
+/// The new edges replacing the runs and the new vertices of the moved vertices.
+#[derive(Debug, Default)]
+struct Healing {
+    // the pairs of the faces and the vertices of the new edges.
+    edges: Vec<([usize; 2], [Vertex; 2])>,
+    // the indices of the new edges replacing the runs, with the orientations along the runs. The closed runs
+    // replaced by nothing are removed wires, and the other runs replaced by nothing collapse into the vertices.
+    runs: HashMap<usize, Vec<(usize, bool)>>,
+    vertices: HashMap<VertexID, Vertex>,
+}
+
+/// Removes the faces of `feature` from `shell`.
+fn remove_feature(shell: &Shell, feature: &HashSet<FaceID>, tol: f64) -> Option<Shell> {
+    let removed_edges = shell
+        .face_iter()
+        .filter(|face| feature.contains(&face.id()))
+        .flat_map(|face| face.edge_iter().map(|edge| edge.id()))
+        .collect::<HashSet<_>>();
+    let faces = shell
+        .face_iter()
+        .filter(|face| !feature.contains(&face.id()))
+        .collect::<Vec<_>>();
+    let wires = faces
+        .iter()
+        .map(|face| face.boundaries())
+        .collect::<Vec<_>>();
+    let runs = find_runs(&wires, &removed_edges);
+    let loops = connect_runs(&runs, &wires)?;
+
+    let context = Context {
+        faces: &faces,
+        wires: &wires,
+        runs: &runs,
+        removed_edges: &removed_edges,
+        surfaces: faces.iter().map(|face| face.surface()).collect(),
+        tol,
+    };
+    if context.is_hole(&loops) {
+        return context.rebuild(Healing::default());
+    }
+    let mut strips = match loops.as_slice() {
+        [runs_loop] => {
+            // every pair of the runs which are not adjacent can be the ends.
+            let m = runs_loop.len();
+            let ends = (0..m).flat_map(|i| (i + 2..m).map(move |j| (i, j)));
+            ends.filter(|(i, j)| j - i <= m - 2)
+                .map(|(i, j)| Strip {
+                    a: runs_loop[i + 1..j].to_vec(),
+                    b: (j + 1..i + m).rev().map(|k| runs_loop[k % m]).collect(),
+                    ends: Some([runs_loop[i], runs_loop[j]]),
+                })
+                .collect::<Vec<_>>()
+        }
+        [loop0, loop1] => vec![Strip {
+            a: loop0.clone(),
+            b: loop1.iter().rev().copied().collect(),
+            ends: None,
+        }],
+        _ => return None,
+    }
+    .into_iter()
+    .filter_map(|strip| context.heal_strip(&strip))
+    .collect::<Vec<_>>();
+    // the faces are extended as short as possible.
+    strips.sort_by(|(_, extension0), (_, extension1)| extension0.total_cmp(extension1));
+    strips
+        .into_iter()
+        .find_map(|(healing, _)| context.rebuild(healing))
+}
+
+/// A strip of the removed faces between the two sides, the sequences of the runs along the strip. The runs of the
+/// side `b` are in the reversed order of the loop, since they go backward along the strip. The ends of an open
+/// strip are the runs before `a` and after `a` in the loop, which collapse into vertices.
+#[derive(Clone, Debug)]
+struct Strip {
+    a: Vec<usize>,
+    b: Vec<usize>,
+    ends: Option<[usize; 2]>,
+}
+
+/// The points where the faces change along a strip.
+#[derive(Clone, Copy, Debug)]
+enum Event {
+    // the end of the strip, with the run collapsing into the vertex.
+    End(usize),
+    // the faces `a[i]` and `a[i + 1]` change at the back of `a[i]`.
+    A(usize),
+    // the faces `b[j]` and `b[j + 1]` change at the front of `b[j]`.
+    B(usize),
+    // a vertex of a run of the side `a`, to divide a closed strip into edges.
+    Split(usize, usize),
+}
+
+/// The remaining faces of a shell and the runs of their boundaries.
+struct Context<'a> {
+    faces: &'a [&'a Face],
+    wires: &'a [Vec<Wire>],
+    runs: &'a [Run],
+    removed_edges: &'a HashSet<EdgeID>,
+    surfaces: Vec<Surface>,
+    tol: f64,
+}
+
+impl Context<'_> {
+    /// Whether the loops are the wires which can be removed from the faces.
+    fn is_hole(&self, loops: &[Vec<usize>]) -> bool {
+        let mut removed_wires = vec![0; self.faces.len()];
+        let closed = loops.iter().all(|runs_loop| match runs_loop.as_slice() {
+            [run] if self.runs[*run].is_closed(self.wires) => {
+                removed_wires[self.runs[*run].face] += 1;
+                true
+            }
+            _ => false,
+        });
+        closed && (removed_wires.iter().zip(self.wires)).all(|(n, wires)| *n < wires.len())
+    }
+
+    fn run_face(&self, run: usize) -> usize { self.runs[run].face }
+
+    /// The position of `point` along the side `a`, the index of the nearest run plus the ratio of the length to the
+    /// nearest point on the run.
+    fn position(&self, a: &[usize], point: Point3) -> f64 {
+        let mut nearest = (f64::INFINITY, 0.0);
+        for (k, run) in a.iter().enumerate() {
+            let polyline = self.runs[*run].polyline(self.wires);
+            let lengths = polyline
+                .windows(2)
+                .map(|p| p[0].distance(p[1]))
+                .collect::<Vec<_>>();
+            let total = lengths.iter().sum::<f64>();
+            let mut length = 0.0;
+            for (p, segment_length) in polyline.windows(2).zip(&lengths) {
+                let dir = p[1] - p[0];
+                let t = match segment_length.so_small() {
+                    true => 0.0,
+                    false => f64::clamp((point - p[0]).dot(dir) / dir.magnitude2(), 0.0, 1.0),
+                };
+                let distance = point.distance(p[0] + dir * t);
+                if distance < nearest.0 {
+                    nearest = (distance, k as f64 + (length + segment_length * t) / total);
+                }
+                length += segment_length;
+            }
+        }
+        nearest.1
+    }
+
+    /// The events along the strip, sorted by the positions along the side `a`.
+    fn events(&self, strip: &Strip) -> Vec<Event> {
+        let (p, q) = (strip.a.len(), strip.b.len());
+        let (na, nb) = match strip.ends {
+            Some(_) => (p - 1, q - 1),
+            None => (if p > 1 { p } else { 0 }, if q > 1 { q } else { 0 }),
+        };
+        let mut events = (0..na)
+            .map(|i| (Event::A(i), (i + 1) as f64))
+            .chain((0..nb).map(|j| {
+                let point = self.runs[strip.b[j]].front(self.wires).point();
+                (Event::B(j), self.position(&strip.a, point))
+            }))
+            .collect::<Vec<_>>();
+        if strip.ends.is_none() && events.len() < 3 {
+            // the closed strip is divided at the vertices of the side `a`, so that the edges are not closed.
+            strip.a.iter().enumerate().for_each(|(k, run)| {
+                let vertices = self.runs[*run].vertices(self.wires);
+                let interior = match p {
+                    1 => 0..vertices.len() - 1,
+                    _ => 1..vertices.len() - 1,
+                };
+                interior.for_each(|l| {
+                    let position = self.position(&strip.a, vertices[l].point());
+                    events.push((Event::Split(k, l), position));
+                });
+            });
+        }
+        if strip.ends.is_none() {
+            events
+                .iter_mut()
+                .for_each(|(_, position)| *position %= p as f64);
+        }
+        events.sort_by(|(_, x), (_, y)| x.total_cmp(y));
+        let mut events = events
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect::<Vec<_>>();
+        if let Some([end0, end1]) = strip.ends {
+            events.insert(0, Event::End(end0));
+            events.push(Event::End(end1));
+        }
+        events
+    }
+
+    /// Computes the new vertices and edges along the strip. Returns the healing and the total length of the
+    /// extensions, the distances between the old and the new vertices.
+    fn heal_strip(&self, strip: &Strip) -> Option<(Healing, f64)> {
+        let (p, q) = (strip.a.len(), strip.b.len());
+        let events = self.events(strip);
+        // the runs on the sides before the first event.
+        let (mut ia, mut ib) = match strip.ends {
+            Some(_) => (0, 0),
+            None => (
+                events
+                    .iter()
+                    .find_map(|event| match event {
+                        Event::A(i) => Some(*i),
+                        _ => None,
+                    })
+                    .unwrap_or(0),
+                events
+                    .iter()
+                    .find_map(|event| match event {
+                        Event::B(j) => Some(*j),
+                        _ => None,
+                    })
+                    .unwrap_or(0),
+            ),
+        };
+        let (ia0, ib0) = (ia, ib);
+
+        let mut healing = Healing::default();
+        let mut extension = 0.0;
+        let mut vertices = Vec::<Vertex>::new();
+        // the pairs of the runs of the segments between the vertices.
+        let mut segments = Vec::<(usize, usize)>::new();
+        for event in events {
+            let (a, b) = (strip.a[ia], strip.b[ib]);
+            let (faces, plane, old) = match event {
+                Event::End(run) => {
+                    let faces = vec![self.run_face(a), self.run_face(b), self.run_face(run)];
+                    (faces, None, self.runs[run].vertices(self.wires))
+                }
+                Event::A(i) => {
+                    if i != ia {
+                        return None;
+                    }
+                    ia = (ia + 1) % p;
+                    let faces = vec![
+                        self.run_face(a),
+                        self.run_face(strip.a[ia]),
+                        self.run_face(b),
+                    ];
+                    (faces, None, vec![self.runs[a].back(self.wires)])
+                }
+                Event::B(j) => {
+                    if j != ib {
+                        return None;
+                    }
+                    ib = (ib + 1) % q;
+                    let faces = vec![
+                        self.run_face(a),
+                        self.run_face(b),
+                        self.run_face(strip.b[ib]),
+                    ];
+                    (faces, None, vec![self.runs[b].front(self.wires)])
+                }
+                Event::Split(k, l) => {
+                    if k != ia {
+                        return None;
+                    }
+                    let run_vertices = self.runs[a].vertices(self.wires);
+                    let m = run_vertices.len();
+                    let (prev, next) = (&run_vertices[(l + m - 2) % (m - 1)], &run_vertices[l + 1]);
+                    let tangent = next.point() - prev.point();
+                    let plane = (run_vertices[l].point(), tangent.normalize());
+                    let faces = vec![self.run_face(a), self.run_face(b)];
+                    (faces, Some(plane), Vec::new())
+                }
+            };
+            let init = old
+                .iter()
+                .fold(Point3::origin(), |sum, v| sum + v.point().to_vec());
+            let init = match old.is_empty() {
+                true => plane?.0,
+                false => init / old.len() as f64,
+            };
+            let surfaces = faces.iter().map(|i| &self.surfaces[*i]).collect::<Vec<_>>();
+            let planes = plane.into_iter().collect::<Vec<_>>();
+            let mut hints = vec![None; surfaces.len()];
+            let point = common_point(&surfaces, &planes, init, &mut hints, self.tol)?;
+            extension += point.distance(init);
+            let vertex = match vertices.last() {
+                // the coincident vertices are merged, e.g. at the corners where both sides change.
+                Some(last) if last.point().distance(point) < self.tol => last.clone(),
+                _ => {
+                    if !vertices.is_empty() {
+                        segments.push((a, b));
+                    }
+                    vertices.push(Vertex::new(point));
+                    vertices[vertices.len() - 1].clone()
+                }
+            };
+            old.iter().for_each(|v| {
+                healing.vertices.insert(v.id(), vertex.clone());
+            });
+        }
+        if strip.ends.is_none() {
+            if (ia, ib) != (ia0, ib0) || vertices.len() < 2 {
+                return None;
+            }
+            segments.push((strip.a[ia], strip.b[ib]));
+            vertices.push(vertices[0].clone());
+        } else if (ia, ib) != (p - 1, q - 1) {
+            return None;
+        }
+
+        healing.edges = segments
+            .iter()
+            .zip(vertices.windows(2))
+            .map(|((a, b), v)| {
+                (
+                    [self.run_face(*a), self.run_face(*b)],
+                    [v[0].clone(), v[1].clone()],
+                )
+            })
+            .collect();
+        let mut indices = HashMap::<usize, Vec<usize>>::default();
+        for (k, (a, b)) in segments.iter().enumerate() {
+            indices.entry(*a).or_default().push(k);
+            indices.entry(*b).or_default().push(k);
+        }
+        for (run, mut indices) in indices {
+            // the edges of a run are consecutive except for the first run of a closed strip.
+            if let Some(gap) = (1..indices.len()).find(|i| indices[*i] != indices[i - 1] + 1) {
+                indices.rotate_left(gap);
+            }
+            let edges = match strip.a.contains(&run) {
+                true => indices.into_iter().map(|k| (k, true)).collect(),
+                false => indices.into_iter().rev().map(|k| (k, false)).collect(),
+            };
+            healing.runs.insert(run, edges);
+        }
+        Some((healing, extension))
+    }
+
+    /// Creates the new faces of the healing. The edges whose vertices are moved are extended, and the surfaces of
+    /// the faces are extended to contain the new boundaries.
+    fn rebuild(&self, healing: Healing) -> Option<Shell> {
+        let mut moved_edges = HashMap::<EdgeID, (Edge, Vec<usize>)>::default();
+        self.wires.iter().enumerate().for_each(|(i, wires)| {
+            wires.iter().flatten().for_each(|edge| {
+                let moved = [edge.front(), edge.back()]
+                    .iter()
+                    .any(|v| healing.vertices.contains_key(&v.id()));
+                if moved && !self.removed_edges.contains(&edge.id()) {
+                    let entry = moved_edges.entry(edge.id());
+                    entry
+                        .or_insert_with(|| (edge.absolute_clone(), Vec::new()))
+                        .1
+                        .push(i);
+                }
+            })
+        });
+        let moved_vertex = |v: &Vertex| healing.vertices.get(&v.id()).unwrap_or(v).clone();
+        let moved_edges = moved_edges
+            .into_iter()
+            .map(|(id, (edge, faces))| {
+                let vertices = [moved_vertex(edge.front()), moved_vertex(edge.back())];
+                let (p0, p1) = (edge.front().point(), edge.back().point());
+                let (q0, q1) = (vertices[0].point(), vertices[1].point());
+                if (q1 - q0).dot(p1 - p0) <= 0.0 || q0.distance(q1) < self.tol {
+                    return None;
+                }
+                // the edge is extended along its curve, which is extrapolated.
+                let curve = edge.curve();
+                let (t0, t1) = curve.range_tuple();
+                let s0 = curve.search_nearest_parameter(q0, Some(t0), 100)?;
+                let s1 = curve.search_nearest_parameter(q1, Some(t1), 100)?;
+                let guide = |t: f64| {
+                    let s = s0 + (s1 - s0) * t;
+                    (curve.subs(s), curve.der(s))
+                };
+                let faces = [faces[0], faces[faces.len() - 1]];
+                Some((id, NewEdge::new(self, faces, vertices, guide)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let new_edges = healing
+            .edges
+            .into_iter()
+            .map(|(faces, vertices)| {
+                let (p0, p1) = (vertices[0].point(), vertices[1].point());
+                let guide = |t: f64| (p0 + (p1 - p0) * t, p1 - p0);
+                NewEdge::new(self, faces, vertices, guide)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut boundary_points = vec![Vec::new(); self.faces.len()];
+        let all_edges = new_edges
+            .iter()
+            .chain(moved_edges.iter().map(|(_, edge)| edge));
+        all_edges.for_each(|edge| {
+            edge.faces.iter().for_each(|i| {
+                boundary_points[*i].extend(edge.points.iter().copied());
+            })
+        });
+        let surfaces = self
+            .surfaces
+            .iter()
+            .zip(&boundary_points)
+            .map(|(surface, points)| match points.is_empty() {
+                true => Some(surface.clone()),
+                false => extend_surface(surface, points),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let new_edges = new_edges
+            .iter()
+            .map(|edge| edge.edge(&surfaces, self.tol))
+            .collect::<Option<Vec<_>>>()?;
+        let moved_edges = moved_edges
+            .iter()
+            .map(|(id, edge)| Some((*id, edge.edge(&surfaces, self.tol)?)))
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        let mut affected = vec![false; self.faces.len()];
+        (self.runs.iter()).for_each(|run| affected[run.face] = true);
+        boundary_points
+            .iter()
+            .enumerate()
+            .for_each(|(i, points)| affected[i] |= !points.is_empty());
+        let faces = self
+            .faces
+            .iter()
+            .enumerate()
+            .map(|(i, face)| {
+                if !affected[i] {
+                    return Some((*face).clone());
+                }
+                let wires = self.wires[i]
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(w, wire)| {
+                        let mut new_wire = Wire::new();
+                        for (k, edge) in wire.iter().enumerate() {
+                            let run = self
+                                .runs
+                                .iter()
+                                .position(|run| (run.face, run.wire, run.start) == (i, w, k));
+                            if let Some(run) = run {
+                                let edges = healing.runs.get(&run).into_iter().flatten();
+                                new_wire.extend(edges.map(|(k, orientation)| match orientation {
+                                    true => new_edges[*k].clone(),
+                                    false => new_edges[*k].inverse(),
+                                }));
+                            } else if self.removed_edges.contains(&edge.id()) {
+                                continue;
+                            } else if let Some(new_edge) = moved_edges.get(&edge.id()) {
+                                new_wire.push_back(match edge.orientation() {
+                                    true => new_edge.clone(),
+                                    false => new_edge.inverse(),
+                                });
+                            } else {
+                                new_wire.push_back(edge.clone());
+                            }
+                        }
+                        match new_wire.is_empty() {
+                            true => None,
+                            false if face.orientation() => Some(new_wire),
+                            false => Some(new_wire.inverse()),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let mut new_face = Face::try_new(wires, surfaces[i].clone()).ok()?;
+                if !face.orientation() {
+                    new_face.invert();
+                }
+                Some(new_face)
+            })
+            .collect::<Option<Shell>>()?;
+        Some(faces)
+    }
+}
+
+/// An edge on the intersection of the extensions of two faces.
+struct NewEdge {
+    faces: [usize; 2],
+    vertices: [Vertex; 2],
+    // the points on the edge from the front to the back.
+    points: Vec<Point3>,
+    tangent: bool,
+}
+
+impl NewEdge {
+    /// Searches the points of the new edge on the planes through the points of `guide`, the points and the
+    /// tangents at the parameters in `[0, 1]`. If the faces are tangent, the points are projected to the first
+    /// face.
+    fn new(
+        context: &Context<'_>,
+        faces: [usize; 2],
+        vertices: [Vertex; 2],
+        guide: impl Fn(f64) -> (Point3, Vector3),
+    ) -> Option<Self> {
+        let surfaces = [&context.surfaces[faces[0]], &context.surfaces[faces[1]]];
+        let (p0, p1) = (vertices[0].point(), vertices[1].point());
+        if matches!(surfaces, [Surface::Plane(_), Surface::Plane(_)]) {
+            let points = vec![p0, p1];
+            return Some(Self {
+                faces,
+                vertices,
+                points,
+                tangent: false,
+            });
+        }
+        let middle = guide(0.5).0;
+        let n0 = normal(surfaces[0], search_parameter(surfaces[0], middle, None)?)?;
+        let n1 = normal(surfaces[1], search_parameter(surfaces[1], middle, None)?)?;
+        let tangent =
+            n0.cross(n1).magnitude() < n0.magnitude() * n1.magnitude() * TANGENT_ANGLE_TOLERANCE;
+        let mut hints = [None; 2];
+        let mut points = (0..=LEADER_DIVISION)
+            .map(|k| {
+                let (pt, der) = guide(k as f64 / LEADER_DIVISION as f64);
+                match tangent {
+                    true => {
+                        let (u, v) = search_parameter(surfaces[0], pt, hints[0])?;
+                        hints[0] = Some((u, v));
+                        Some(surfaces[0].subs(u, v))
+                    }
+                    false => {
+                        let plane = (pt, der.normalize());
+                        common_point(&surfaces, &[plane], pt, &mut hints, context.tol)
+                    }
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+        points[0] = p0;
+        points[LEADER_DIVISION] = p1;
+        Some(Self {
+            faces,
+            vertices,
+            points,
+            tangent,
+        })
+    }
+
+    /// Creates the edge on the extended `surfaces`.
+    fn edge(&self, surfaces: &[Surface], tol: f64) -> Option<Edge> {
+        let (p0, p1) = (self.points[0], self.points[self.points.len() - 1]);
+        let straight = self.points.iter().all(|pt| {
+            let t = (pt - p0).dot(p1 - p0) / (p1 - p0).magnitude2();
+            pt.distance(p0 + (p1 - p0) * t) < tol
+        });
+        let curve = if straight {
+            Curve::Line(Line(p0, p1))
+        } else if self.tangent {
+            let n = self.points.len();
+            let knot_vec = KnotVec::uniform_knot(2, n - 2);
+            let points = (self.points.iter().enumerate())
+                .map(|(i, pt)| (i as f64 / (n - 1) as f64, *pt))
+                .collect::<Vec<_>>();
+            Curve::BSplineCurve(BSplineCurve::try_interpole(knot_vec, points).ok()?)
+        } else {
+            let surfaces = [&surfaces[self.faces[0]], &surfaces[self.faces[1]]];
+            intersection_curve(surfaces, self.points.clone(), tol)?
+        };
+        Edge::try_new(&self.vertices[0], &self.vertices[1], curve).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,142 @@
+use super::extension::extend_surface;
+use super::remove_faces;
+use crate::test_util::{cube, cylinder, find_edge, volume};
+use crate::{errors::Error, fillet_edges};
+use std::collections::HashSet;
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+fn curved_faces(solid: &Solid) -> Vec<FaceID> {
+    solid
+        .face_iter()
+        .filter(|face| !matches!(face.surface(), Surface::Plane(_)))
+        .map(|face| face.id())
+        .collect()
+}
+
+fn assert_cube(solid: &Solid) {
+    assert_eq!(solid.face_iter().count(), 6);
+    let edges = solid
+        .edge_iter()
+        .map(|edge| edge.id())
+        .collect::<HashSet<_>>();
+    assert_eq!(edges.len(), 12);
+    solid.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        assert!([pt.x, pt.y, pt.z]
+            .iter()
+            .all(|x| x.near(&0.0) || x.near(&1.0)));
+    });
+    assert!(f64::abs(volume(solid, 0.01) - 1.0) < 1.0e-6);
+}
+
+#[test]
+fn remove_fillet() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(&cube, Point3::origin(), Point3::new(1.0, 0.0, 0.0));
+    let filleted = fillet_edges(&cube, &[edge], 0.3.into()).unwrap();
+    let healed = remove_faces(&filleted, &curved_faces(&filleted), 1.0e-4).unwrap();
+    assert_cube(&healed);
+}
+
+#[test]
+fn remove_fillets_around_face() {
+    let cube = cube(Point3::origin(), 1.0);
+    let corners = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+    let top_edges = (0..4)
+        .map(|i| {
+            let (x0, y0) = corners[i];
+            let (x1, y1) = corners[(i + 1) % 4];
+            find_edge(&cube, Point3::new(x0, y0, 1.0), Point3::new(x1, y1, 1.0))
+        })
+        .collect::<Vec<_>>();
+    let filleted = fillet_edges(&cube, &top_edges, 0.2.into()).unwrap();
+    let healed = remove_faces(&filleted, &curved_faces(&filleted), 1.0e-4).unwrap();
+    assert_cube(&healed);
+}
+
+#[test]
+fn remove_through_hole() {
+    let v = builder::vertex(Point3::new(-1.0, -1.0, 0.0));
+    let e = builder::tsweep(&v, Vector3::unit_x() * 2.0);
+    let square: Face = builder::tsweep(&e, Vector3::unit_y() * 2.0);
+    let v = builder::vertex(Point3::new(0.5, 0.0, 0.0));
+    let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let mut wires = square.boundaries();
+    wires.push(circle.inverse());
+    let plate = builder::try_attach_plane(&wires).unwrap();
+    let plate: Solid = builder::tsweep(&plate, Vector3::unit_z());
+    assert_eq!(plate.boundaries()[0].len(), 6 + circle.len());
+
+    let healed = remove_faces(&plate, &curved_faces(&plate), 1.0e-4).unwrap();
+    assert_eq!(healed.face_iter().count(), 6);
+    assert!(f64::abs(volume(&healed, 0.01) - 4.0) < 1.0e-6);
+}
+
+#[test]
+fn extend_nurbs_surface() {
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let arc = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(1.0));
+    let face: Face = builder::tsweep(&arc[0], Vector3::unit_z());
+    let surface = face.surface();
+    assert!(matches!(surface, Surface::NurbsSurface(_)));
+    // the points on the cylinder out of the face
+    let points = [Point3::new(0.0, 1.0, -0.5), Point3::new(1.0, 0.0, 1.5)];
+    let extended = extend_surface(&surface, &points).unwrap();
+    let (urange, vrange) = extended.try_range_tuple();
+    let ((u0, u1), (v0, v1)) = (urange.unwrap(), vrange.unwrap());
+    points.into_iter().for_each(|pt| {
+        let (u, v) = extended.search_parameter(pt, None, 100).unwrap();
+        assert_near!(extended.subs(u, v), pt);
+        assert!(u0 <= u && u <= u1 && v0 <= v && v <= v1);
+    });
+    // the extension is on the cylinder.
+    (0..=10).for_each(|i| {
+        (0..=10).for_each(|j| {
+            let u = u0 + (u1 - u0) * i as f64 / 10.0;
+            let v = v0 + (v1 - v0) * j as f64 / 10.0;
+            let pt = extended.subs(u, v);
+            assert!(f64::abs(pt.x * pt.x + pt.y * pt.y - 1.0) < 1.0e-10);
+        })
+    });
+}
+
+#[test]
+fn remove_faces_errors() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let other = builder::translated(&cube0, Vector3::unit_x() * 2.0);
+    let face = other.face_iter().next().unwrap().id();
+    assert_eq!(
+        remove_faces(&cube0, &[face], 1.0e-4).unwrap_err(),
+        Error::FaceNotFound
+    );
+    // the neighboring faces of a face of a cube do not meet.
+    let face = cube0.face_iter().next().unwrap().id();
+    assert_eq!(
+        remove_faces(&cube0, &[face], 1.0e-4).unwrap_err(),
+        Error::RemoveFacesFailed
+    );
+}
+
+#[test]
+fn remove_fillet_around_cylinder() {
+    let cylinder = cylinder(Point3::origin(), 1.0, 1.0);
+    let top_edges = cylinder
+        .edge_iter()
+        .filter(|edge| edge.front().point().z.near(&1.0) && edge.back().point().z.near(&1.0))
+        .map(|edge| edge.id())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let filleted = fillet_edges(&cylinder, &top_edges, 0.2.into()).unwrap();
+    let fillets = filleted
+        .face_iter()
+        .filter(|face| !matches!(face.surface(), Surface::Plane(_)))
+        .filter(|face| face.vertex_iter().all(|v| v.point().z > 0.5))
+        .map(|face| face.id())
+        .collect::<Vec<_>>();
+    let healed = remove_faces(&filleted, &fillets, 1.0e-4).unwrap();
+    assert_eq!(healed.face_iter().count(), cylinder.face_iter().count());
+    let ratio = volume(&healed, 0.01) / volume(&cylinder, 0.01);
+    assert!(f64::abs(ratio - 1.0) < 1.0e-4, "{ratio}");
+}
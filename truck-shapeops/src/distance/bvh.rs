@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use truck_base::bounding_box::BoundingBox;
+use truck_geometry::prelude::*;
+
+// The bounding volume hierarchy of the items with the bounding boxes. The items are split at the median of the
+// centers along the longest axis.
+pub(super) struct Bvh {
+    nodes: Vec<Node>,
+}
+
+struct Node {
+    bdb: BoundingBox<Point3>,
+    kind: NodeKind,
+}
+
+#[derive(Clone, Copy)]
+enum NodeKind {
+    // the index of the item
+    Leaf(usize),
+    // the indices of the children
+    Branch(usize, usize),
+}
+
+impl Bvh {
+    pub(super) fn new(bdbs: Vec<BoundingBox<Point3>>) -> Self {
+        let mut nodes = Vec::with_capacity(2 * bdbs.len());
+        let mut items: Vec<_> = bdbs.into_iter().enumerate().collect();
+        if !items.is_empty() {
+            build(&mut nodes, &mut items);
+        }
+        Self { nodes }
+    }
+
+    // The root is the last node pushed.
+    fn root(&self) -> Option<usize> { self.nodes.len().checked_sub(1) }
+
+    // The pairs of the items of `self` and `other` in the ascending order of the distances of the bounding boxes.
+    // The pairs are found lazily by splitting the pair of the nodes nearest to each other.
+    pub(super) fn close_pairs<'a>(&'a self, other: &'a Bvh) -> ClosePairs<'a> {
+        let mut heap = BinaryHeap::new();
+        if let (Some(root0), Some(root1)) = (self.root(), other.root()) {
+            let bound = bdb_distance(&self.nodes[root0].bdb, &other.nodes[root1].bdb);
+            heap.push(Candidate {
+                bound,
+                nodes: (root0, root1),
+            });
+        }
+        ClosePairs {
+            bvhs: (self, other),
+            heap,
+        }
+    }
+}
+
+// Pushes the nodes of the items, and returns the index of the root.
+fn build(nodes: &mut Vec<Node>, items: &mut [(usize, BoundingBox<Point3>)]) -> usize {
+    let bdb = items.iter().fold(BoundingBox::new(), |bdb, (_, x)| bdb + x);
+    let kind = if let [(index, _)] = items {
+        NodeKind::Leaf(*index)
+    } else {
+        let centers: BoundingBox<Point3> = items.iter().map(|(_, x)| x.center()).collect();
+        let diagonal = centers.diagonal();
+        let axis = (0..3)
+            .max_by(|i, j| diagonal[*i].total_cmp(&diagonal[*j]))
+            .unwrap_or(0);
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |(_, x), (_, y)| {
+            x.center()[axis].total_cmp(&y.center()[axis])
+        });
+        let (front, back) = items.split_at_mut(mid);
+        NodeKind::Branch(build(nodes, front), build(nodes, back))
+    };
+    nodes.push(Node { bdb, kind });
+    nodes.len() - 1
+}
+
+// The distance of the bounding boxes, which is the lower bound of the distances of the points in them.
+fn bdb_distance(bdb0: &BoundingBox<Point3>, bdb1: &BoundingBox<Point3>) -> f64 {
+    let (min0, max0) = (bdb0.min(), bdb0.max());
+    let (min1, max1) = (bdb1.min(), bdb1.max());
+    (0..3)
+        .map(|i| f64::max(0.0, f64::max(min1[i] - max0[i], min0[i] - max1[i])))
+        .map(|x| x * x)
+        .sum::<f64>()
+        .sqrt()
+}
+
+// The pair of the nodes with the distance of their bounding boxes, ordered so that the heap pops the nearest one.
+struct Candidate {
+    bound: f64,
+    nodes: (usize, usize),
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering { other.bound.total_cmp(&self.bound) }
+}
+
+// The iterator of the pairs of the items: `(distance of the bounding boxes, index of item0, index of item1)`.
+pub(super) struct ClosePairs<'a> {
+    bvhs: (&'a Bvh, &'a Bvh),
+    heap: BinaryHeap<Candidate>,
+}
+
+impl Iterator for ClosePairs<'_> {
+    type Item = (f64, usize, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (bvh0, bvh1) = self.bvhs;
+        loop {
+            let Candidate { bound, nodes } = self.heap.pop()?;
+            let (node0, node1) = (&bvh0.nodes[nodes.0], &bvh1.nodes[nodes.1]);
+            // splits the larger branch
+            let split0 = match (node0.kind, node1.kind) {
+                (NodeKind::Leaf(i), NodeKind::Leaf(j)) => return Some((bound, i, j)),
+                (NodeKind::Branch(..), NodeKind::Leaf(_)) => true,
+                (NodeKind::Leaf(_), NodeKind::Branch(..)) => false,
+                _ => node0.bdb.diameter() >= node1.bdb.diameter(),
+            };
+            let children = match (split0, node0.kind, node1.kind) {
+                (true, NodeKind::Branch(a, b), _) => [(a, nodes.1), (b, nodes.1)],
+                (false, _, NodeKind::Branch(a, b)) => [(nodes.0, a), (nodes.0, b)],
+                _ => unreachable!(),
+            };
+            children.into_iter().for_each(|nodes| {
+                let bound = bdb_distance(&bvh0.nodes[nodes.0].bdb, &bvh1.nodes[nodes.1].bdb);
+                self.heap.push(Candidate { bound, nodes });
+            });
+        }
+    }
+}
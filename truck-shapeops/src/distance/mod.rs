@@ -0,0 +1,301 @@
+use crate::classify::TrimmedFace;
+use crate::{ShapeOpsCurve, ShapeOpsSurface};
+use bvh::Bvh;
+use rustc_hash::FxHashSet as HashSet;
+use truck_base::bounding_box::BoundingBox;
+use truck_geometry::prelude::*;
+use truck_meshalgo::prelude::*;
+use truck_topology::*;
+
+mod bvh;
+
+/// The topological element on which a closest point lies, cf. [`distance`].
+#[derive(Debug)]
+pub enum ShapeElement<C, S> {
+    /// the vertex
+    Vertex(VertexID<Point3>),
+    /// the edge, apart from the end vertices
+    Edge(EdgeID<C>),
+    /// the face, apart from the boundary of the face
+    Face(FaceID<S>),
+}
+
+impl<C, S> Clone for ShapeElement<C, S> {
+    #[inline(always)]
+    fn clone(&self) -> Self { *self }
+}
+
+impl<C, S> Copy for ShapeElement<C, S> {}
+
+impl<C, S> PartialEq for ShapeElement<C, S> {
+    fn eq(&self, other: &Self) -> bool {
+        use ShapeElement::*;
+        match (self, other) {
+            (Vertex(id0), Vertex(id1)) => id0 == id1,
+            (Edge(id0), Edge(id1)) => id0 == id1,
+            (Face(id0), Face(id1)) => id0 == id1,
+            _ => false,
+        }
+    }
+}
+
+impl<C, S> Eq for ShapeElement<C, S> {}
+
+/// The closest points of two solids, cf. [`distance`].
+#[derive(Clone, Debug)]
+pub struct ClosestPoints<C, S> {
+    /// the distance between the solids
+    pub distance: f64,
+    /// the closest points on the first and the second solids
+    pub points: [Point3; 2],
+    /// the elements on which the closest points lie
+    pub elements: [ShapeElement<C, S>; 2],
+}
+
+// The element with the bounding box and the sample points, used for pruning and for the initial hints.
+struct Entry<C, S> {
+    element: Element<C, S>,
+    bdb: BoundingBox<Point3>,
+    samples: Vec<Point3>,
+    sample_tree: Bvh,
+}
+
+enum Element<C, S> {
+    Vertex(VertexID<Point3>, Point3),
+    Edge(EdgeID<C>, C),
+    Face(FaceID<S>, TrimmedFace<S>),
+}
+
+// The parameter of a point on the element.
+#[derive(Clone, Copy, Debug)]
+enum Parameter {
+    Vertex,
+    Edge(f64),
+    Face((f64, f64)),
+}
+
+/// Returns the closest points of the boundaries of `solid0` and `solid1`.
+///
+/// The vertices, the edges and the faces of the solids are paired in the ascending order of the distances of their
+/// bounding boxes by traversing the bounding volume hierarchies of the elements, and the traversal stops at the pairs
+/// whose bounding boxes are farther than the closest points found. The
+/// closest points of each pair are searched by [`algo::curve::search_closest_parameter`] for two edges, and
+/// otherwise by projecting the points onto the curves and the surfaces alternately, starting from the closest
+/// points of the tessellations with the tolerance `tol`. The points projected onto the surfaces are trimmed by the
+/// boundaries of the faces.
+///
+/// The distance is measured between the boundaries, so it is positive even if a solid contains the other. Use
+/// [`classify_point`](crate::classify_point) to check the containment.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+/// let moved = builder::translated(&cube, Vector3::new(2.0, 0.5, 0.5));
+///
+/// let closest = truck_shapeops::distance(&cube, &moved, 0.05).unwrap();
+/// assert!(f64::abs(closest.distance - 1.0) < 1.0e-6);
+/// assert!(f64::abs(closest.points[0].x - 1.0) < 1.0e-6);
+/// assert!(f64::abs(closest.points[1].x - 2.0) < 1.0e-6);
+/// ```
+///
+/// # Failures
+/// Returns `None` if a face cannot be triangulated, if the boundary of a face cannot be projected onto the surface,
+/// or if the boundary of a solid is empty.
+pub fn distance<C, S>(
+    solid0: &Solid<Point3, C, S>,
+    solid1: &Solid<Point3, C, S>,
+    tol: f64,
+) -> Option<ClosestPoints<C, S>>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    nonpositive_tolerance!(tol);
+    let entries0 = entries(solid0, tol)?;
+    let entries1 = entries(solid1, tol)?;
+    let tree0 = Bvh::new(entries0.iter().map(|entry| entry.bdb).collect());
+    let tree1 = Bvh::new(entries1.iter().map(|entry| entry.bdb).collect());
+    let mut closest: Option<ClosestPoints<C, S>> = None;
+    for (lower_bound, i, j) in tree0.close_pairs(&tree1) {
+        let (entry0, entry1) = (&entries0[i], &entries1[j]);
+        if matches!(&closest, Some(closest) if closest.distance <= lower_bound) {
+            break;
+        }
+        let Some((pt0, pt1)) = closest_points(entry0, entry1) else {
+            continue;
+        };
+        let dist = pt0.distance(pt1);
+        if matches!(&closest, Some(closest) if closest.distance <= dist) {
+            continue;
+        }
+        closest = Some(ClosestPoints {
+            distance: dist,
+            points: [pt0, pt1],
+            elements: [entry0.element.id(), entry1.element.id()],
+        });
+    }
+    closest
+}
+
+fn entries<C, S>(solid: &Solid<Point3, C, S>, tol: f64) -> Option<Vec<Entry<C, S>>>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface, {
+    let margin = Vector3::new(tol, tol, tol);
+    let with_margin = |samples: &[Point3]| {
+        let mut bdb: BoundingBox<Point3> = samples.iter().collect();
+        let (min, max) = (bdb.min() - margin, bdb.max() + margin);
+        bdb.push(min);
+        bdb.push(max);
+        bdb
+    };
+    let sample_tree = |samples: &[Point3]| {
+        Bvh::new(
+            samples
+                .iter()
+                .map(|pt| [*pt].into_iter().collect())
+                .collect(),
+        )
+    };
+    let mut entries = Vec::new();
+    let poly_solid = solid.triangulation(tol);
+    for (face, poly_face) in solid.face_iter().zip(poly_solid.face_iter()) {
+        let samples = poly_face.surface()?.positions().clone();
+        entries.push(Entry {
+            element: Element::Face(face.id(), TrimmedFace::try_new(face, tol)?),
+            bdb: with_margin(&samples),
+            sample_tree: sample_tree(&samples),
+            samples,
+        });
+    }
+    let mut edge_ids = HashSet::default();
+    for edge in solid.edge_iter() {
+        if edge_ids.insert(edge.id()) {
+            let curve = edge.curve();
+            let (_, samples) = curve.parameter_division(curve.range_tuple(), tol);
+            entries.push(Entry {
+                element: Element::Edge(edge.id(), curve),
+                bdb: with_margin(&samples),
+                sample_tree: sample_tree(&samples),
+                samples,
+            });
+        }
+    }
+    let mut vertex_ids = HashSet::default();
+    for vertex in solid.vertex_iter() {
+        if vertex_ids.insert(vertex.id()) {
+            let pt = vertex.point();
+            entries.push(Entry {
+                element: Element::Vertex(vertex.id(), pt),
+                bdb: [pt].into_iter().collect(),
+                samples: vec![pt],
+                sample_tree: sample_tree(&[pt]),
+            });
+        }
+    }
+    Some(entries)
+}
+
+impl<C, S> Element<C, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    fn id(&self) -> ShapeElement<C, S> {
+        match self {
+            Element::Vertex(id, _) => ShapeElement::Vertex(*id),
+            Element::Edge(id, _) => ShapeElement::Edge(*id),
+            Element::Face(id, _) => ShapeElement::Face(*id),
+        }
+    }
+
+    // The nearest point on the element, without trimming the surface.
+    fn project(&self, point: Point3, hint: Option<Parameter>) -> Option<(Parameter, Point3)> {
+        match self {
+            Element::Vertex(_, pt) => Some((Parameter::Vertex, *pt)),
+            Element::Edge(_, curve) => {
+                let (t0, t1) = curve.range_tuple();
+                let hint = match hint {
+                    Some(Parameter::Edge(t)) => SPHint1D::Parameter(t),
+                    _ => SPHint1D::Range(t0, t1),
+                };
+                let t = curve.search_nearest_parameter(point, hint, 100)?;
+                let t = t.clamp(t0, t1);
+                Some((Parameter::Edge(t), curve.subs(t)))
+            }
+            Element::Face(_, face) => {
+                let hint = match hint {
+                    Some(Parameter::Face(uv)) => Some(uv),
+                    _ => None,
+                };
+                let uv = face.search_nearest_parameter(point, hint)?;
+                Some((Parameter::Face(uv), face.subs(uv)))
+            }
+        }
+    }
+
+    // Whether the point of the parameter is in the interior of the element.
+    fn includes(&self, parameter: Parameter) -> bool {
+        match (self, parameter) {
+            (Element::Vertex(..), Parameter::Vertex) => true,
+            (Element::Edge(_, curve), Parameter::Edge(t)) => {
+                let (t0, t1) = curve.range_tuple();
+                t0 < t && t < t1
+            }
+            (Element::Face(_, face), Parameter::Face(uv)) => face.includes(uv),
+            _ => false,
+        }
+    }
+}
+
+// The closest points of the interiors of the elements.
+fn closest_points<C, S>(entry0: &Entry<C, S>, entry1: &Entry<C, S>) -> Option<(Point3, Point3)>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface, {
+    let (element0, element1) = (&entry0.element, &entry1.element);
+    // the bounding boxes of the samples are the points, so the first pair is the closest one.
+    let (_, i, j) = entry0.sample_tree.close_pairs(&entry1.sample_tree).next()?;
+    let (pt0, pt1) = (entry0.samples[i], entry1.samples[j]);
+    if let (Element::Edge(_, curve0), Element::Edge(_, curve1)) = (element0, element1) {
+        let (t0, _) = element0.project(pt0, None)?;
+        let (t1, _) = element1.project(pt1, None)?;
+        let (Parameter::Edge(t0), Parameter::Edge(t1)) = (t0, t1) else {
+            return None;
+        };
+        let res = algo::curve::search_closest_parameter(curve0, curve1, (t0, t1), 100);
+        if let Some((t0, t1)) = res {
+            return match element0.includes(Parameter::Edge(t0))
+                && element1.includes(Parameter::Edge(t1))
+            {
+                true => Some((curve0.subs(t0), curve1.subs(t1))),
+                false => None,
+            };
+        }
+    }
+    let (mut param0, mut pt0) = element0.project(pt0, None)?;
+    let (mut param1, mut pt1) = element1.project(pt1, None)?;
+    // the alternating projections converge slowly if the elements are close and curved, so the steps are
+    // checked with the smaller tolerance.
+    for _ in 0..1000 {
+        (param1, pt1) = element1.project(pt0, Some(param1))?;
+        let (param, pt) = element0.project(pt1, Some(param0))?;
+        let converged = pt.distance(pt0) < TOLERANCE * 1.0e-2;
+        (param0, pt0) = (param, pt);
+        if converged {
+            break;
+        }
+    }
+    match element0.includes(param0) && element1.includes(param1) {
+        true => Some((pt0, pt1)),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,107 @@
+use super::*;
+use crate::test_util::cube;
+use std::f64::consts::PI;
+use truck_modeling::{builder, Curve, Solid, Surface};
+
+fn is_vertex(solid: &Solid, element: ShapeElement<Curve, Surface>, pt: Point3) -> bool {
+    solid
+        .vertex_iter()
+        .any(|v| element == ShapeElement::Vertex(v.id()) && v.point().near(&pt))
+}
+
+#[test]
+fn distance_of_cubes() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(2.0, 0.5, 0.5), 1.0);
+    let closest = distance(&cube0, &cube1, 0.05).unwrap();
+    assert_near!(closest.distance, 1.0);
+    assert_near!(closest.points[0].x, 1.0);
+    assert_near!(closest.points[1].x, 2.0);
+
+    // the closest vertices
+    let cube1 = cube(Point3::new(2.0, 2.0, 2.0), 1.0);
+    let closest = distance(&cube0, &cube1, 0.05).unwrap();
+    assert_near!(closest.distance, f64::sqrt(3.0));
+    assert!(is_vertex(
+        &cube0,
+        closest.elements[0],
+        Point3::new(1.0, 1.0, 1.0)
+    ));
+    assert!(is_vertex(
+        &cube1,
+        closest.elements[1],
+        Point3::new(2.0, 2.0, 2.0)
+    ));
+
+    // the interfering cubes
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let closest = distance(&cube0, &cube1, 0.05).unwrap();
+    assert_near!(closest.distance, 0.0);
+}
+
+#[test]
+fn distance_of_skew_edges() {
+    let center = |solid: &Solid| builder::translated(solid, Vector3::new(-0.5, -0.5, -0.5));
+    let cube0 = center(&cube(Point3::origin(), 1.0));
+    let cube0 = builder::rotated(&cube0, Point3::origin(), Vector3::unit_y(), Rad(PI / 4.0));
+    let cube1 = center(&cube(Point3::origin(), 1.0));
+    let cube1 = builder::rotated(&cube1, Point3::origin(), Vector3::unit_x(), Rad(PI / 4.0));
+    let cube1 = builder::translated(&cube1, Vector3::unit_z() * 2.0);
+    let closest = distance(&cube0, &cube1, 0.05).unwrap();
+    let h = f64::sqrt(0.5);
+    assert_near!(closest.distance, 2.0 - 2.0 * h);
+    assert_near!(closest.points[0], Point3::new(0.0, 0.0, h));
+    assert_near!(closest.points[1], Point3::new(0.0, 0.0, 2.0 - h));
+    assert!(matches!(closest.elements[0], ShapeElement::Edge(_)));
+    assert!(matches!(closest.elements[1], ShapeElement::Edge(_)));
+}
+
+#[test]
+fn distance_of_sphere_and_cube() {
+    let v = builder::vertex(Point3::new(0.0, 0.0, 1.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_y(), Rad(PI));
+    let shell = builder::cone(&w, Vector3::unit_z(), Rad(7.0));
+    let shell = builder::rotated(&shell, Point3::origin(), Vector3::unit_x(), Rad(0.5));
+    let shell = builder::translated(&shell, Vector3::new(0.5, 0.5, 3.0));
+    let sphere = Solid::new(vec![shell]);
+    let cube = cube(Point3::origin(), 1.0);
+    let closest = distance(&sphere, &cube, 0.05).unwrap();
+    assert_near!(closest.distance, 1.0);
+    assert_near!(closest.points[0], Point3::new(0.5, 0.5, 2.0));
+    assert_near!(closest.points[1], Point3::new(0.5, 0.5, 1.0));
+    assert!(matches!(closest.elements[0], ShapeElement::Face(_)));
+    assert!(matches!(closest.elements[1], ShapeElement::Face(_)));
+}
+
+#[test]
+fn close_pairs_in_ascending_order() {
+    let points = |n: usize| {
+        (0..n)
+            .map(|_| Point3::new(rand::random(), rand::random(), rand::random()))
+            .collect::<Vec<_>>()
+    };
+    let (points0, points1) = (points(50), points(30));
+    let tree = |points: &[Point3]| {
+        Bvh::new(
+            points
+                .iter()
+                .map(|pt| [*pt].into_iter().collect())
+                .collect(),
+        )
+    };
+    let (tree0, tree1) = (tree(&points0), tree(&points1));
+    let pairs = tree0.close_pairs(&tree1).collect::<Vec<_>>();
+    assert_eq!(pairs.len(), 50 * 30);
+    pairs.windows(2).for_each(|x| assert!(x[0].0 <= x[1].0));
+    pairs.iter().for_each(|(dist, i, j)| {
+        assert!(f64::abs(points0[*i].distance(points1[*j]) - dist) < 1.0e-12)
+    });
+    let nearest = points0
+        .iter()
+        .flat_map(|pt0| points1.iter().map(move |pt1| pt0.distance(*pt1)))
+        .fold(f64::INFINITY, f64::min);
+    assert!(f64::abs(pairs[0].0 - nearest) < 1.0e-12);
+
+    let empty = Bvh::new(Vec::new());
+    assert_eq!(tree0.close_pairs(&empty).count(), 0);
+}
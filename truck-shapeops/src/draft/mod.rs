@@ -0,0 +1,329 @@
+use crate::{
+    errors::Error,
+    offset::{
+        adjacent_faces,
+        geometry::{
+            approximate_curve, common_point, normal, range_tuple, search_parameter, similar_curve,
+            TANGENT_ANGLE_TOLERANCE,
+        },
+        intersection_edge_curve, outward_normal, replace_faces,
+    },
+    Result,
+};
+use rustc_hash::FxHashMap as HashMap;
+use std::f64::consts::PI;
+use truck_modeling::*;
+
+// The tolerance of the approximations of the drafted surfaces and of the intersections of the drafted faces.
+const DRAFT_TOLERANCE: f64 = 1.0e-4;
+// The number of the divisions of the curves at which the drafted points are sampled.
+const SAMPLE_DIVISION: usize = 8;
+
+/// Drafts the faces `faces` of `solid` by `angle` for pulling the solid out of a mould in `pull_direction`.
+///
+/// The faces have to be parallel to the pull direction: the planes containing the direction, and the ruled surfaces
+/// whose rulings are parallel to it, e.g. the sides of the extrusions and the cylinders around the axes along the
+/// direction. Each point of the faces moves inward by `h tan(angle)` along the normal, where `h` is the height of
+/// the point from `neutral_plane` along the pull direction, i.e. the faces are tilted about their intersections
+/// with the neutral plane. A positive angle makes the solid narrower in the pull direction. Planes are tilted to
+/// planes, and the cylinders of revolution to cones. The curves of the other ruled surfaces are the similar copies
+/// if they are, and are approximated by B-spline curves otherwise. The vertices and the edges are moved to the
+/// intersections of the drafted faces and the neighboring faces, keeping the topology of the solid.
+///
+/// # Examples
+/// ```
+/// use std::f64::consts::PI;
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // the side faces
+/// let sides = cube
+///     .face_iter()
+///     .filter(|face| face.vertex_iter().any(|v| v.point().z.near(&0.0)))
+///     .filter(|face| face.vertex_iter().any(|v| v.point().z.near(&1.0)))
+///     .map(|face| face.id())
+///     .collect::<Vec<_>>();
+/// let bottom = Plane::new(Point3::origin(), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+/// let angle = Rad(PI / 36.0);
+/// let drafted = truck_shapeops::draft_faces(&cube, &sides, Vector3::unit_z(), bottom, angle).unwrap();
+///
+/// // the top face shrinks, and the bottom face does not.
+/// let d = f64::tan(angle.0);
+/// drafted.vertex_iter().for_each(|v| {
+///     let pt = v.point();
+///     match pt.z.near(&1.0) {
+///         true => assert!(pt.x.near(&d) || pt.x.near(&(1.0 - d))),
+///         false => assert!(pt.x.near(&0.0) || pt.x.near(&1.0)),
+///     }
+/// });
+/// ```
+///
+/// # Errors
+/// - If `pull_direction` is parallel to `neutral_plane`, or `angle` is zero or not in `(-π/2, π/2)`, returns
+///   [`Error::InvalidDraft`].
+/// - If one of `faces` is not contained in `solid`, returns [`Error::FaceNotFound`].
+/// - If a face is not parallel to the pull direction or is not supported, the drafted faces do not meet the
+///   neighboring faces, or an edge collapses or is reversed, returns [`Error::DraftFailed`].
+pub fn draft_faces(
+    solid: &Solid,
+    faces: &[FaceID],
+    pull_direction: Vector3,
+    neutral_plane: Plane,
+    angle: Rad<f64>,
+) -> Result<Solid> {
+    let n = neutral_plane.normal();
+    let direction = pull_direction.normalize();
+    let transversal = direction.magnitude2().is_finite() && f64::abs(direction.dot(n)) > TOLERANCE;
+    let valid_angle = angle.0.abs() > TOLERANCE && angle.0.abs() < PI / 2.0;
+    if !transversal || !valid_angle {
+        return Err(Error::InvalidDraft);
+    }
+    let contained = |id: &FaceID| solid.face_iter().any(|face| face.id() == *id);
+    if !faces.iter().all(contained) {
+        return Err(Error::FaceNotFound);
+    }
+
+    let draft = Draft {
+        origin: neutral_plane.origin(),
+        normal: n,
+        direction,
+        tan: f64::tan(angle.0),
+    };
+    let boundaries = solid
+        .boundaries()
+        .iter()
+        .map(|shell| draft_shell(shell, faces, &draft).ok_or(Error::DraftFailed))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Solid::try_new(boundaries)?)
+}
+
+// The neutral plane, the unit pull direction and the tangent of the draft angle.
+struct Draft {
+    origin: Point3,
+    normal: Vector3,
+    direction: Vector3,
+    tan: f64,
+}
+
+impl Draft {
+    // Whether the outward normal `n` is perpendicular to the pull direction.
+    fn parallel(&self, n: Vector3) -> bool {
+        f64::abs(n.dot(self.direction)) < f64::sin(TANGENT_ANGLE_TOLERANCE)
+    }
+
+    // The drafted point of `point` on the face with the outward normal `n`.
+    fn point(&self, point: Point3, n: Vector3) -> Point3 {
+        let height = (point - self.origin).dot(self.normal) / self.direction.dot(self.normal);
+        point - n * (height * self.tan)
+    }
+
+    // The drafted point of the point on `surface` at the parameter, with the sign of the outward normals.
+    fn point_on(
+        &self,
+        surface: &impl ParametricSurface3D,
+        uv: (f64, f64),
+        sign: f64,
+    ) -> Option<Point3> {
+        let n = normal(surface, uv)?.normalize() * sign;
+        self.parallel(n)
+            .then(|| self.point(surface.subs(uv.0, uv.1), n))
+    }
+
+    // The drafted surface of the face. The normals of the surface are oriented to the same side as the ones of the
+    // surface of the face.
+    fn surface(&self, face: &Face) -> Option<Surface> {
+        let surface = face.surface();
+        let sign = if face.orientation() { 1.0 } else { -1.0 };
+        let mut drafted = match &surface {
+            Surface::Plane(plane) => {
+                let n = plane.normal() * sign;
+                if !self.parallel(n) {
+                    return None;
+                }
+                let (o, u, v) = (plane.origin(), plane.u_axis(), plane.v_axis());
+                let map = |pt: Point3| self.point(pt, n);
+                Surface::Plane(Plane::new(map(o), map(o + u), map(o + v)))
+            }
+            Surface::RevolutedCurve(revolution) => self.revolution(revolution, sign)?,
+            Surface::BSplineSurface(_) | Surface::NurbsSurface(_) => {
+                self.ruled_surface(&surface, sign)?
+            }
+        };
+
+        // the drafted points of the boundaries are on the drafted surface.
+        let mut hint = None;
+        for edge in face.edge_iter() {
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            for i in 0..=SAMPLE_DIVISION {
+                let pt = curve.subs(t0 + (t1 - t0) * i as f64 / SAMPLE_DIVISION as f64);
+                let uv = search_parameter(&surface, pt, None)?;
+                let moved = self.point_on(&surface, uv, sign)?;
+                // the hint of the previous point may lead to the other side of the closed surface.
+                let on_drafted = |hint: Option<(f64, f64)>| {
+                    let (u, v) = search_parameter(&drafted, moved, hint)?;
+                    (drafted.subs(u, v).distance(moved) <= DRAFT_TOLERANCE).then_some((u, v))
+                };
+                hint = Some(on_drafted(hint).or_else(|| on_drafted(None))?);
+            }
+        }
+        let pt = face.vertex_iter().next()?.point();
+        let n = normal(&surface, search_parameter(&surface, pt, None)?)?;
+        let moved = self.point(pt, n * sign);
+        if normal(&drafted, search_parameter(&drafted, moved, None)?)?.dot(n) < 0.0 {
+            drafted.invert();
+        }
+        Some(drafted)
+    }
+
+    // The cone of the drafted cylinder, the revolution of the tilted line around the axis along the pull direction.
+    fn revolution(
+        &self,
+        surface: &Processor<RevolutedCurve<Curve>, Matrix4>,
+        sign: f64,
+    ) -> Option<Surface> {
+        let trans = *surface.transform();
+        let entity = surface.entity();
+        let Curve::Line(_) = entity.entity_curve() else {
+            return None;
+        };
+        let axis = trans.transform_vector(entity.axis()).normalize();
+        if !axis.cross(self.direction).so_small() {
+            return None;
+        }
+        let param = |t: f64| match surface.orientation() {
+            true => (t, 0.0),
+            false => (0.0, t),
+        };
+        let line = Line(
+            self.point_on(surface, param(0.0), sign)?,
+            self.point_on(surface, param(1.0), sign)?,
+        );
+        let origin = trans.transform_point(entity.origin());
+        Some(Surface::RevolutedCurve(Processor::new(
+            RevolutedCurve::by_revolution(Curve::Line(line), origin, axis),
+        )))
+    }
+
+    // The drafted ruled surface between the drafted curves at the ends of the rulings. The surface is a B-spline or
+    // NURBS surface of degree one in one of the directions.
+    fn ruled_surface(&self, surface: &Surface, sign: f64) -> Option<Surface> {
+        let mut nurbs = match surface {
+            Surface::BSplineSurface(bsp) => NurbsSurface::from(bsp.clone()),
+            Surface::NurbsSurface(nurbs) => nurbs.clone(),
+            _ => return None,
+        };
+        let ruled = |nurbs: &NurbsSurface<Vector4>| {
+            nurbs.vdegree() == 1 && nurbs.control_points()[0].len() == 2
+        };
+        // the rulings are along the second parameter, where the normals are inverted by swapping the axes.
+        let swapped = !ruled(&nurbs);
+        let sign = if swapped {
+            nurbs.swap_axes();
+            -sign
+        } else {
+            sign
+        };
+        if !ruled(&nurbs) {
+            return None;
+        }
+        let (_, (v0, v1)) = range_tuple(&nurbs)?;
+        let rows = [(nurbs.row_curve(0), v0), (nurbs.row_curve(1), v1)].map(|(row, v)| {
+            let target = |u: f64| self.point_on(&nurbs, (u, v), sign);
+            similar_curve(&row, target, SAMPLE_DIVISION).or_else(|| {
+                let bsp = approximate_curve(target, row.range_tuple(), DRAFT_TOLERANCE)?;
+                Some(NurbsCurve::from(bsp))
+            })
+        });
+        let [Some(row0), Some(row1)] = rows else {
+            return None;
+        };
+        let mut drafted = NurbsSurface::new(BSplineSurface::homotopy(
+            row0.non_rationalized().clone(),
+            row1.non_rationalized().clone(),
+        ));
+        if swapped {
+            drafted.swap_axes();
+        }
+        Some(Surface::NurbsSurface(drafted))
+    }
+}
+
+/// Drafts the faces of `shell` in `faces`, and moves the vertices and the edges to the intersections of the faces.
+fn draft_shell(shell: &Shell, faces: &[FaceID], draft: &Draft) -> Option<Shell> {
+    let drafted = shell
+        .face_iter()
+        .map(|face| faces.contains(&face.id()))
+        .collect::<Vec<_>>();
+    let surfaces = shell
+        .face_iter()
+        .zip(&drafted)
+        .map(|(face, drafted)| match drafted {
+            true => draft.surface(face),
+            false => Some(face.surface()),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let (vertex_faces, edge_faces) = adjacent_faces(shell);
+
+    let vertices = vertex_faces
+        .into_iter()
+        .map(|(id, (vertex, faces))| {
+            if !faces.iter().any(|i| drafted[*i]) {
+                return Some((id, vertex));
+            }
+            let surfaces = faces.iter().map(|i| &surfaces[*i]).collect::<Vec<_>>();
+            let mut hints = vec![None; surfaces.len()];
+            let point = common_point(&surfaces, &[], vertex.point(), &mut hints, DRAFT_TOLERANCE)?;
+            Some((id, Vertex::new(point)))
+        })
+        .collect::<Option<HashMap<_, _>>>()?;
+
+    let edges = edge_faces
+        .into_iter()
+        .map(|(id, (edge, faces))| {
+            let (front, back) = (&vertices[&edge.front().id()], &vertices[&edge.back().id()]);
+            let (i, j) = (faces[0], faces[faces.len() - 1]);
+            let moved = front != edge.front() || back != edge.back();
+            if !drafted[i] && !drafted[j] && !moved {
+                return Some((id, edge));
+            }
+            let (p0, p1) = (edge.front().point(), edge.back().point());
+            let (q0, q1) = (front.point(), back.point());
+            let reversed = (q1 - q0).dot(p1 - p0) <= 0.0 || q0.distance(q1) <= DRAFT_TOLERANCE;
+            if p0.distance(p1) > DRAFT_TOLERANCE && reversed {
+                return None;
+            }
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            let middle = curve.subs((t0 + t1) / 2.0);
+            let (n0, n1) = (
+                outward_normal(&shell[i], middle)?,
+                outward_normal(&shell[j], middle)?,
+            );
+            let tangent = n0.angle(n1) < Rad(TANGENT_ANGLE_TOLERANCE);
+            let planes = matches!(
+                (&surfaces[i], &surfaces[j]),
+                (Surface::Plane(_), Surface::Plane(_))
+            );
+            let curve = match (tangent, &curve) {
+                // the ruling between the tangent faces is drafted to a ruling.
+                (true, Curve::Line(_)) => Curve::Line(Line(q0, q1)),
+                (true, _) => return None,
+                (false, _) if planes => Curve::Line(Line(q0, q1)),
+                (false, _) => {
+                    intersection_edge_curve(&curve, [&surfaces[i], &surfaces[j]], (q0, q1))?
+                }
+            };
+            Some((id, Edge::try_new(front, back, curve).ok()?))
+        })
+        .collect::<Option<HashMap<_, _>>>()?;
+
+    replace_faces(shell, surfaces, &edges)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,238 @@
+use super::draft_faces;
+use crate::errors::Error;
+use crate::test_util::{cube, volume};
+use std::f64::consts::PI;
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+fn horizontal_plane(height: f64) -> Plane {
+    Plane::new(
+        Point3::new(0.0, 0.0, height),
+        Point3::new(1.0, 0.0, height),
+        Point3::new(0.0, 1.0, height),
+    )
+}
+
+// the faces between the heights `0` and `height`.
+fn side_faces(solid: &Solid, height: f64) -> Vec<FaceID> {
+    solid
+        .face_iter()
+        .filter(|face| face.vertex_iter().any(|v| v.point().z.near(&0.0)))
+        .filter(|face| face.vertex_iter().any(|v| v.point().z.near(&height)))
+        .map(|face| face.id())
+        .collect()
+}
+
+// the volume of the frustum with the areas of the bottom and the top.
+fn frustum(height: f64, bottom: f64, top: f64) -> f64 {
+    height / 3.0 * (bottom + top + f64::sqrt(bottom * top))
+}
+
+#[test]
+fn draft_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let sides = side_faces(&cube, 1.0);
+    assert_eq!(sides.len(), 4);
+    let angle = Rad(PI / 18.0);
+    let d = f64::tan(angle.0);
+    let drafted = draft_faces(
+        &cube,
+        &sides,
+        Vector3::unit_z(),
+        horizontal_plane(0.0),
+        angle,
+    )
+    .unwrap();
+    assert_eq!(drafted.face_iter().count(), 6);
+    assert!(
+        f64::abs(volume(&drafted, 0.001) - frustum(1.0, 1.0, (1.0 - 2.0 * d).powi(2))) < 1.0e-6
+    );
+
+    // the neutral plane in the middle, and the negative angle
+    let drafted = draft_faces(
+        &cube,
+        &sides,
+        Vector3::unit_z(),
+        horizontal_plane(0.5),
+        Rad(-angle.0),
+    )
+    .unwrap();
+    drafted.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        let expected = match pt.z.near(&1.0) {
+            true => [-d / 2.0, 1.0 + d / 2.0],
+            false => [d / 2.0, 1.0 - d / 2.0],
+        };
+        assert!(expected.iter().any(|x| pt.x.near(x)));
+        assert!(expected.iter().any(|y| pt.y.near(y)));
+    });
+}
+
+#[test]
+fn draft_one_face() {
+    let cube = cube(Point3::origin(), 1.0);
+    let face = cube
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().x.near(&1.0)))
+        .unwrap()
+        .id();
+    let angle = Rad(PI / 6.0);
+    let d = f64::tan(angle.0);
+    let drafted = draft_faces(
+        &cube,
+        &[face],
+        Vector3::unit_z(),
+        horizontal_plane(0.0),
+        angle,
+    )
+    .unwrap();
+    assert_eq!(drafted.face_iter().count(), 6);
+    assert!(f64::abs(volume(&drafted, 0.001) - (1.0 - d / 2.0)) < 1.0e-6);
+    // the faces which are not drafted keep their surfaces.
+    let moved = drafted
+        .vertex_iter()
+        .filter(|v| v.point().near(&Point3::new(1.0 - d, 0.0, 1.0)))
+        .count();
+    assert!(moved > 0);
+}
+
+#[test]
+fn draft_cylinder() {
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let cylinder: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let sides = side_faces(&cylinder, 1.0);
+    let angle = Rad(PI / 12.0);
+    let drafted = draft_faces(
+        &cylinder,
+        &sides,
+        Vector3::unit_z(),
+        horizontal_plane(0.0),
+        angle,
+    )
+    .unwrap();
+    assert_eq!(drafted.face_iter().count(), cylinder.face_iter().count());
+    // the sides are the cone.
+    let r = 1.0 - f64::tan(angle.0);
+    drafted
+        .face_iter()
+        .filter(|face| matches!(face.surface(), Surface::NurbsSurface(_)))
+        .for_each(|face| {
+            let pt = face.surface().subs(0.3, 0.4);
+            let radius = f64::sqrt(pt.x * pt.x + pt.y * pt.y);
+            assert_near!(radius, 1.0 - (1.0 - r) * pt.z);
+        });
+    let expected = frustum(1.0, PI, PI * r * r) / frustum(1.0, PI, PI);
+    let ratio = volume(&drafted, 0.001) / volume(&cylinder, 0.001);
+    assert!(f64::abs(ratio - expected) < 1.0e-3, "{ratio} {expected}");
+}
+
+#[test]
+fn draft_washer() {
+    // the washer of revolution around the pull direction, with the outer and the inner cylinders.
+    let v = builder::vertex(Point3::new(0.5, 0.0, 0.0));
+    let e = builder::tsweep(&v, Vector3::unit_z());
+    let f = builder::tsweep(&e, Vector3::unit_x() * 0.5);
+    let washer = builder::rsweep(&f, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let sides = washer
+        .face_iter()
+        .filter(|face| matches!(face.surface(), Surface::RevolutedCurve(_)))
+        .filter(|face| face.vertex_iter().any(|v| v.point().z.near(&0.0)))
+        .filter(|face| face.vertex_iter().any(|v| v.point().z.near(&1.0)))
+        .map(|face| face.id())
+        .collect::<Vec<_>>();
+    let angle = Rad(PI / 36.0);
+    let d = f64::tan(angle.0);
+    let drafted = draft_faces(
+        &washer,
+        &sides,
+        Vector3::unit_z(),
+        horizontal_plane(0.0),
+        angle,
+    )
+    .unwrap();
+    assert_eq!(drafted.face_iter().count(), washer.face_iter().count());
+    // the outer cylinder shrinks, and the hole grows toward the top.
+    drafted
+        .vertex_iter()
+        .filter(|v| v.point().z.near(&1.0))
+        .for_each(|v| {
+            let pt = v.point();
+            let radius = f64::sqrt(pt.x * pt.x + pt.y * pt.y);
+            assert!(radius.near(&(1.0 - d)) || radius.near(&(0.5 + d)));
+        });
+    let outer = frustum(1.0, PI, PI * (1.0 - d) * (1.0 - d));
+    let inner = frustum(1.0, PI * 0.25, PI * (0.5 + d) * (0.5 + d));
+    let expected = (outer - inner) / (PI * 0.75);
+    let ratio = volume(&drafted, 0.001) / volume(&washer, 0.001);
+    assert!(f64::abs(ratio - expected) < 1.0e-3, "{ratio} {expected}");
+}
+
+#[test]
+fn draft_faces_errors() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let sides = side_faces(&cube0, 1.0);
+    let vertical = Plane::new(
+        Point3::origin(),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(0.0, 0.0, 1.0),
+    );
+    assert_eq!(
+        draft_faces(&cube0, &sides, Vector3::unit_z(), vertical, Rad(0.1)).unwrap_err(),
+        Error::InvalidDraft
+    );
+    assert_eq!(
+        draft_faces(
+            &cube0,
+            &sides,
+            Vector3::unit_z(),
+            horizontal_plane(0.0),
+            Rad(PI / 2.0)
+        )
+        .unwrap_err(),
+        Error::InvalidDraft
+    );
+    let cube1 = builder::translated(&cube0, Vector3::unit_x() * 2.0);
+    let other = cube1.face_iter().next().unwrap().id();
+    assert_eq!(
+        draft_faces(
+            &cube0,
+            &[other],
+            Vector3::unit_z(),
+            horizontal_plane(0.0),
+            Rad(0.1)
+        )
+        .unwrap_err(),
+        Error::FaceNotFound
+    );
+    // the top face is not parallel to the pull direction.
+    let top = cube0
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().z.near(&1.0)))
+        .unwrap()
+        .id();
+    assert_eq!(
+        draft_faces(
+            &cube0,
+            &[top],
+            Vector3::unit_z(),
+            horizontal_plane(0.0),
+            Rad(0.1)
+        )
+        .unwrap_err(),
+        Error::DraftFailed
+    );
+    // the sides collapse.
+    assert_eq!(
+        draft_faces(
+            &cube0,
+            &sides,
+            Vector3::unit_z(),
+            horizontal_plane(0.0),
+            Rad(PI / 3.0)
+        )
+        .unwrap_err(),
+        Error::DraftFailed
+    );
+}
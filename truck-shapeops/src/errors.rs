@@ -0,0 +1,218 @@
+use thiserror::Error;
+use truck_meshalgo::prelude::{Point3, PolylineCurve};
+use truck_topology::FaceID;
+
+/// Shape operation errors
+#[derive(Debug, PartialEq, Error)]
+pub enum Error {
+    /// wrapper of topological error
+    #[error(transparent)]
+    FromTopology(#[from] truck_topology::errors::Error),
+    /// tried to process an edge which is not contained in the solid.
+    #[error("The specified edge is not contained in the solid.")]
+    EdgeNotFound,
+    /// the radius of fillet is not positive.
+    /// cf. [`fillet_edges`](../fn.fillet_edges.html)
+    #[error("The radius of fillet must be positive.")]
+    InvalidFilletRadius,
+    /// failed to create fillet surfaces or to trim the faces adjacent to the fillet.
+    /// cf. [`fillet_edges`](../fn.fillet_edges.html)
+    #[error("Failed to create the fillet along the edges.")]
+    FilletFailed,
+    /// tried to process a face which is not contained in the solid.
+    /// cf. [`shell`](../fn.shell.html)
+    #[error("The specified face is not contained in the solid.")]
+    FaceNotFound,
+    /// the thickness of the shell is zero.
+    /// cf. [`shell`](../fn.shell.html)
+    #[error("The thickness of the shell must not be zero.")]
+    InvalidThickness,
+    /// failed to offset the faces or to trim the offset faces at their intersections.
+    /// cf. [`offset`](../fn.offset.html), [`shell`](../fn.shell.html)
+    #[error("Failed to offset the faces of the solid.")]
+    OffsetFailed,
+    /// failed to extend the faces around the removed faces until they meet.
+    /// cf. [`remove_faces`](../fn.remove_faces.html)
+    #[error("Failed to heal the solid after removing the faces.")]
+    RemoveFacesFailed,
+    /// the distances of chamfer are not positive, or the angle is not in `(0, π)`.
+    /// cf. [`chamfer_edges`](../fn.chamfer_edges.html)
+    #[error("The distances of chamfer must be positive, and the angle must be in (0, π).")]
+    InvalidChamfer,
+    /// failed to create the chamfers or to cut them out of the solid, with the error of the boolean operation if it
+    /// failed.
+    /// cf. [`chamfer_edges`](../fn.chamfer_edges.html)
+    #[error("Failed to create the chamfer along the edges.")]
+    ChamferFailed(#[source] Option<ShapeOpsError<truck_modeling::Surface>>),
+    /// the pull direction of draft is parallel to the neutral plane, or the angle is not in `(-π/2, π/2)` or zero.
+    /// cf. [`draft_faces`](../fn.draft_faces.html)
+    #[error("The pull direction must not be parallel to the neutral plane, and the angle must be nonzero in (-π/2, π/2).")]
+    InvalidDraft,
+    /// failed to tilt the faces or to trim the faces at the intersections with the neighboring faces.
+    /// cf. [`draft_faces`](../fn.draft_faces.html)
+    #[error("Failed to draft the faces of the solid.")]
+    DraftFailed,
+    /// the face to be extruded is not planar or is parallel to the direction, the distance is zero, the draft angle
+    /// is not in `(-π/2, π/2)`, or the target face is not ahead of the face.
+    /// cf. [`extrude`](../fn.extrude.html)
+    #[error("The face must be planar and transversal to the direction, and the extent and the draft angle must be valid.")]
+    InvalidExtrude,
+    /// failed to trim the extrusion by the target face, with the error of the boolean operation if it failed.
+    /// cf. [`extrude`](../fn.extrude.html)
+    #[error("Failed to extrude the face.")]
+    ExtrudeFailed(#[source] Option<ShapeOpsError<truck_modeling::Surface>>),
+}
+
+/// Errors of the boolean operations and the sections, with the failing stage and the faces involved.
+///
+/// The ids are the ones of the faces of the shells processed at the failing stage. If the solids have several
+/// boundary shells, the shells created by the former stages are processed in the later stages.
+#[derive(Clone, Debug, Error)]
+pub enum ShapeOpsError<S> {
+    /// failed to triangulate the face.
+    #[error("Failed to triangulate the face {face:?}.")]
+    TriangulationFailed {
+        /// the face which is not triangulated
+        face: FaceID<S>,
+    },
+    /// failed to compute the intersection curves between the faces.
+    #[error(
+        "Failed to compute the intersection curves between the faces {face0:?} and {face1:?}."
+    )]
+    IntersectionFailed {
+        /// the face of the first solid
+        face0: FaceID<S>,
+        /// the face of the second solid
+        face1: FaceID<S>,
+    },
+    /// failed to add the intersection curve to the boundaries of the faces.
+    #[error("Failed to add the intersection curve to the boundaries of the faces {face0:?} and {face1:?}.")]
+    LoopsStoreFailed {
+        /// the face of the first solid
+        face0: FaceID<S>,
+        /// the face of the second solid
+        face1: FaceID<S>,
+        /// the polyline of the intersection curve
+        polyline: PolylineCurve<Point3>,
+    },
+    /// failed to divide the face by the intersection curves.
+    #[error("Failed to divide the face {face:?} by the intersection curves.")]
+    DivideFaceFailed {
+        /// the face which is not divided
+        face: FaceID<S>,
+    },
+    /// failed to approximate the intersection curve by a B-spline curve.
+    #[error("Failed to approximate the intersection curve between the faces {face0:?} and {face1:?} by a B-spline curve.")]
+    ApproximationFailed {
+        /// the face of the first solid
+        face0: FaceID<S>,
+        /// the face of the second solid
+        face1: FaceID<S>,
+        /// the polyline of the intersection curve
+        polyline: PolylineCurve<Point3>,
+    },
+    /// failed to trace the section curves of the face, or to approximate them by B-spline curves.
+    /// cf. [`section`](../fn.section.html)
+    #[error("Failed to trace the section curves of the face {face:?}.")]
+    SectionFailed {
+        /// the face whose section is not traced
+        face: FaceID<S>,
+    },
+    /// the section of the solid is not closed.
+    /// cf. [`section_faces`](../fn.section_faces.html)
+    #[error("The section of the solid is not closed.")]
+    SectionNotClosed,
+    /// the wires of the section do not bound faces: a hole is not enclosed by any outer boundary, or the wires are
+    /// not simple.
+    /// cf. [`section_faces`](../fn.section_faces.html)
+    #[error("The wires of the section do not bound faces.")]
+    InvalidSectionFaces,
+}
+
+// The surfaces need not be comparable, since the faces are compared by their ids.
+impl<S> PartialEq for ShapeOpsError<S> {
+    fn eq(&self, other: &Self) -> bool {
+        use ShapeOpsError::*;
+        match (self, other) {
+            (TriangulationFailed { face: a }, TriangulationFailed { face: b }) => a == b,
+            (
+                IntersectionFailed {
+                    face0: a0,
+                    face1: a1,
+                },
+                IntersectionFailed {
+                    face0: b0,
+                    face1: b1,
+                },
+            ) => a0 == b0 && a1 == b1,
+            (
+                LoopsStoreFailed {
+                    face0: a0,
+                    face1: a1,
+                    polyline: a,
+                },
+                LoopsStoreFailed {
+                    face0: b0,
+                    face1: b1,
+                    polyline: b,
+                },
+            ) => a0 == b0 && a1 == b1 && a == b,
+            (DivideFaceFailed { face: a }, DivideFaceFailed { face: b }) => a == b,
+            (
+                ApproximationFailed {
+                    face0: a0,
+                    face1: a1,
+                    polyline: a,
+                },
+                ApproximationFailed {
+                    face0: b0,
+                    face1: b1,
+                    polyline: b,
+                },
+            ) => a0 == b0 && a1 == b1 && a == b,
+            (SectionFailed { face: a }, SectionFailed { face: b }) => a == b,
+            (SectionNotClosed, SectionNotClosed) => true,
+            (InvalidSectionFaces, InvalidSectionFaces) => true,
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn print_messages() {
+    use std::io::Write;
+    writeln!(
+        &mut std::io::stderr(),
+        "****** test of the expressions of error messages ******\n"
+    )
+    .unwrap();
+    writeln!(
+        &mut std::io::stderr(),
+        "{}\n",
+        Error::FromTopology(truck_topology::errors::Error::SameVertex)
+    )
+    .unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::EdgeNotFound).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::FilletFailed).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::OffsetFailed).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::RemoveFacesFailed).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::ChamferFailed(None)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::DraftFailed).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::ExtrudeFailed(None)).unwrap();
+    let face = truck_topology::Face::<Point3, (), ()>::new_unchecked(Vec::new(), ());
+    writeln!(
+        &mut std::io::stderr(),
+        "{}\n",
+        ShapeOpsError::ApproximationFailed {
+            face0: face.id(),
+            face1: face.id(),
+            polyline: PolylineCurve(Vec::new()),
+        }
+    )
+    .unwrap();
+    writeln!(
+        &mut std::io::stderr(),
+        "*******************************************************"
+    )
+    .unwrap();
+}
@@ -0,0 +1,248 @@
+use crate::{
+    difference, draft_faces,
+    errors::Error,
+    offset::geometry::{normal, search_parameter},
+    Result,
+};
+use std::f64::consts::PI;
+use truck_modeling::*;
+
+// The tolerance of the boolean operations trimming the extrusions, as the ratio to the size of the profile.
+const TOLERANCE_RATIO: f64 = 0.01;
+// The number of the divisions of the edges and of the parameter ranges at which the target faces are sampled.
+const SAMPLE_DIVISION: usize = 8;
+// The extrusions trimmed by the target faces are longer than the farthest point of the target by this ratio.
+const EXTENSION_RATIO: f64 = 0.25;
+
+/// The extents of the extrusions.
+#[derive(Clone, Debug)]
+pub enum ExtrudeExtent {
+    /// the distance along the direction. A negative distance extrudes the face to the opposite side.
+    Distance(f64),
+    /// up to the surface of the target face, which has to cover the extruded face viewed from the direction.
+    UpToFace(Face),
+    /// the total distance, extruding the face by half of it to the both sides.
+    Symmetric(f64),
+}
+
+/// Options of [`extrude`].
+#[derive(Clone, Debug)]
+pub struct ExtrudeOptions {
+    /// the direction of the extrusion, which must not be parallel to the face.
+    pub direction: Vector3,
+    /// the extent of the extrusion
+    pub extent: ExtrudeExtent,
+    /// the draft angle of the side faces. A positive angle makes the solid narrower away from the face.
+    pub draft_angle: Rad<f64>,
+}
+
+/// Extrudes the planar face `face` along the direction with the extent and the draft angle of `options`.
+///
+/// The face is swept by [`builder::tsweep`] to the prism, the pad of the face. The extrusion up to a face is swept
+/// beyond the target and trimmed by the difference with the solid swept from the target face, so that the end of the
+/// solid is on the surface of the target face. Each line along the direction through the face has to meet the target
+/// face once. If the draft angle is nonzero, the side faces are tilted about the plane of the face by
+/// [`draft_faces`], so that the planes are tilted to planes and the ruled surfaces to the tapered ruled surfaces, and
+/// the ends are re-trimmed by the tilted sides.
+///
+/// # Examples
+/// ```
+/// use std::f64::consts::PI;
+/// use truck_modeling::*;
+/// use truck_shapeops::{extrude, ExtrudeExtent, ExtrudeOptions};
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let square: Face = builder::tsweep(&e, Vector3::unit_y());
+///
+/// // the pad tapered by 5 degrees
+/// let options = ExtrudeOptions {
+///     direction: Vector3::unit_z(),
+///     extent: ExtrudeExtent::Distance(1.0),
+///     draft_angle: Rad(PI / 36.0),
+/// };
+/// let pad = extrude(&square, options).unwrap();
+/// let d = f64::tan(PI / 36.0);
+/// pad.vertex_iter().for_each(|v| {
+///     let pt = v.point();
+///     match pt.z.near(&1.0) {
+///         true => assert!(pt.x.near(&d) || pt.x.near(&(1.0 - d))),
+///         false => assert!(pt.z.near(&0.0) && (pt.x.near(&0.0) || pt.x.near(&1.0))),
+///     }
+/// });
+///
+/// // the pad up to the tilted plane
+/// let target = builder::try_attach_plane(&[{
+///     let v = [(-1.0, -1.0, 2.0), (2.0, -1.0, 2.0), (2.0, 2.0, 5.0), (-1.0, 2.0, 5.0)]
+///         .map(|(x, y, z)| builder::vertex(Point3::new(x, y, z)));
+///     (0..4).map(|i| builder::line(&v[i], &v[(i + 1) % 4])).collect()
+/// }])
+/// .unwrap();
+/// let options = ExtrudeOptions {
+///     direction: Vector3::unit_z(),
+///     extent: ExtrudeExtent::UpToFace(target),
+///     draft_angle: Rad(0.0),
+/// };
+/// let pad = extrude(&square, options).unwrap();
+/// pad.vertex_iter().for_each(|v| {
+///     let pt = v.point();
+///     assert!(pt.z.near(&0.0) || f64::abs(pt.z - (pt.y + 3.0)) < 0.01);
+/// });
+/// ```
+///
+/// # Errors
+/// - If the face is not planar, the direction is parallel to the face, the distance is zero, the draft angle is not
+///   in `(-π/2, π/2)`, or the target face is not ahead of the face in the direction, returns
+///   [`Error::InvalidExtrude`].
+/// - If the extrusion is not trimmed by the target face, returns [`Error::ExtrudeFailed`], whose source is the error
+///   of the failed boolean operation.
+/// - If the side faces are not drafted, returns the error of [`draft_faces`], i.e. [`Error::DraftFailed`].
+pub fn extrude(face: &Face, options: ExtrudeOptions) -> Result<Solid> {
+    let ExtrudeOptions {
+        direction,
+        extent,
+        draft_angle,
+    } = options;
+    let Surface::Plane(plane) = face.oriented_surface() else {
+        return Err(Error::InvalidExtrude);
+    };
+    let direction = direction.normalize();
+    let transversal =
+        direction.magnitude2().is_finite() && direction.dot(plane.normal()).abs() > TOLERANCE;
+    if !transversal || draft_angle.0.abs() >= PI / 2.0 {
+        return Err(Error::InvalidExtrude);
+    }
+    // the distance along the direction from the plane of the face
+    let height =
+        |pt: Point3| (pt - plane.origin()).dot(plane.normal()) / direction.dot(plane.normal());
+
+    let (start, length) = match &extent {
+        ExtrudeExtent::Distance(distance) => (0.0, *distance),
+        ExtrudeExtent::Symmetric(distance) => (-distance / 2.0, *distance),
+        ExtrudeExtent::UpToFace(target) => {
+            let heights = target_points(target)
+                .ok_or(Error::InvalidExtrude)?
+                .into_iter()
+                .map(height);
+            let (min, max) = heights.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), h| {
+                (f64::min(min, h), f64::max(max, h))
+            });
+            if min < TOLERANCE {
+                return Err(Error::InvalidExtrude);
+            }
+            (0.0, max * (1.0 + EXTENSION_RATIO))
+        }
+    };
+    if length.abs() < TOLERANCE {
+        return Err(Error::InvalidExtrude);
+    }
+
+    // the face is oriented so that the normal looks toward the sweep, and the caps are outward.
+    let vector = direction * length;
+    let profile = builder::translated(face, direction * start);
+    let profile = match plane.normal().dot(vector) > 0.0 {
+        true => profile,
+        false => profile.inverse(),
+    };
+    let solid: Solid = builder::tsweep(&profile, vector);
+    let solid = match &extent {
+        ExtrudeExtent::UpToFace(target) => {
+            let end = length / (1.0 + EXTENSION_RATIO);
+            let size = face.vertex_iter().fold(0.0, |size, v0| {
+                face.vertex_iter().fold(size, |size, v1| {
+                    f64::max(size, v0.point().distance(v1.point()))
+                })
+            });
+            let tol = TOLERANCE_RATIO * f64::min(size, end);
+            let trimmed = trim_by_target(&solid, target, vector, tol)?;
+            // the parts of the extrusion out of the target face are not trimmed.
+            if !trimmed.vertex_iter().all(|v| height(v.point()) < end + tol) {
+                return Err(Error::ExtrudeFailed(None));
+            }
+            trimmed
+        }
+        _ => solid,
+    };
+    if draft_angle.0.abs() < TOLERANCE {
+        return Ok(solid);
+    }
+    let sides = solid
+        .face_iter()
+        .filter(|face| is_side_face(face, direction))
+        .map(|face| face.id())
+        .collect::<Vec<_>>();
+    draft_faces(&solid, &sides, vector.normalize(), plane, draft_angle)
+}
+
+// Subtracts the solid swept from `target` by `vector` from `solid`, the extrusion beyond the target.
+fn trim_by_target(solid: &Solid, target: &Face, vector: Vector3, tol: f64) -> Result<Solid> {
+    let surface = target.oriented_surface();
+    let pt = target
+        .vertex_iter()
+        .next()
+        .ok_or(Error::InvalidExtrude)?
+        .point();
+    let n = search_parameter(&surface, pt, None)
+        .and_then(|uv| normal(&surface, uv))
+        .ok_or(Error::ExtrudeFailed(None))?;
+    let target = match n.dot(vector) > 0.0 {
+        true => target.clone(),
+        false => target.inverse(),
+    };
+    let tool: Solid = builder::tsweep(&target, vector);
+    let trimmed = difference(solid, &tool, tol).map_err(|e| Error::ExtrudeFailed(Some(e)))?;
+    match trimmed.boundaries().len() {
+        1 => Ok(trimmed),
+        _ => Err(Error::ExtrudeFailed(None)),
+    }
+}
+
+// Whether the face is parallel to `direction`, sampled at the middle points of the boundary edges.
+fn is_side_face(face: &Face, direction: Vector3) -> bool {
+    let surface = face.surface();
+    face.edge_iter().all(|edge| {
+        let curve = edge.curve();
+        let (t0, t1) = curve.range_tuple();
+        let pt = curve.subs((t0 + t1) / 2.0);
+        search_parameter(&surface, pt, None)
+            .and_then(|uv| normal(&surface, uv))
+            .is_some_and(|n| n.normalize().dot(direction).abs() < TOLERANCE_RATIO)
+    })
+}
+
+// The points sampled on the boundaries and on the parameter range of the boundaries of the face.
+fn target_points(face: &Face) -> Option<Vec<Point3>> {
+    let surface = face.surface();
+    let mut points = face
+        .edge_iter()
+        .flat_map(|edge| {
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            (0..SAMPLE_DIVISION)
+                .map(move |i| curve.subs(t0 + (t1 - t0) * i as f64 / SAMPLE_DIVISION as f64))
+        })
+        .collect::<Vec<_>>();
+    let params = points
+        .iter()
+        .map(|pt| search_parameter(&surface, *pt, None))
+        .collect::<Option<Vec<_>>>()?;
+    let ((u0, v0), (u1, v1)) = params.iter().fold(
+        (
+            (f64::INFINITY, f64::INFINITY),
+            (f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |((u0, v0), (u1, v1)), (u, v)| ((u0.min(*u), v0.min(*v)), (u1.max(*u), v1.max(*v))),
+    );
+    let division = SAMPLE_DIVISION as f64;
+    (0..=SAMPLE_DIVISION).for_each(|i| {
+        (0..=SAMPLE_DIVISION).for_each(|j| {
+            let u = u0 + (u1 - u0) * i as f64 / division;
+            let v = v0 + (v1 - v0) * j as f64 / division;
+            points.push(surface.subs(u, v));
+        })
+    });
+    Some(points)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,198 @@
+use super::{extrude, ExtrudeExtent, ExtrudeOptions};
+use crate::errors::Error;
+use crate::test_util::volume;
+use std::f64::consts::PI;
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+fn square() -> Face {
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    builder::tsweep(&e, Vector3::unit_y())
+}
+
+fn disk(radius: f64) -> Face {
+    let v = builder::vertex(Point3::new(radius, 0.0, 0.0));
+    let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    builder::try_attach_plane(&[circle]).unwrap()
+}
+
+fn quadrangle(points: [(f64, f64, f64); 4]) -> Face {
+    let v = points.map(|(x, y, z)| builder::vertex(Point3::new(x, y, z)));
+    let wire: Wire = (0..4)
+        .map(|i| builder::line(&v[i], &v[(i + 1) % 4]))
+        .collect();
+    builder::try_attach_plane(&[wire]).unwrap()
+}
+
+fn options(extent: ExtrudeExtent, draft_angle: f64) -> ExtrudeOptions {
+    ExtrudeOptions {
+        direction: Vector3::unit_z(),
+        extent,
+        draft_angle: Rad(draft_angle),
+    }
+}
+
+// the minimum and the maximum of the heights of the vertices
+fn height_range(solid: &Solid) -> Vector2 {
+    solid.vertex_iter().fold(
+        Vector2::new(f64::INFINITY, f64::NEG_INFINITY),
+        |range, v| {
+            Vector2::new(
+                f64::min(range.x, v.point().z),
+                f64::max(range.y, v.point().z),
+            )
+        },
+    )
+}
+
+#[test]
+fn extrude_distance() {
+    let pad = extrude(&square(), options(ExtrudeExtent::Distance(2.0), 0.0)).unwrap();
+    assert_near!(height_range(&pad), Vector2::new(0.0, 2.0));
+    assert!((volume(&pad, 0.001) - 2.0).abs() < 1.0e-3);
+
+    // the face is extruded to the back side.
+    let pocket = extrude(&square(), options(ExtrudeExtent::Distance(-2.0), 0.0)).unwrap();
+    assert_near!(height_range(&pocket), Vector2::new(-2.0, 0.0));
+    assert!((volume(&pocket, 0.001) - 2.0).abs() < 1.0e-3);
+
+    // the direction is oblique to the face.
+    let options = ExtrudeOptions {
+        direction: Vector3::new(1.0, 0.0, 1.0),
+        ..options(ExtrudeExtent::Distance(f64::sqrt(2.0)), 0.0)
+    };
+    let pad = extrude(&square(), options).unwrap();
+    assert_near!(height_range(&pad), Vector2::new(0.0, 1.0));
+    assert!((volume(&pad, 0.001) - 1.0).abs() < 1.0e-3);
+}
+
+#[test]
+fn extrude_symmetric() {
+    let pad = extrude(&disk(1.0), options(ExtrudeExtent::Symmetric(2.0), 0.0)).unwrap();
+    assert_near!(height_range(&pad), Vector2::new(-1.0, 1.0));
+    assert!((volume(&pad, 0.001) - 2.0 * PI).abs() < 1.0e-2);
+}
+
+#[test]
+fn extrude_with_draft() {
+    let angle = PI / 18.0;
+    let d = f64::tan(angle);
+    let pad = extrude(&square(), options(ExtrudeExtent::Distance(1.0), angle)).unwrap();
+    let top = (1.0 - 2.0 * d) * (1.0 - 2.0 * d);
+    let frustum = (1.0 + top + f64::sqrt(top)) / 3.0;
+    assert!((volume(&pad, 0.001) - frustum).abs() < 1.0e-3);
+
+    // the negative angle widens the solid.
+    let pad = extrude(&square(), options(ExtrudeExtent::Distance(1.0), -angle)).unwrap();
+    let top = (1.0 + 2.0 * d) * (1.0 + 2.0 * d);
+    let frustum = (1.0 + top + f64::sqrt(top)) / 3.0;
+    assert!((volume(&pad, 0.001) - frustum).abs() < 1.0e-3);
+
+    // the cylinder is tapered to the truncated cone.
+    let pad = extrude(&disk(1.0), options(ExtrudeExtent::Distance(1.0), angle)).unwrap();
+    let r = 1.0 - d;
+    let cone = PI / 3.0 * (1.0 + r + r * r);
+    assert!((volume(&pad, 0.001) - cone).abs() < 1.0e-2);
+}
+
+#[test]
+fn extrude_up_to_face() {
+    let target = quadrangle([
+        (-1.0, -1.0, 2.0),
+        (2.0, -1.0, 2.0),
+        (2.0, 2.0, 5.0),
+        (-1.0, 2.0, 5.0),
+    ]);
+    let pad = extrude(
+        &square(),
+        options(ExtrudeExtent::UpToFace(target.clone()), 0.0),
+    )
+    .unwrap();
+    pad.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        assert!(pt.z.near(&0.0) || f64::abs(pt.z - (pt.y + 3.0)) < 0.01);
+    });
+    assert!((volume(&pad, 0.001) - 3.5).abs() < 1.0e-2);
+
+    // the target face with the reversed normal
+    let pad = extrude(
+        &square(),
+        options(ExtrudeExtent::UpToFace(target.inverse()), 0.0),
+    )
+    .unwrap();
+    assert!((volume(&pad, 0.001) - 3.5).abs() < 1.0e-2);
+
+    // the drafted pad ends on the target.
+    let pad = extrude(
+        &square(),
+        options(ExtrudeExtent::UpToFace(target), PI / 36.0),
+    )
+    .unwrap();
+    pad.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        assert!(pt.z.near(&0.0) || f64::abs(pt.z - (pt.y + 3.0)) < 0.01);
+    });
+    assert!(volume(&pad, 0.001) < 3.5);
+}
+
+#[test]
+fn extrude_up_to_curved_face() {
+    // the part of the cylinder of radius 4 around the y-axis
+    let v0 = builder::vertex(Point3::new(-2.0, -1.0, f64::sqrt(12.0)));
+    let v1 = builder::vertex(Point3::new(2.0, -1.0, f64::sqrt(12.0)));
+    let arc = builder::circle_arc(&v0, &v1, Point3::new(0.0, -1.0, 4.0));
+    let target: Face = builder::tsweep(&arc, Vector3::new(0.0, 3.0, 0.0));
+    let pad = extrude(&square(), options(ExtrudeExtent::UpToFace(target), 0.0)).unwrap();
+    pad.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        assert!(pt.z.near(&0.0) || f64::abs(pt.x * pt.x + pt.z * pt.z - 16.0) < 0.05);
+    });
+    // the integral of `sqrt(16 - x^2)` over `[0, 1]`
+    let area = (f64::sqrt(15.0) + 16.0 * f64::asin(0.25)) / 2.0;
+    assert!((volume(&pad, 0.001) - area).abs() < 1.0e-2);
+}
+
+#[test]
+fn invalid_extrusions() {
+    let parallel = ExtrudeOptions {
+        direction: Vector3::unit_x(),
+        ..options(ExtrudeExtent::Distance(1.0), 0.0)
+    };
+    assert_eq!(
+        extrude(&square(), parallel).unwrap_err(),
+        Error::InvalidExtrude
+    );
+    assert_eq!(
+        extrude(&square(), options(ExtrudeExtent::Distance(0.0), 0.0)).unwrap_err(),
+        Error::InvalidExtrude
+    );
+    assert_eq!(
+        extrude(&square(), options(ExtrudeExtent::Distance(1.0), PI / 2.0)).unwrap_err(),
+        Error::InvalidExtrude
+    );
+
+    // the target face behind the face
+    let behind = quadrangle([
+        (-1.0, -1.0, -2.0),
+        (2.0, -1.0, -2.0),
+        (2.0, 2.0, -1.0),
+        (-1.0, 2.0, -1.0),
+    ]);
+    assert_eq!(
+        extrude(&square(), options(ExtrudeExtent::UpToFace(behind), 0.0)).unwrap_err(),
+        Error::InvalidExtrude
+    );
+
+    // the target face does not cover the face.
+    let small = quadrangle([
+        (0.25, 0.25, 2.0),
+        (0.75, 0.25, 2.0),
+        (0.75, 0.75, 2.0),
+        (0.25, 0.75, 2.0),
+    ]);
+    assert_eq!(
+        extrude(&square(), options(ExtrudeExtent::UpToFace(small), 0.0)).unwrap_err(),
+        Error::ExtrudeFailed(None)
+    );
+}
@@ -0,0 +1,183 @@
+use super::*;
+
+/// The rolling ball at a vertex where three fillets meet.
+///
+/// Each fillet ends at the great circle of the ball, and the corner is covered by the spherical triangle.
+#[derive(Clone, Debug)]
+pub(super) struct CornerBall {
+    // the original vertex, which is removed by the fillets
+    vertex: Vertex,
+    center: Point3,
+    radius: f64,
+    // the contact vertices of the ball and the faces around the corner
+    contacts: Vec<Vertex>,
+    // the end arcs of the fillets, oriented as the boundary of the corner face
+    arcs: Vec<Edge>,
+    // the edges connecting the contact vertices and the corner temporarily until all fillets are created.
+    // The edges themselves are kept since the ids of the dropped edges may be reused.
+    bridges: Vec<Edge>,
+}
+
+impl CornerBall {
+    /// Creates the ball tangent to the three faces around `vertex`.
+    ///
+    /// Returns `None` if `vertex` is not shared by exactly three faces, or if the three edges are not all convex
+    /// or all concave.
+    pub(super) fn new(shell: &Shell, vertex: &Vertex, radius: f64) -> Option<Self> {
+        let faces = faces_around_vertex(shell, vertex);
+        let edges = shell
+            .edge_iter()
+            .filter(|edge| edge.front() == vertex || edge.back() == vertex)
+            .unique_by(|edge| edge.id())
+            .collect::<Vec<_>>();
+        if faces.len() != 3 || edges.len() != 3 {
+            return None;
+        }
+        let signs = edges
+            .iter()
+            .map(|edge| {
+                let (face0, face1) = adjacent_faces(shell, edge)?;
+                let (surface0, surface1) = (
+                    shell[face0].oriented_surface(),
+                    shell[face1].oriented_surface(),
+                );
+                let curve = edge.oriented_curve();
+                let (t0, t1) = curve.range_tuple();
+                let t = if edge.front() == vertex { t0 } else { t1 };
+                let (u0, v0) = surface0.search_parameter(vertex.point(), None, 100)?;
+                let (u1, v1) = surface1.search_parameter(vertex.point(), None, 100)?;
+                let n = surface0.normal(u0, v0).cross(surface1.normal(u1, v1));
+                Some(f64::signum(n.dot(curve.der(t))))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if signs[0] != signs[1] || signs[0] != signs[2] {
+            return None;
+        }
+        let sign = signs[0];
+
+        let surfaces = faces
+            .iter()
+            .map(|&i| shell[i].oriented_surface())
+            .collect::<Vec<_>>();
+        let mut points = vec![vertex.point(); 3];
+        let mut params = surfaces
+            .iter()
+            .map(|surface| surface.search_parameter(vertex.point(), None, 100))
+            .collect::<Option<Vec<_>>>()?;
+        for _ in 0..100 {
+            let normals = surfaces
+                .iter()
+                .zip(&params)
+                .map(|(surface, &(u, v))| surface.normal(u, v))
+                .collect::<Vec<_>>();
+            let mat = Matrix3::from_cols(normals[0], normals[1], normals[2]).transpose();
+            let vec = Vector3::new(
+                normals[0].dot(points[0].to_vec()) - sign * radius,
+                normals[1].dot(points[1].to_vec()) - sign * radius,
+                normals[2].dot(points[2].to_vec()) - sign * radius,
+            );
+            let center = Point3::from_vec(mat.invert()? * vec);
+            let contacts = normals
+                .iter()
+                .map(|n| center + sign * radius * n)
+                .collect::<Vec<_>>();
+            if points.iter().zip(&contacts).all(|(p, q)| p.near(q)) {
+                return Some(Self {
+                    vertex: vertex.clone(),
+                    center,
+                    radius,
+                    contacts: points.into_iter().map(Vertex::new).collect(),
+                    arcs: Vec::new(),
+                    bridges: Vec::new(),
+                });
+            }
+            for ((surface, param), (point, contact)) in surfaces
+                .iter()
+                .zip(&mut params)
+                .zip(points.iter_mut().zip(contacts))
+            {
+                *param = surface.search_nearest_parameter(contact, Some(*param), 100)?;
+                *point = surface.subs(param.0, param.1);
+            }
+        }
+        None
+    }
+
+    #[inline(always)]
+    pub(super) fn vertex(&self) -> &Vertex { &self.vertex }
+
+    #[inline(always)]
+    pub(super) fn center(&self) -> Point3 { self.center }
+
+    /// The contact vertex nearest to `point`.
+    pub(super) fn contact(&self, point: Point3) -> &Vertex {
+        let dist = |v: &&Vertex| v.point().distance2(point);
+        self.contacts
+            .iter()
+            .min_by(|v0, v1| dist(v0).total_cmp(&dist(v1)))
+            .unwrap()
+    }
+
+    /// Registers the end arc of a fillet.
+    #[inline(always)]
+    pub(super) fn push_arc(&mut self, arc: Edge) { self.arcs.push(arc) }
+
+    /// Whether `edge` is a temporary bridge of the corner.
+    #[inline(always)]
+    pub(super) fn is_bridge(&self, edge: &Edge) -> bool {
+        self.bridges.iter().any(|bridge| bridge.is_same(edge))
+    }
+
+    /// Creates the temporary edge from `v0` to `v1`, if one of them is the corner and the other is a contact vertex.
+    pub(super) fn bridge(&mut self, v0: &Vertex, v1: &Vertex) -> Option<Edge> {
+        let is_contact = |v: &Vertex| self.contacts.contains(v);
+        let valid =
+            (v0 == &self.vertex && is_contact(v1)) || (v1 == &self.vertex && is_contact(v0));
+        if !valid {
+            return None;
+        }
+        let line = Line(v0.point(), v1.point());
+        let edge = Edge::new(v0, v1, NurbsCurve::from(line.to_bspline()).into());
+        self.bridges.push(edge.clone());
+        Some(edge)
+    }
+
+    /// Creates the spherical face covering the corner.
+    ///
+    /// Returns `None` if the three fillets have not been created yet, or if the temporary bridges remain in `shell`.
+    pub(super) fn corner_face(&self, shell: &Shell) -> Option<Face> {
+        if self.arcs.len() != 3 || shell.edge_iter().any(|edge| self.is_bridge(&edge)) {
+            return None;
+        }
+        let mut arcs = self.arcs.clone();
+        let mut boundary: Wire = vec![arcs.pop()?].into();
+        while let Some(i) = arcs
+            .iter()
+            .position(|arc| Some(arc.front()) == boundary.back_vertex())
+        {
+            boundary.push_back(arcs.swap_remove(i));
+        }
+        if !arcs.is_empty() || !boundary.is_closed() {
+            return None;
+        }
+
+        // The poles and the seam of the sphere are placed away from the spherical triangle.
+        let directions = boundary
+            .vertex_iter()
+            .map(|v| (v.point() - self.center).normalize())
+            .collect::<Vec<_>>();
+        let dir = (directions[0] + directions[1] + directions[2]).normalize();
+        let axis = match dir.x.abs() < 0.5 {
+            true => Vector3::unit_x().cross(dir).normalize(),
+            false => Vector3::unit_y().cross(dir).normalize(),
+        };
+        let mut surface = geometry::sphere(self.center, self.radius, axis, -dir);
+        let orientation = (0..3)
+            .map(|i| directions[i].cross(directions[(i + 1) % 3]))
+            .sum::<Vector3>();
+        if orientation.dot(dir) < 0.0 {
+            surface.invert();
+        }
+        Face::try_new(vec![boundary], surface).ok()
+    }
+}
@@ -0,0 +1,254 @@
+use super::geometry::GeometryTable;
+use super::*;
+use crate::{errors::Error, Result};
+use std::fmt::{Debug, Formatter};
+use truck_modeling as modeling;
+
+const FILLET_DIVISION: usize = 5;
+// The number of the divisions of `[0, 1]` at which the variable radius is checked before filleting.
+const RADIUS_CHECK_DIVISION: usize = 1000;
+// The edges whose tangent vectors at the shared vertex make an angle smaller than this are filleted at once. This is
+// looser than `offset::geometry::TANGENT_ANGLE_TOLERANCE` for the faces, since the boundaries of the fillets, which
+// are approximated through the rolling balls, are tangent to the adjacent edges only approximately.
+const CHAIN_ANGLE_TOLERANCE: f64 = 1.0e-2;
+
+/// Radius of fillets.
+pub enum FilletRadius {
+    /// constant radius
+    Constant(f64),
+    /// variable radius, the function of the arc length along each chain of tangent edges normalized to `[0, 1]`.
+    Variable(Box<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl FilletRadius {
+    fn radius(&self, t: f64) -> f64 {
+        match self {
+            FilletRadius::Constant(radius) => *radius,
+            FilletRadius::Variable(radius) => radius(t),
+        }
+    }
+}
+
+impl Debug for FilletRadius {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilletRadius::Constant(radius) => f.debug_tuple("Constant").field(radius).finish(),
+            FilletRadius::Variable(_) => f.debug_tuple("Variable").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl From<f64> for FilletRadius {
+    #[inline(always)]
+    fn from(radius: f64) -> Self { FilletRadius::Constant(radius) }
+}
+
+/// Fillets the edges of the solid by rolling ball.
+///
+/// The edges which are tangentially connected each other are filleted at once along the chain of edges.
+/// For variable radius, the parameter of radius is the arc length normalized along each chain.
+/// The edges adjacent to the filleted ones are trimmed, and the fillets meeting at a vertex are connected by
+/// the intersection curves. If three chains end at a vertex shared by three faces, the corner is blended by
+/// the rolling ball touching the three faces.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_shapeops::{fillet_edges, FilletRadius};
+///
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let edge = cube.edge_iter().next().unwrap();
+/// let filleted = fillet_edges(&cube, &[edge.id()], FilletRadius::Constant(0.2)).unwrap();
+/// assert_eq!(filleted.face_iter().count(), 7);
+/// ```
+///
+/// # Failures
+/// - If one of `edges` is not contained in `solid`, returns [`Error::EdgeNotFound`].
+/// - If the radius is not positive, returns [`Error::InvalidFilletRadius`].
+/// - If the fillet surfaces cannot be constructed, e.g. at a vertex shared by more than three faces, or at a
+///   corner where the radii of the three chains are different, returns [`Error::FilletFailed`].
+pub fn fillet_edges(
+    solid: &modeling::Solid,
+    edges: &[modeling::EdgeID],
+    radius: FilletRadius,
+) -> Result<modeling::Solid> {
+    // The radius is validated before any shell is modified.
+    let positive = match &radius {
+        FilletRadius::Constant(radius) => *radius > TOLERANCE,
+        FilletRadius::Variable(radius) => (0..=RADIUS_CHECK_DIVISION)
+            .all(|i| radius(i as f64 / RADIUS_CHECK_DIVISION as f64) > TOLERANCE),
+    };
+    if !positive {
+        return Err(Error::InvalidFilletRadius);
+    }
+
+    let mut table = GeometryTable::default();
+    let mut requested = edges.to_vec();
+    let shells = solid
+        .boundaries()
+        .iter()
+        .map(|shell| {
+            let mut new_shell = table.shell_to_fillet(shell);
+            let fillet_edges = shell
+                .edge_iter()
+                .zip(new_shell.edge_iter())
+                .filter(|(edge, _)| requested.contains(&edge.id()))
+                .unique_by(|(edge, _)| edge.id())
+                .collect::<Vec<_>>();
+            requested.retain(|id| fillet_edges.iter().all(|(edge, _)| edge.id() != *id));
+            let fillet_edges = fillet_edges.into_iter().map(|(_, edge)| edge).collect();
+            let chains = tangent_chains(fillet_edges);
+            let mut corners =
+                corner_balls(&new_shell, &chains, &radius).ok_or(Error::FilletFailed)?;
+            for chain in chains {
+                let wire = chain
+                    .edge_iter()
+                    .map(|edge| find_current_edge(&new_shell, edge))
+                    .collect::<Option<Wire>>()
+                    .ok_or(Error::FilletFailed)?;
+                fillet_along_wire_with_corners(
+                    &mut new_shell,
+                    &wire,
+                    |t| radius.radius(t),
+                    FILLET_DIVISION,
+                    &mut corners,
+                )
+                .ok_or(Error::FilletFailed)?;
+            }
+            let corner_faces = corners
+                .iter()
+                .map(|corner| corner.corner_face(&new_shell))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(Error::FilletFailed)?;
+            new_shell.extend(corner_faces);
+            Ok(new_shell)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !requested.is_empty() {
+        return Err(Error::EdgeNotFound);
+    }
+
+    let boundaries = shells
+        .iter()
+        .map(|shell| {
+            shell.try_mapped(
+                |point| Some(*point),
+                |curve| table.curve_to_modeling(curve),
+                |surface| Some(table.surface_to_modeling(surface)),
+            )
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::FilletFailed)?;
+    Ok(modeling::Solid::try_new(boundaries)?)
+}
+
+fn smoothly_connected(edge0: &Edge, edge1: &Edge) -> bool {
+    if edge0.back() != edge1.front() {
+        return false;
+    }
+    let (curve0, curve1) = (edge0.oriented_curve(), edge1.oriented_curve());
+    let der0 = curve0.der(curve0.range_tuple().1);
+    let der1 = curve1.der(curve1.range_tuple().0);
+    der0.angle(der1) < Rad(CHAIN_ANGLE_TOLERANCE)
+}
+
+fn tangent_chains(mut edges: Vec<Edge>) -> Vec<Wire> {
+    let mut chains = Vec::new();
+    while let Some(edge) = edges.pop() {
+        let mut chain: Wire = vec![edge].into();
+        while !chain.is_cyclic() {
+            let back = chain.back_edge().unwrap();
+            let next = edges.iter().enumerate().find_map(|(i, edge)| {
+                [edge.clone(), edge.inverse()]
+                    .into_iter()
+                    .find(|edge| smoothly_connected(back, edge))
+                    .map(|edge| (i, edge))
+            });
+            let Some((i, edge)) = next else { break };
+            edges.swap_remove(i);
+            chain.push_back(edge);
+        }
+        while !chain.is_cyclic() {
+            let front = chain.front_edge().unwrap();
+            let prev = edges.iter().enumerate().find_map(|(i, edge)| {
+                [edge.clone(), edge.inverse()]
+                    .into_iter()
+                    .find(|edge| smoothly_connected(edge, front))
+                    .map(|edge| (i, edge))
+            });
+            let Some((i, edge)) = prev else { break };
+            edges.swap_remove(i);
+            chain.push_front(edge);
+        }
+        chains.push(chain);
+    }
+    chains
+}
+
+// The rolling balls at the vertices where three chains end.
+// Returns `None` if the corner cannot be blended by a ball, e.g. the radii of the chains are different.
+fn corner_balls(shell: &Shell, chains: &[Wire], radius: &FilletRadius) -> Option<Vec<CornerBall>> {
+    let ends = chains
+        .iter()
+        .filter(|chain| !chain.is_cyclic())
+        .flat_map(|chain| {
+            let (front, back) = chain.ends_vertices().unwrap();
+            [
+                (front.clone(), radius.radius(0.0)),
+                (back.clone(), radius.radius(1.0)),
+            ]
+        })
+        .collect::<Vec<_>>();
+    ends.iter()
+        .map(|(vertex, _)| vertex)
+        .unique_by(|vertex| vertex.id())
+        .filter_map(|vertex| {
+            let radii = ends
+                .iter()
+                .filter(|(v, _)| v == vertex)
+                .map(|(_, r)| *r)
+                .collect::<Vec<_>>();
+            match radii.len() == 3 {
+                true if radii.iter().all(|r| r.near(&radii[0])) => {
+                    Some(CornerBall::new(shell, vertex, radii[0]))
+                }
+                true => Some(None),
+                false => None,
+            }
+        })
+        .collect()
+}
+
+// Returns the edge in `shell` corresponding to `original`.
+// The edges adjacent to fillets are trimmed, so the edge is searched geometrically if it is not found.
+fn find_current_edge(shell: &Shell, original: &Edge) -> Option<Edge> {
+    let orient = |edge: Edge, orientation: bool| match orientation == original.orientation() {
+        true => edge,
+        false => edge.inverse(),
+    };
+    if let Some(edge) = shell.edge_iter().find(|edge| edge.is_same(original)) {
+        return Some(orient(edge.absolute_clone(), true));
+    }
+    let curve = original.curve();
+    let (t0, t1) = curve.range_tuple();
+    let parameter = |point: Point3| {
+        let t = curve.search_nearest_parameter(point, None, 100)?;
+        match t0 - TOLERANCE < t && t < t1 + TOLERANCE && curve.subs(t).near(&point) {
+            true => Some(t),
+            false => None,
+        }
+    };
+    shell.edge_iter().find_map(|edge| {
+        let edge = edge.absolute_clone();
+        let piece = edge.curve();
+        let (s0, s1) = piece.range_tuple();
+        let u0 = parameter(piece.subs(s0))?;
+        let u1 = parameter(piece.subs(s1))?;
+        parameter(piece.subs((s0 + s1) / 2.0))?;
+        Some(orient(edge, u0 < u1))
+    })
+}
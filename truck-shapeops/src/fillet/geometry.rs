@@ -0,0 +1,183 @@
+use super::*;
+use truck_modeling as modeling;
+
+const APPROXIMATION_TOLERANCE: f64 = 1.0e-4;
+
+pub(crate) fn curve_to_nurbs(curve: &modeling::Curve) -> NurbsCurve<Vector4> {
+    match curve {
+        modeling::Curve::Line(line) => NurbsCurve::from(line.to_bspline()),
+        modeling::Curve::BSplineCurve(bsp) => NurbsCurve::from(bsp.clone()),
+        modeling::Curve::NurbsCurve(nurbs) => nurbs.clone(),
+        modeling::Curve::IntersectionCurve(curve) => curve_to_nurbs(curve.leader()),
+    }
+}
+
+pub(super) fn surface_to_nurbs(surface: &modeling::Surface) -> NurbsSurface<Vector4> {
+    match surface {
+        modeling::Surface::Plane(plane) => plane.into_nurbs(),
+        modeling::Surface::BSplineSurface(bsp) => NurbsSurface::from(bsp.clone()),
+        modeling::Surface::NurbsSurface(nurbs) => nurbs.clone(),
+        modeling::Surface::RevolutedCurve(_) => revolution_to_nurbs(surface),
+    }
+}
+
+// Exact NURBS representation of the surface of revolution, by the tensor product of the entity curve and
+// the rational quadratic full circle.
+fn revolution_to_nurbs(surface: &modeling::Surface) -> NurbsSurface<Vector4> {
+    let modeling::Surface::RevolutedCurve(processor) = surface else {
+        unreachable!()
+    };
+    let revolution = processor.entity();
+    let curve = curve_to_nurbs(revolution.entity_curve());
+    let (origin, axis) = (revolution.origin(), revolution.axis().normalize());
+    let mut nurbs = revolve(&curve, origin, axis);
+    nurbs.transform_by(*processor.transform());
+
+    let (u0, u1) = curve.range_tuple();
+    let (u, v) = ((u0 + u1) / 2.0, 0.125);
+    if let Some((s, t)) = surface.search_parameter(nurbs.subs(u, v), None, 100) {
+        if nurbs.normal(u, v).dot(surface.normal(s, t)) < 0.0 {
+            nurbs.invert();
+        }
+    }
+    nurbs
+}
+
+// Rotates `curve` by the full circle around the axis. The parameter `v` is the angle normalized to `[0, 1]`.
+fn revolve(curve: &NurbsCurve<Vector4>, origin: Point3, axis: Vector3) -> NurbsSurface<Vector4> {
+    let s = f64::sqrt(0.5);
+    let circle = [
+        (1.0, 0.0, 1.0),
+        (s, s, s),
+        (0.0, 1.0, 1.0),
+        (-s, s, s),
+        (-1.0, 0.0, 1.0),
+        (-s, -s, s),
+        (0.0, -1.0, 1.0),
+        (s, -s, s),
+        (1.0, 0.0, 1.0),
+    ];
+    let control_points = curve
+        .control_points()
+        .iter()
+        .map(|pt| {
+            let weight = pt.w;
+            let pt = Point3::from_homogeneous(*pt);
+            let center = origin + axis.dot(pt - origin) * axis;
+            let (x, y) = (pt - center, axis.cross(pt - center));
+            circle
+                .iter()
+                .map(|&(cx, cy, cw)| (center.to_vec() * cw + x * cx + y * cy).extend(cw) * weight)
+                .collect()
+        })
+        .collect();
+    let vknot_vec = KnotVec::from(vec![
+        0.0, 0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75, 1.0, 1.0, 1.0,
+    ]);
+    let knot_vecs = (curve.knot_vec().clone(), vknot_vec);
+    NurbsSurface::new(BSplineSurface::new(knot_vecs, control_points))
+}
+
+/// Exact NURBS sphere whose poles are in the direction of `axis`, and whose seam is in the direction of `seam`.
+/// The normal vectors of the sphere are directed outward.
+/// `axis` and `seam` must be orthogonal unit vectors.
+pub(super) fn sphere(
+    center: Point3,
+    radius: f64,
+    axis: Vector3,
+    seam: Vector3,
+) -> NurbsSurface<Vector4> {
+    let (n, e) = (axis * radius, seam * radius);
+    let s = f64::sqrt(0.5);
+    let control_points = vec![
+        (center + n).to_homogeneous(),
+        (center + n + e).to_homogeneous() * s,
+        (center + e).to_homogeneous(),
+        (center - n + e).to_homogeneous() * s,
+        (center - n).to_homogeneous(),
+    ];
+    let knot_vec = KnotVec::from(vec![0.0, 0.0, 0.0, 0.5, 0.5, 1.0, 1.0, 1.0]);
+    let semicircle = NurbsCurve::new(BSplineCurve::new(knot_vec, control_points));
+    revolve(&semicircle, center, axis)
+}
+
+/// Converts the geometry of the fillet module to the one of `truck_modeling`.
+/// The curves and surfaces that are not changed by fillet are restored to the original ones.
+#[derive(Clone, Debug, Default)]
+pub(super) struct GeometryTable {
+    curves: Vec<(NurbsCurve<Vector4>, modeling::Curve)>,
+    surfaces: Vec<(NurbsSurface<Vector4>, modeling::Surface)>,
+}
+
+impl GeometryTable {
+    /// Converts `shell` to the fillet geometry, and registers the original geometry.
+    pub(super) fn shell_to_fillet(&mut self, shell: &modeling::Shell) -> Shell {
+        let new_shell = shell.mapped(
+            Point3::clone,
+            |curve| Curve::NurbsCurve(curve_to_nurbs(curve)),
+            surface_to_nurbs,
+        );
+        let edges = shell.edge_iter().zip(new_shell.edge_iter());
+        edges.for_each(|(edge, new_edge)| {
+            if let Curve::NurbsCurve(nurbs) = new_edge.curve() {
+                self.curves.push((nurbs, edge.curve()));
+            }
+        });
+        let faces = shell.face_iter().zip(new_shell.face_iter());
+        faces.for_each(|(face, new_face)| {
+            let (surface, new_surface) = (face.surface(), new_face.surface());
+            self.surfaces
+                .push((new_surface.inverse(), surface.inverse()));
+            self.surfaces.push((new_surface, surface));
+        });
+        new_shell
+    }
+
+    pub(super) fn curve_to_modeling(&self, curve: &Curve) -> Option<modeling::Curve> {
+        match curve {
+            Curve::NurbsCurve(nurbs) => Some(self.nurbs_curve_to_modeling(nurbs)),
+            Curve::PCurve(pcurve) => {
+                let range = pcurve.range_tuple();
+                let bsp = BSplineCurve::quadratic_approximation(pcurve, range, TOLERANCE, 100)?;
+                Some(modeling::Curve::BSplineCurve(bsp))
+            }
+            Curve::IntersectionCurve(curve) => {
+                let leader = curve.leader();
+                let range = leader.range_tuple();
+                let bsp = BSplineCurve::quadratic_approximation(
+                    leader,
+                    range,
+                    APPROXIMATION_TOLERANCE,
+                    100,
+                )?;
+                Some(modeling::Curve::IntersectionCurve(IntersectionCurve::new(
+                    Box::new(self.surface_to_modeling(curve.surface0())),
+                    Box::new(self.surface_to_modeling(curve.surface1())),
+                    Box::new(modeling::Curve::BSplineCurve(bsp)),
+                )))
+            }
+        }
+    }
+
+    fn nurbs_curve_to_modeling(&self, nurbs: &NurbsCurve<Vector4>) -> modeling::Curve {
+        if let Some((_, curve)) = self.curves.iter().find(|(x, _)| x == nurbs) {
+            return curve.clone();
+        }
+        let cpts = nurbs.control_points();
+        let is_line = nurbs.degree() == 1
+            && cpts.len() == 2
+            && cpts[0].w.near(&cpts[1].w)
+            && nurbs.is_clamped();
+        match is_line {
+            true => modeling::Curve::Line(Line(nurbs.front(), nurbs.back())),
+            false => modeling::Curve::NurbsCurve(nurbs.clone()),
+        }
+    }
+
+    pub(super) fn surface_to_modeling(&self, surface: &NurbsSurface<Vector4>) -> modeling::Surface {
+        match self.surfaces.iter().find(|(x, _)| x == surface) {
+            Some((_, surface)) => surface.clone(),
+            None => modeling::Surface::NurbsSurface(surface.clone()),
+        }
+    }
+}
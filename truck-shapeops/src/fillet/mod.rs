@@ -1,15 +1,16 @@
-/***********************************/
-/*** This module is a prototype. ***/
-/***********************************/
-
-#![allow(dead_code)]
-
 use algo::curve::search_closest_parameter;
 use derive_more::*;
 use itertools::Itertools;
+use rustc_hash::FxHashMap as HashMap;
 use std::f64::consts::PI;
 use truck_geometry::prelude::*;
 
+mod corner;
+mod edges;
+pub(crate) mod geometry;
+use corner::CornerBall;
+pub use edges::{fillet_edges, FilletRadius};
+
 #[cfg(test)]
 use truck_meshalgo::prelude::*;
 
@@ -116,7 +117,8 @@ fn circle_arc_by_three_points(
     circle_arc(point0, origin, axis, angle * 2.0, (w0, w1))
 }
 
-fn circum_center(pt0: Point3, pt1: Point3, pt2: Point3) -> Point3 {
+// The center of the circle through the three points.
+pub(crate) fn circum_center(pt0: Point3, pt1: Point3, pt2: Point3) -> Point3 {
     let (vec0, vec1) = (pt1 - pt0, pt2 - pt0);
     let (a2, ab, b2) = (vec0.dot(vec0), vec0.dot(vec1), vec1.dot(vec1));
     let (det, u, v) = (a2 * b2 - ab * ab, a2 * b2 - ab * b2, a2 * b2 - ab * a2);
@@ -225,8 +227,6 @@ fn composite_line_bezier(
 
 #[derive(Clone, Copy, Debug)]
 struct RelaySphere {
-    // center of fillet circle
-    center: Point3,
     // contact point of sphere and surface0, 3d coordinate and parameter
     contact0: (Point3, Point2),
     // contact point of sphere and surface1, 3d coordinate and parameter
@@ -263,7 +263,7 @@ impl RelaySphere {
     }
 
     fn next_point(
-        surface: &(impl ParametricSurface3D + SearchParameter<D2, Point = Point3>),
+        surface: &impl ParametricSurface3D,
         (u, v): (f64, f64),
         (p, q): (Point3, Point3),
     ) -> (Point3, (f64, f64)) {
@@ -282,14 +282,15 @@ impl RelaySphere {
 
     fn generate(
         point_on_curve: (Point3, Vector3),
-        surface0: &(impl ParametricSurface3D + SearchParameter<D2, Point = Point3>),
-        surface1: &(impl ParametricSurface3D + SearchParameter<D2, Point = Point3>),
+        surface0: &(impl ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>),
+        surface1: &(impl ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>),
         radius: f64,
     ) -> Option<Self> {
         let (p, der) = point_on_curve;
         let (mut p0, mut p1) = (p, p);
-        let (mut u0, mut v0) = surface0.search_parameter(p0, None, 10)?;
-        let (mut u1, mut v1) = surface1.search_parameter(p1, None, 10)?;
+        // The point may be slightly off the surfaces if the curve is an approximation.
+        let (mut u0, mut v0) = surface0.search_nearest_parameter(p0, None, 100)?;
+        let (mut u1, mut v1) = surface1.search_nearest_parameter(p1, None, 100)?;
         let mut center = Point3::origin();
         for _ in 0..100 {
             let (n0, n1) = (surface0.normal(u0, v0), surface1.normal(u1, v1));
@@ -303,20 +304,11 @@ impl RelaySphere {
             }
         }
         Some(Self {
-            center,
             contact0: (p0, (u0, v0).into()),
             contact1: (p1, (u1, v1).into()),
             transit: center + radius * (p - center).normalize(),
         })
     }
-
-    fn fillet_wire(self) -> NurbsCurve<Vector4> {
-        circle_arc_by_three_points(
-            self.contact0.0.to_homogeneous(),
-            self.contact1.0.to_homogeneous(),
-            self.transit,
-        )
-    }
 }
 
 fn relay_spheres(
@@ -325,30 +317,29 @@ fn relay_spheres(
     curve: &impl FilletCurve,
     division: usize,
     radius: impl Fn(f64) -> f64,
-    extend: bool,
+    (a0, a1): (f64, f64),
+    (extend0, extend1): (bool, bool),
 ) -> Option<Vec<RelaySphere>> {
     let (t0, t1) = curve.range_tuple();
-    let generator = move |i: isize| {
-        let a = i as f64 / division as f64;
+    let generator = |a: f64| {
         let t = (1.0 - a) * t0 + a * t1;
         RelaySphere::generate((curve.subs(t), curve.der(t)), surface0, surface1, radius(a))
     };
-    let range = match extend {
-        true => -1..=(division as isize + 1),
-        false => 0..=division as isize,
+    // The extended parts must be long enough to cross the edges adjacent to the ends.
+    let extension = |a: f64| {
+        let length = curve.der((1.0 - a) * t0 + a * t1).magnitude() * (t1 - t0);
+        f64::max(1.0 / division as f64, 2.0 * radius(a) / length)
     };
-    range.map(generator).collect()
-}
-
-fn almost_fillet_patch(rs0: RelaySphere, rs1: RelaySphere) -> BSplineSurface<Vector4> {
-    let nurbs0 = rs0.fillet_wire();
-    let nurbs1 = rs1.fillet_wire();
-    let knot_vecs = (KnotVec::bezier_knot(1), nurbs0.knot_vec().clone());
-    let control_points = vec![
-        nurbs0.control_points().clone(),
-        nurbs1.control_points().clone(),
-    ];
-    BSplineSurface::new(knot_vecs, control_points)
+    let mut params = (0..=division)
+        .map(|i| a0 + (a1 - a0) * i as f64 / division as f64)
+        .collect::<Vec<_>>();
+    if extend0 {
+        params.insert(0, a0 - extension(a0));
+    }
+    if extend1 {
+        params.push(a1 + extension(a1));
+    }
+    params.into_iter().map(generator).collect()
 }
 
 fn expand_fillet(
@@ -410,19 +401,6 @@ fn expand_fillet(
     NurbsSurface::new(bsp_surface)
 }
 
-// Orientation of `curve` should be compatible with orientation of `surface0`.
-fn rolling_ball_fillet_surface(
-    surface0: &NurbsSurface<Vector4>,
-    surface1: &NurbsSurface<Vector4>,
-    curve: &impl FilletCurve,
-    division: usize,
-    radius: impl Fn(f64) -> f64,
-    extend: bool,
-) -> Option<NurbsSurface<Vector4>> {
-    let relay_spheres = relay_spheres(surface0, surface1, curve, division, radius, extend)?;
-    Some(expand_fillet(&relay_spheres, surface0, surface1))
-}
-
 fn find_adjacent_edge(face: &Face, edge_id: EdgeID) -> Option<(Edge, Edge)> {
     face.boundary_iters()
         .into_iter()
@@ -431,555 +409,575 @@ fn find_adjacent_edge(face: &Face, edge_id: EdgeID) -> Option<(Edge, Edge)> {
         .map(|(x, _, y)| (x, y))
 }
 
-fn take_ori<T>(ori: bool, (a, b): (T, T)) -> T {
-    match ori {
-        true => a,
-        false => b,
+// Indices of the faces adjacent to `edge`.
+// The first face contains `edge` itself, and the second one contains the inverse of `edge`.
+fn adjacent_faces(shell: &Shell, edge: &Edge) -> Option<(usize, usize)> {
+    let find = |edge: Edge| {
+        shell
+            .iter()
+            .position(|face| face.edge_iter().any(|e| e == edge))
+    };
+    match (find(edge.clone())?, find(edge.inverse())?) {
+        (face0, face1) if face0 != face1 => Some((face0, face1)),
+        _ => None,
     }
 }
 
-fn cut_face_by_bezier(
-    face: &Face,
-    mut bezier: NurbsCurve<Vector4>,
-    filleted_edge_id: EdgeID,
-) -> Option<(Face, Edge)> {
-    let (front_edge, back_edge) = find_adjacent_edge(face, filleted_edge_id)?;
-
-    let new_front_edge = {
-        let curve = front_edge.curve();
-        let hint = algo::curve::presearch_closest_point(
-            &bezier,
-            &curve,
-            (bezier.range_tuple(), curve.range_tuple()),
-            10,
-        );
-        let (t0, t1) = search_closest_parameter(&bezier, &curve, hint, 100)?;
-        let v0 = Vertex::new(bezier.subs(t0));
-        bezier = bezier.cut(t0);
-        front_edge.not_strictly_cut_with_parameter(&v0, t1)?.0
-    };
+fn faces_around_vertex(shell: &Shell, vertex: &Vertex) -> Vec<usize> {
+    let contains = |face: &Face| face.vertex_iter().any(|v| v == *vertex);
+    let iter = shell.iter().enumerate();
+    iter.filter(|(_, face)| contains(face))
+        .map(|(i, _)| i)
+        .collect()
+}
 
-    let new_back_edge = {
-        let curve = back_edge.curve();
-        let hint = algo::curve::presearch_closest_point(
-            &bezier,
-            &curve,
-            (bezier.range_tuple(), curve.range_tuple()),
-            10,
-        );
-        let (t0, t1) = search_closest_parameter(&bezier, &curve, hint, 100)?;
-        let v1 = Vertex::new(bezier.subs(t0));
-        bezier.cut(t0);
-        back_edge.not_strictly_cut_with_parameter(&v1, t1)?.1
-    };
+// The number of the divisions of the edges at which the arc lengths are sampled.
+const ARC_LENGTH_DIVISION: usize = 32;
 
-    let fillet_edge = Edge::new(new_front_edge.back(), new_back_edge.front(), bezier.into());
-    let new_boundaries = face
-        .absolute_boundaries()
-        .iter()
-        .cloned()
-        .map(|mut boundary| {
-            if let Some(idx) = boundary.iter().position(|edge0| edge0.is_same(&front_edge)) {
-                let len = boundary.len();
-                if face.orientation() {
-                    boundary[idx] = new_front_edge.clone();
-                    boundary[(idx + 1) % len] = fillet_edge.clone();
-                    boundary[(idx + 2) % len] = new_back_edge.clone();
-                } else {
-                    boundary[(len + idx - 2) % len] = new_back_edge.inverse();
-                    boundary[(len + idx - 1) % len] = fillet_edge.inverse();
-                    boundary[idx] = new_front_edge.inverse();
-                }
-            }
-            boundary
-        })
+// The arc lengths from the front of the curve at the uniformly divided parameters.
+fn arc_lengths(curve: &impl FilletCurve) -> Vec<f64> {
+    let (t0, t1) = curve.range_tuple();
+    let points = (0..=ARC_LENGTH_DIVISION)
+        .map(|i| curve.subs(t0 + (t1 - t0) * i as f64 / ARC_LENGTH_DIVISION as f64))
         .collect::<Vec<_>>();
-    let mut new_face = Face::new(new_boundaries, face.surface());
-    if !face.orientation() {
-        new_face.invert();
-    }
-    Some((new_face, fillet_edge))
+    let mut lengths = vec![0.0];
+    points.windows(2).for_each(|p| {
+        let last = lengths[lengths.len() - 1];
+        lengths.push(last + p[0].distance(p[1]));
+    });
+    lengths
 }
 
-fn create_pcurve_edge(
-    (v0, hint0): (&Vertex, (f64, f64)),
-    (v1, hint1): (&Vertex, (f64, f64)),
-    fillet_surface: &NurbsSurface<Vector4>,
-) -> Option<Edge> {
-    let uv0 = fillet_surface.search_parameter(v0.point(), hint0, 100)?;
-    let uv1 = fillet_surface.search_parameter(v1.point(), hint1, 100)?;
-    let curve = PCurve::new(Line(uv0.into(), uv1.into()), fillet_surface.clone());
-    Some(Edge::new(v0, v1, curve.into()))
+// The arc length at the normalized parameter `t`, interpolating `lengths` linearly.
+fn arc_length_at(lengths: &[f64], t: f64) -> f64 {
+    let x = f64::clamp(t, 0.0, 1.0) * ARC_LENGTH_DIVISION as f64;
+    let i = usize::min(x as usize, ARC_LENGTH_DIVISION - 1);
+    lengths[i] + (lengths[i + 1] - lengths[i]) * (x - i as f64)
 }
 
-fn simple_fillet(
-    face0: &Face,
-    face1: &Face,
-    filleted_edge_id: EdgeID,
+fn fillet_surfaces_along_wire(
+    shell: &Shell,
+    wire: &Wire,
+    adjacent_faces: &[(usize, usize)],
     radius: impl Fn(f64) -> f64,
     fillet_division: usize,
-) -> Option<(Face, Face, Face)> {
-    let is_filleted_edge = move |edge: &Edge| edge.id() == filleted_edge_id;
-    let filleted_edge = face0.edge_iter().find(is_filleted_edge)?;
-
-    let fillet_surface = {
-        let surface0 = face0.oriented_surface();
-        let surface1 = face1.oriented_surface();
-        let curve = filleted_edge.oriented_curve();
-        rolling_ball_fillet_surface(&surface0, &surface1, &curve, fillet_division, radius, true)?
-    };
-
-    let (new_face0, fillet_edge0) = {
-        let bezier = fillet_surface.column_curve(0);
-        cut_face_by_bezier(face0, bezier, filleted_edge.id())?
-    };
-    let (new_face1, fillet_edge1) = {
-        let bezier = fillet_surface.column_curve(fillet_surface.control_points().len() - 1);
-        cut_face_by_bezier(face1, bezier.inverse(), filleted_edge.id())?
-    };
-
-    let ((v0, v1), (v2, v3)) = (fillet_edge0.ends(), fillet_edge1.ends());
-    let edge0 = create_pcurve_edge((v0, (0.0, 0.0)), (v3, (1.0, 0.0)), &fillet_surface)?;
-    let edge1 = create_pcurve_edge((v2, (1.0, 1.0)), (v1, (0.0, 1.0)), &fillet_surface)?;
-    let fillet = {
-        let fillet_boundary = [fillet_edge0.inverse(), edge0, fillet_edge1.inverse(), edge1];
-        Face::new(vec![fillet_boundary.into()], fillet_surface)
-    };
-
-    Some((new_face0, new_face1, fillet))
-}
-
-fn create_new_side(
-    side: &Face,
-    fillet_edge: &Edge,
-    corner_vertex_id: VertexID,
-    left_face_front_edge: &Edge,
-    right_face_back_edge: &Edge,
-) -> Option<Face> {
-    let (boundary_idx, edge_idx) = side.boundary_iters().into_iter().enumerate().find_map(
-        |(boundary_idx, boundary_iter)| {
-            boundary_iter
-                .enumerate()
-                .find(|(_, edge)| edge.back().id() == corner_vertex_id)
-                .map(move |(edge_idx, _)| (boundary_idx, edge_idx))
-        },
-    )?;
-    let new_boundaries = side
-        .absolute_boundaries()
+    (end0, end1): (Option<f64>, Option<f64>),
+) -> Option<Vec<NurbsSurface<Vector4>>> {
+    let (len, closed) = (wire.len(), wire.is_cyclic());
+    // The parameter of the radius is the arc length along the wire normalized to `[0, 1]`.
+    let arc_lengths = wire
+        .edge_iter()
+        .map(|edge| arc_lengths(&edge.oriented_curve()))
+        .collect::<Vec<_>>();
+    let offsets = arc_lengths
         .iter()
-        .enumerate()
-        .map(|(idx, boundary)| {
-            let mut new_boundary = boundary.clone();
-            if idx == boundary_idx {
-                let len = new_boundary.len();
-                if side.orientation() {
-                    new_boundary[edge_idx] = right_face_back_edge.inverse();
-                    new_boundary[(edge_idx + 1) % len] = left_face_front_edge.inverse();
-                    new_boundary.insert(edge_idx + 1, fillet_edge.inverse());
-                } else {
-                    new_boundary[len - edge_idx - 1] = right_face_back_edge.clone();
-                    new_boundary[(2 * len - edge_idx - 2) % len] = left_face_front_edge.clone();
-                    new_boundary.insert(len - edge_idx, fillet_edge.clone());
-                }
-            }
-            new_boundary
+        .scan(0.0, |sum, lengths| {
+            let offset = *sum;
+            *sum += lengths[ARC_LENGTH_DIVISION];
+            Some(offset)
         })
-        .collect();
-    let side_surface = Box::new(side.oriented_surface());
-    let Curve::PCurve(fillet_curve) = fillet_edge.curve() else {
-        return None;
-    };
-    let fillet_surface = Box::new(fillet_curve.surface().clone());
-    let new_curve = IntersectionCurve::new(side_surface, fillet_surface, fillet_curve);
-    fillet_edge.set_curve(new_curve.into());
-    let mut new_face = Face::new(new_boundaries, side.surface());
-    if !side.orientation() {
-        new_face.invert();
-    }
-    Some(new_face)
+        .collect::<Vec<_>>();
+    let total = offsets[len - 1] + arc_lengths[len - 1][ARC_LENGTH_DIVISION];
+    let create_fillet_surface =
+        |(i, (edge, &(face0, face1))): (usize, (&Edge, &(usize, usize)))| {
+            let surface0 = &shell[face0].oriented_surface();
+            let surface1 = &shell[face1].oriented_surface();
+            let curve = &edge.oriented_curve();
+            let radius = |t: f64| {
+                let s = offsets[i] + arc_length_at(&arc_lengths[i], t);
+                radius(f64::clamp(s / total, 0.0, 1.0))
+            };
+            let (first, last) = (!closed && i == 0, !closed && i + 1 == len);
+            // The fillet ends at the rolling ball of the corner, or is extended to cross the side face.
+            let (a0, extend0) = match (first, end0) {
+                (true, Some(a0)) => (a0, false),
+                (first, _) => (0.0, first),
+            };
+            let (a1, extend1) = match (last, end1) {
+                (true, Some(a1)) => (a1, false),
+                (last, _) => (1.0, last),
+            };
+            let mut relay_spheres = relay_spheres(
+                surface0,
+                surface1,
+                curve,
+                fillet_division,
+                radius,
+                (a0, a1),
+                (extend0, extend1),
+            )?;
+            // The fillet surface is only continuous at the knots. Avoid knots at the ends of the wire
+            // so that the intersection curves with the side faces can be searched stably.
+            if extend0 {
+                relay_spheres.remove(1);
+            }
+            if extend1 {
+                relay_spheres.remove(relay_spheres.len() - 2);
+            }
+            Some(expand_fillet(&relay_spheres, surface0, surface1))
+        };
+    let mut fillet_surfaces = wire
+        .edge_iter()
+        .zip(adjacent_faces)
+        .enumerate()
+        .map(create_fillet_surface)
+        .collect::<Option<Vec<_>>>()?;
+
+    let junctions = if closed { len } else { len - 1 };
+    (0..junctions).for_each(|i| {
+        let j = (i + 1) % len;
+        (0..fillet_surfaces[i].control_points().len()).for_each(|k| {
+            let last = fillet_surfaces[i].control_points()[k].len() - 1;
+            let p = *fillet_surfaces[i].control_point(k, last);
+            let q = *fillet_surfaces[j].control_point(k, 0);
+            let c = (p + q) / 2.0;
+            *fillet_surfaces[i].control_point_mut(k, last) = c;
+            *fillet_surfaces[j].control_point_mut(k, 0) = c;
+        });
+    });
+    Some(fillet_surfaces)
 }
 
-#[allow(clippy::type_complexity)]
-fn fillet_with_side(
-    face0: &Face,
-    face1: &Face,
-    filleted_edge_id: EdgeID,
-    side0: Option<&Face>,
-    side1: Option<&Face>,
-    radius: impl Fn(f64) -> f64,
-    fillet_division: usize,
-) -> Option<(Face, Face, Face, Option<Face>, Option<Face>)> {
-    let (new_face0, new_face1, fillet) =
-        simple_fillet(face0, face1, filleted_edge_id, radius, fillet_division)?;
-
-    let (front_edge0, back_edge0) = {
-        let fillet_edge_id = fillet.absolute_boundaries()[0][0].id();
-        find_adjacent_edge(&new_face0, fillet_edge_id)?
+// The parameters of `bezier` and the curve of `edge` at which `bezier` crosses `edge`.
+// Only the front half (or the back half) of `bezier` is searched.
+fn crossing_parameter(
+    bezier: &NurbsCurve<Vector4>,
+    edge: &Edge,
+    front_half: bool,
+) -> Option<(f64, f64)> {
+    // The second derivation of the intersection curve is not implemented.
+    let curve = match edge.curve() {
+        Curve::IntersectionCurve(curve) => {
+            let range = curve.range_tuple();
+            let bsp = BSplineCurve::quadratic_approximation(&curve, range, TOLERANCE, 100)?;
+            NurbsCurve::from(bsp).into()
+        }
+        curve => curve,
     };
-    let (front_edge1, back_edge1) = {
-        let fillet_edge_id = fillet.absolute_boundaries()[0][2].id();
-        find_adjacent_edge(&new_face1, fillet_edge_id)?
+    let (t0, t1) = bezier.range_tuple();
+    let range = match front_half {
+        true => (t0, (t0 + t1) / 2.0),
+        false => ((t0 + t1) / 2.0, t1),
     };
-
-    let is_filleted_edge = |edge: &Edge| edge.id() == filleted_edge_id;
-    let filleted_edge = face0.edge_iter().find(is_filleted_edge)?;
-    let (v0, v1) = filleted_edge.ends();
-
-    let new_side0 = side0.and_then(|side0| {
-        let fillet_edge = &fillet.absolute_boundaries()[0][1];
-        create_new_side(side0, fillet_edge, v0.id(), &front_edge0, &back_edge1)
-    });
-    let new_side1 = side1.and_then(|side1| {
-        let fillet_edge = &fillet.absolute_boundaries()[0][3];
-        create_new_side(side1, fillet_edge, v1.id(), &front_edge1, &back_edge0)
-    });
-    Some((new_face0, new_face1, fillet, new_side0, new_side1))
-}
-
-#[derive(Clone, Copy, Debug)]
-struct FaceBoundaryEdgeIndex {
-    face_index: usize,
-    boundary_index: usize,
-    edge_index: usize,
+    let hint =
+        algo::curve::presearch_closest_point(bezier, &curve, (range, curve.range_tuple()), 10);
+    search_closest_parameter(bezier, &curve, hint, 100)
 }
 
-impl From<(usize, usize, usize)> for FaceBoundaryEdgeIndex {
-    fn from((face_index, boundary_index, edge_index): (usize, usize, usize)) -> Self {
-        Self {
-            face_index,
-            boundary_index,
-            edge_index,
-        }
+fn trim_bezier(mut bezier: NurbsCurve<Vector4>, (t0, t1): (f64, f64)) -> NurbsCurve<Vector4> {
+    let (s0, s1) = bezier.range_tuple();
+    if t1 < s1 - TOLERANCE {
+        bezier.cut(t1);
     }
+    if t0 > s0 + TOLERANCE {
+        bezier = bezier.cut(t0);
+    }
+    bezier
 }
 
-fn find_shared_face_with_front_edge(shell: &Shell, wire: &Wire) -> Option<FaceBoundaryEdgeIndex> {
-    shell.iter().enumerate().find_map(|(face_idx, face)| {
-        let mut boundary_iter = face.boundary_iters().into_iter().enumerate();
-        boundary_iter.find_map(|(boundary_idx, boundary_iter)| {
-            let mut edge_iter = boundary_iter.circular_tuple_windows().enumerate();
-            edge_iter.find_map(|(edge_idx, (edge0, edge1))| {
-                match edge0.is_same(&wire[0]) && edge1.is_same(&wire[1]) {
-                    true => Some((face_idx, boundary_idx, edge_idx).into()),
-                    false => None,
-                }
-            })
-        })
-    })
-}
-
-fn enumerate_adjacent_faces(
-    shell: &Shell,
-    wire: &Wire,
-    shared_face: FaceBoundaryEdgeIndex,
-) -> Option<Vec<FaceBoundaryEdgeIndex>> {
-    let iter = wire.edge_iter().map(|edge0| {
-        shell.iter().enumerate().find_map(|(face_idx, face)| {
-            if face_idx == shared_face.face_index {
-                return None;
-            }
-            let mut boundary_iter = face.boundary_iters().into_iter().enumerate();
-            boundary_iter.find_map(|(boundary_idx, boundary_iter)| {
-                let mut edge_iter = boundary_iter.enumerate();
-                edge_iter.find_map(|(edge_idx, edge)| match edge.is_same(edge0) {
-                    true => Some((face_idx, boundary_idx, edge_idx).into()),
-                    false => None,
-                })
-            })
-        })
-    });
-    iter.collect()
-}
+const SWALLOWING_TOLERANCE: f64 = 1.0e-3;
 
-fn fillet_surfaces_along_wire(
-    shell: &Shell,
-    wire: &Wire,
-    shared_face_index: FaceBoundaryEdgeIndex,
-    adjacent_faces: &[FaceBoundaryEdgeIndex],
-    radius: impl Fn(f64) -> f64,
-    fillet_division: usize,
-) -> Option<Vec<NurbsSurface<Vector4>>> {
-    let wire_faces_iter = wire.edge_iter().zip(adjacent_faces);
-    let create_fillet_surface = move |(edge, face_index): (&Edge, &FaceBoundaryEdgeIndex)| {
-        let surface0 = &shell[shared_face_index.face_index].oriented_surface();
-        let surface1 = &shell[face_index.face_index].oriented_surface();
-        let curve = &edge.oriented_curve();
-        let first_wire = edge.id() == wire.front_edge().unwrap().id();
-        let last_wire = edge.id() == wire.back_edge().unwrap().id();
-        let extend = first_wire || last_wire;
-        let mut relay_spheres =
-            relay_spheres(surface0, surface1, curve, fillet_division, &radius, extend)?;
-        if first_wire {
-            relay_spheres.pop();
-        }
-        if last_wire {
-            relay_spheres.remove(0);
+fn bridge_edge(edges: &[Edge], v0: &Vertex, v1: &Vertex) -> Option<Edge> {
+    edges.iter().find_map(|edge| {
+        if edge.front() == v0 && edge.back() == v1 {
+            Some(edge.clone())
+        } else if edge.back() == v0 && edge.front() == v1 {
+            Some(edge.inverse())
+        } else {
+            None
         }
-        Some(expand_fillet(&relay_spheres, surface0, surface1))
-    };
-    wire_faces_iter.map(create_fillet_surface).collect()
-}
-
-fn concat_fillet_surface(surfaces: &[NurbsSurface<Vector4>]) -> NurbsSurface<Vector4> {
-    let concat_beziers = |i: usize| {
-        let mut collector = CurveCollector::<NurbsCurve<Vector4>>::Singleton;
-        (0..surfaces.len()).for_each(|n| {
-            let mut curve = surfaces[n].column_curve(i);
-            curve.knot_translate(n as f64);
-            collector.concat(&curve);
-        });
-        collector.unwrap()
-    };
-    let len = surfaces[0].control_points().len();
-    let long_beziers = (0..len).map(concat_beziers).collect::<Vec<_>>();
-
-    let uknot_vec = unit_circle_knot_vec();
-    let vknot_vec = long_beziers[0].knot_vec().clone();
-    let destruct_bezier = |bezier: NurbsCurve<Vector4>| bezier.into_non_rationalized().destruct().1;
-    let control_points = long_beziers.into_iter().map(destruct_bezier).collect();
-    NurbsSurface::new(BSplineSurface::new((uknot_vec, vknot_vec), control_points))
-}
-
-fn create_free_edge(curve: Curve) -> Edge {
-    let v0 = Vertex::new(curve.front());
-    let v1 = Vertex::new(curve.back());
-    Edge::new(&v0, &v1, curve)
+    })
 }
 
-fn cut_face_by_last_bezier(
-    shell: &mut Shell,
-    face_index: FaceBoundaryEdgeIndex,
-    fillet_surface: &NurbsSurface<Vector4>,
-) -> Option<Edge> {
-    let len = fillet_surface.control_points().len();
-    let last_long_bezier = fillet_surface.column_curve(len - 1);
-    let face = &shell[face_index.face_index];
-    let filleted_edge = &face.boundaries()[face_index.boundary_index][face_index.edge_index];
-    let (trimmed_face, edge1) =
-        cut_face_by_bezier(face, last_long_bezier.inverse(), filleted_edge.id())?;
-    shell[face_index.face_index] = trimmed_face;
-    Some(edge1)
+fn rebuild_face(face: &Face, boundaries: Vec<Wire>) -> Option<Face> {
+    match face.orientation() {
+        true => Face::try_new(boundaries, face.surface()).ok(),
+        false => {
+            let boundaries = boundaries.iter().map(Wire::inverse).collect();
+            let mut new_face = Face::try_new(boundaries, face.surface()).ok()?;
+            new_face.invert();
+            Some(new_face)
+        }
+    }
 }
 
-fn fillet_along_wire(
+/// Fillets the edges of `wire` by one sequence of fillet faces.
+///
+/// The faces on the left side of the edges of `wire` are regarded as `face0`, and the ones on the right side are
+/// regarded as `face1`. The edges adjacent to the ends of the wire are trimmed, and the new boundaries of the faces
+/// around the ends are connected by the intersection curves of the fillet. The ends of `wire` at the vertices of
+/// `corners` are finished by the rolling balls of the corners instead.
+///
+/// The faces around the corners are connected to the corners by the temporary bridges, which are removed when
+/// all the fillets meeting at the corners are created.
+fn fillet_along_wire_with_corners(
     shell: &mut Shell,
     wire: &Wire,
     radius: impl Fn(f64) -> f64,
     fillet_division: usize,
+    corners: &mut [CornerBall],
 ) -> Option<()> {
-    if !radius(0.0).near2(&radius(1.0)) {
+    if wire.is_empty() || !wire.is_continuous() {
         return None;
     }
-    if !wire.is_continuous() {
-        eprintln!("fillet_along_wire failure: Wire must be continuous.");
-        return None;
-    }
-    if wire.is_closed() {
-        eprintln!("fillet_along_wire failure: Closed wire case is not implemented.");
-        return None;
-    }
-
-    let shared_face_index = find_shared_face_with_front_edge(shell, wire)?;
-    let adjacent_faces = enumerate_adjacent_faces(shell, wire, shared_face_index)?;
-
-    let mut fillet_surfaces = fillet_surfaces_along_wire(
-        shell,
-        wire,
-        shared_face_index,
-        &adjacent_faces,
-        radius,
-        fillet_division,
-    )?;
-
-    (1..fillet_surfaces.len()).for_each(|i| {
-        let len = fillet_surfaces[i].control_points().len();
-        (0..len).for_each(|j| {
-            let len = fillet_surfaces[i - 1].control_points()[j].len();
-            let p = *fillet_surfaces[i - 1].control_point(j, len - 1);
-            let q = *fillet_surfaces[i].control_point(j, 0);
-            let c = (p + q) / 2.0;
-            *fillet_surfaces[i - 1].control_point_mut(j, len - 1) = c;
-            *fillet_surfaces[i].control_point_mut(j, 0) = c;
-        });
-    });
-
-    type CffTuple<'a> = (&'a [NurbsSurface<Vector4>], &'a FaceBoundaryEdgeIndex);
-    let create_fillet_face = |(surfaces, face_index): CffTuple<'_>| {
-        let fillet_surface = concat_fillet_surface(surfaces);
-        let edge0 = create_free_edge(surfaces[1].column_curve(0).into());
-
-        let edge1 = cut_face_by_last_bezier(shell, *face_index, &fillet_surface)?;
+    let (len, closed) = (wire.len(), wire.is_cyclic());
+    let faces = wire
+        .edge_iter()
+        .map(|edge| adjacent_faces(shell, edge))
+        .collect::<Option<Vec<_>>>()?;
+
+    // the indices of the corners at the ends of the wire, and the parameters of the edges at the rolling balls.
+    let corner_at = |vertex: &Vertex| match closed {
+        true => None,
+        false => corners.iter().position(|corner| corner.vertex() == vertex),
+    };
+    let end_corners = [corner_at(wire[0].front()), corner_at(wire[len - 1].back())];
+    let corner_parameter = |edge: &Edge, corner: Option<usize>| match corner {
+        Some(corner) => {
+            let curve = edge.oriented_curve();
+            let (t0, t1) = curve.range_tuple();
+            let t = curve.search_nearest_parameter(corners[corner].center(), None, 100)?;
+            Some(Some((t - t0) / (t1 - t0)))
+        }
+        None => Some(None),
+    };
+    let ends = (
+        corner_parameter(&wire[0], end_corners[0])?,
+        corner_parameter(&wire[len - 1], end_corners[1])?,
+    );
+    let fillet_surfaces =
+        fillet_surfaces_along_wire(shell, wire, &faces, radius, fillet_division, ends)?;
 
-        let edge2 = {
-            let (v0, v1) = (edge0.front(), edge1.back());
-            let (u, v) = fillet_surface.search_parameter(v1.point(), (1.0, 1.0), 100)?;
-            let param_line = Line((0.0, 1.0).into(), (u, v).into());
-            let pcurve = PCurveLns::new(param_line, fillet_surface.clone());
-            Edge::new(v0, v1, pcurve.into())
-        };
+    let last_column = number_of_cpts_of_unit_circle() - 1;
+    let beziers0 = fillet_surfaces
+        .iter()
+        .map(|surface| surface.column_curve(0));
+    let beziers1 = fillet_surfaces
+        .iter()
+        .map(|surface| surface.column_curve(last_column));
+    let mut ranges0 = beziers0
+        .clone()
+        .map(|bezier| bezier.range_tuple())
+        .collect::<Vec<_>>();
+    let mut ranges1 = beziers1
+        .clone()
+        .map(|bezier| bezier.range_tuple())
+        .collect::<Vec<_>>();
 
-        let edge3 = {
-            let (v0, v1) = (edge0.back(), edge1.front());
-            let (u, v) = fillet_surface.search_parameter(v1.point(), (1.0, 2.0), 100)?;
-            let param_line = Line((0.0, 2.0).into(), (u, v).into());
-            let pcurve = PCurveLns::new(param_line, fillet_surface.clone());
-            Edge::new(v0, v1, pcurve.into())
-        };
+    // edges adjacent to the ends of the wire except for the corners:
+    // (index of the end vertex, edge in face0, edge in face1, end vertex),
+    // with the parameters of the edges at which the fillet crosses.
+    let mut end_edges = Vec::new();
+    if !closed {
+        for (front, corner) in [true, false].into_iter().zip(end_corners) {
+            if corner.is_some() {
+                continue;
+            }
+            let (i, k, vertex) = match front {
+                true => (0, 0, wire[0].front()),
+                false => (len - 1, len, wire[len - 1].back()),
+            };
+            let (face0, face1) = faces[i];
+            let (prev_edge0, next_edge0) = find_adjacent_edge(&shell[face0], wire[i].id())?;
+            let (prev_edge1, next_edge1) = find_adjacent_edge(&shell[face1], wire[i].id())?;
+            let (edge0, edge1) = match front {
+                true => (prev_edge0, next_edge1),
+                false => (next_edge0, prev_edge1),
+            };
+            let (t0, s0) = crossing_parameter(&fillet_surfaces[i].column_curve(0), &edge0, front)?;
+            let (t1, s1) =
+                crossing_parameter(&fillet_surfaces[i].column_curve(last_column), &edge1, front)?;
+            match front {
+                true => (ranges0[i].0, ranges1[i].0) = (t0, t1),
+                false => (ranges0[i].1, ranges1[i].1) = (t0, t1),
+            }
+            end_edges.push((k, (edge0, s0), (edge1, s1), vertex.clone()));
+        }
+        if end_edges
+            .iter()
+            .any(|(_, (edge0, _), (edge1, _), _)| edge0.is_same(edge1))
+        {
+            return None;
+        }
+    }
 
-        let boundary = [edge0.inverse(), edge2, edge1.inverse(), edge3.inverse()].into();
-        Some(Face::new(vec![boundary], fillet_surface))
+    let beziers0 = beziers0
+        .zip(&ranges0)
+        .map(|(bezier, range)| trim_bezier(bezier, *range));
+    let beziers1 = beziers1
+        .zip(&ranges1)
+        .map(|(bezier, range)| trim_bezier(bezier, *range));
+    let beziers0 = beziers0.collect::<Vec<_>>();
+    let beziers1 = beziers1.collect::<Vec<_>>();
+    let create_vertices = |beziers: &[NurbsCurve<Vector4>]| {
+        let mut vertices = beziers
+            .iter()
+            .map(|bezier| Vertex::new(bezier.front()))
+            .collect::<Vec<_>>();
+        match closed {
+            true => vertices.push(vertices[0].clone()),
+            false => vertices.push(Vertex::new(beziers[len - 1].back())),
+        }
+        vertices
     };
+    let (mut vertices0, mut vertices1) = (create_vertices(&beziers0), create_vertices(&beziers1));
+
+    // The fillet ends at the contact points of the rolling ball at the corner.
+    for (k, corner) in [0, len].into_iter().zip(end_corners) {
+        if let Some(corner) = corner {
+            vertices0[k] = corners[corner].contact(vertices0[k].point()).clone();
+            vertices1[k] = corners[corner].contact(vertices1[k].point()).clone();
+            if vertices0[k] == vertices1[k] {
+                return None;
+            }
+        }
+    }
 
-    let mut fillet_faces = fillet_surfaces
-        .windows(3)
-        .zip(adjacent_faces.iter().skip(1))
-        .map(create_fillet_face)
-        .collect::<Option<Shell>>()?;
-
-    let first_fillet = {
-        let fillet_surface = concat_fillet_surface(&fillet_surfaces[0..=1]);
-
-        let (front_edge, _) =
-            find_adjacent_edge(&shell[shared_face_index.face_index], wire[0].id())?;
-
-        let edge0 = {
-            let mut bezier = fillet_surfaces[0].column_curve(0);
-            let curve = front_edge.oriented_curve();
-            let (t0, _) = search_closest_parameter(&bezier, &curve, (0.0, 1.0), 100)?;
-            bezier = bezier.cut(t0);
-            let v0 = Vertex::new(bezier.front());
-            let v1 = Vertex::new(bezier.back());
-            Edge::new(&v0, &v1, bezier.into())
-        };
-
-        let edge1 = cut_face_by_last_bezier(shell, adjacent_faces[0], &fillet_surface)?;
+    // If the fillet crosses an adjacent edge at the opposite end of the corner, the edge is removed.
+    let mut removed_edges = Vec::new();
+    for (k, (edge0, _), (edge1, _), vertex) in &end_edges {
+        for (edge, new_vertex) in [(edge0, &mut vertices0[*k]), (edge1, &mut vertices1[*k])] {
+            let far = match edge.front() == vertex {
+                true => edge.back(),
+                false => edge.front(),
+            };
+            if far.point().distance(new_vertex.point()) < SWALLOWING_TOLERANCE {
+                *new_vertex = far.clone();
+                removed_edges.push(edge.id());
+            }
+        }
+    }
+    let contact_edges0 = beziers0
+        .into_iter()
+        .enumerate()
+        .map(|(i, bezier)| Edge::new(&vertices0[i], &vertices0[i + 1], bezier.into()))
+        .collect::<Vec<_>>();
+    let contact_edges1 = beziers1
+        .into_iter()
+        .enumerate()
+        .map(|(i, bezier)| Edge::new(&vertices1[i], &vertices1[i + 1], bezier.into()))
+        .collect::<Vec<_>>();
 
-        let edge2 = {
-            let (v0, v1) = (edge0.front(), edge1.back());
-            let t0 = edge0.curve().range_tuple().0;
-            let (u, v) = fillet_surface.search_parameter(v1.point(), (1.0, 0.0), 100)?;
-            let param_line = Line((0.0, t0).into(), (u, v).into());
-            let pcurve = PCurveLns::new(param_line, fillet_surface.clone());
-            Edge::new(v0, v1, pcurve.into())
-        };
+    // the edges crossing the fillet from face0 to face1
+    let mut end_arcs = Vec::new();
+    let mut cross_edges = (0..=len)
+        .map(|k| {
+            let (v0, v1) = (&vertices0[k], &vertices1[k]);
+            let end = match (closed, k) {
+                (false, 0) => Some(0),
+                (false, k) if k == len => Some(1),
+                _ => None,
+            };
+            match end {
+                Some(end) if end_corners[end].is_none() => {
+                    let (i, t0, t1) = match k == 0 {
+                        true => (0, ranges0[0].0, ranges1[0].0),
+                        false => (len - 1, ranges0[len - 1].1, ranges1[len - 1].1),
+                    };
+                    let line = Line(Point2::new(0.0, t0), Point2::new(1.0, t1));
+                    let pcurve = PCurveLns::new(line, fillet_surfaces[i].clone());
+                    let edge = Edge::new(v0, v1, pcurve.into());
+                    end_arcs.push(edge.clone());
+                    edge
+                }
+                Some(1) => {
+                    let surface = &fillet_surfaces[len - 1];
+                    let last_row = surface.control_points()[0].len() - 1;
+                    Edge::new(v0, v1, surface.row_curve(last_row).into())
+                }
+                _ => {
+                    let arc = fillet_surfaces[k % len].row_curve(0);
+                    Edge::new(v0, v1, arc.into())
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    if closed {
+        cross_edges[len] = cross_edges[0].clone();
+    }
 
-        let edge3 = {
-            let (v0, v1) = (edge0.back(), edge1.front());
-            let (u, v) = fillet_surface.search_parameter(v1.point(), (1.0, 1.0), 100)?;
-            let param_line = Line((0.0, 1.0).into(), (u, v).into());
-            let pcurve = PCurveLns::new(param_line, fillet_surface.clone());
-            Edge::new(v0, v1, pcurve.into())
-        };
+    // edges to be trimmed: (edge, the vertex to be removed, the new vertex, the parameter of the new vertex)
+    let mut trimmed_edges = Vec::new();
+    end_edges
+        .iter()
+        .for_each(|(k, (edge0, s0), (edge1, s1), vertex)| {
+            trimmed_edges.push((
+                edge0.clone(),
+                vertex.clone(),
+                vertices0[*k].clone(),
+                Some(*s0),
+            ));
+            trimmed_edges.push((
+                edge1.clone(),
+                vertex.clone(),
+                vertices1[*k].clone(),
+                Some(*s1),
+            ));
+        });
+    trimmed_edges.retain(|(edge, ..)| !removed_edges.contains(&edge.id()));
+    let junctions = if closed { 0..len } else { 1..len };
+    for k in junctions {
+        let (i0, i1) = ((k + len - 1) % len, k);
+        let vertex = wire[i1].front();
+        let adjacent = [faces[i0].0, faces[i0].1, faces[i1].0, faces[i1].1];
+        let others = adjacent
+            .iter()
+            .flat_map(|&i| shell[i].edge_iter())
+            .filter(|edge| edge.front() == vertex || edge.back() == vertex)
+            .filter(|edge| wire.edge_iter().all(|e| !e.is_same(edge)))
+            .unique_by(|edge| edge.id())
+            .collect::<Vec<_>>();
+        match others.len() {
+            0 => {}
+            1 => {
+                let new_vertex = match (faces[i0].0 == faces[i1].0, faces[i0].1 == faces[i1].1) {
+                    (false, true) => &vertices0[k],
+                    (true, false) => &vertices1[k],
+                    _ => return None,
+                };
+                trimmed_edges.push((others[0].clone(), vertex.clone(), new_vertex.clone(), None));
+            }
+            _ => return None,
+        }
+    }
 
-        let wire = [edge0.inverse(), edge2, edge1.inverse(), edge3.inverse()].into();
-        Face::new(vec![wire], fillet_surface)
-    };
-    fillet_faces.insert(0, first_fillet);
-
-    let last_fillet = {
-        let len = wire.len();
-
-        let (_, last_edge) =
-            find_adjacent_edge(&shell[shared_face_index.face_index], wire[len - 1].id())?;
-
-        let edge0 = {
-            let mut bezier = fillet_surfaces[len - 1].column_curve(0);
-            let curve = last_edge.oriented_curve();
-            let (t0, _) = search_closest_parameter(&bezier, &curve, (1.0, 2.0), 100)?;
-            bezier.cut(t0);
-            let v0 = Vertex::new(bezier.front());
-            let v1 = Vertex::new(bezier.back());
-            Edge::new(&v0, &v1, bezier.into())
+    let mut new_edges = HashMap::<EdgeID, Edge>::default();
+    for (edge, vertex, new_vertex, parameter) in trimmed_edges {
+        let current = match new_edges.get(&edge.id()) {
+            Some(current) => current.clone(),
+            None => edge.absolute_clone(),
         };
-
-        let fillet_surface = concat_fillet_surface(&fillet_surfaces[len - 2..len]);
-
-        let edge1 = cut_face_by_last_bezier(shell, adjacent_faces[len - 1], &fillet_surface)?;
-
-        let edge2 = {
-            let (v0, v1) = (edge0.front(), edge1.back());
-            let (u, v) = fillet_surface.search_parameter(v1.point(), (1.0, 1.0), 100)?;
-            let param_line = Line((0.0, 1.0).into(), (u, v).into());
-            let pcurve = PCurveLns::new(param_line, fillet_surface.clone());
-            Edge::new(v0, v1, pcurve.into())
+        let (edge0, edge1) = match parameter {
+            Some(t) => current.not_strictly_cut_with_parameter(&new_vertex, t)?,
+            None => current.not_strictly_cut(&new_vertex)?,
         };
-
-        let edge3 = {
-            let (v0, v1) = (edge0.back(), edge1.front());
-            let t0 = edge0.curve().range_tuple().1;
-            let (u, v) = fillet_surface.search_parameter(v1.point(), (1.0, 2.0), 100)?;
-            let param_line = Line((0.0, t0 + 1.0).into(), (u, v).into());
-            let pcurve = PCurveLns::new(param_line, fillet_surface.clone());
-            Edge::new(v0, v1, pcurve.into())
+        let new_edge = if current.front() == &vertex {
+            edge1
+        } else if current.back() == &vertex {
+            edge0
+        } else {
+            return None;
         };
+        new_edges.insert(edge.id(), new_edge);
+    }
 
-        let wire = [edge0.inverse(), edge2, edge1.inverse(), edge3.inverse()].into();
-        Face::new(vec![wire], fillet_surface)
-    };
-    fillet_faces.push(last_fillet);
-
-    {
-        let mut previous_vertex = None;
-        let mut new_wire = fillet_faces
-            .face_iter()
-            .map(|face| {
-                let edge = &face.boundaries()[0][0];
-                let v0 = Vertex::new(edge.front().point());
-                let v1 = match &previous_vertex {
-                    Some(v) => Vertex::clone(v),
-                    None => Vertex::new(edge.back().point()),
+    // faces around the ends of the wire which are not adjacent to the wire
+    let mut side_faces = Vec::new();
+    for ((k, _, _, vertex), arc) in end_edges.iter().zip(&end_arcs) {
+        let (face0, face1) = faces[usize::min(*k, len - 1)];
+        let sides = faces_around_vertex(shell, vertex)
+            .into_iter()
+            .filter(|&j| j != face0 && j != face1)
+            .collect::<Vec<_>>();
+        match sides.len() {
+            0 => {}
+            1 => {
+                let Curve::PCurve(pcurve) = arc.curve() else {
+                    unreachable!()
                 };
-                let new_edge = Edge::new(&v0, &v1, edge.oriented_curve());
-                previous_vertex = Some(v0);
-                new_edge.inverse()
-            })
-            .collect::<Wire>();
-
-        let shared_face = &mut shell[shared_face_index.face_index];
-        let (front_edge, _) = find_adjacent_edge(shared_face, wire[0].id())?;
-        let (_, back_edge) = find_adjacent_edge(shared_face, wire[wire.len() - 1].id())?;
-
-        let mut boundaries = shared_face.boundaries();
-
-        if front_edge == back_edge {
-            let pre_new_edge = front_edge
-                .not_strictly_cut(new_wire.front_vertex().unwrap())?
-                .0;
-            let new_edge = pre_new_edge
-                .not_strictly_cut(new_wire.back_vertex().unwrap())?
-                .1;
-            new_wire.push_front(new_edge);
-        } else {
-            let new_front_edge = front_edge
-                .not_strictly_cut(new_wire.front_vertex().unwrap())?
-                .0;
-            let new_back_edge = back_edge
-                .not_strictly_cut(new_wire.back_vertex().unwrap())?
-                .1;
-            new_wire.push_front(new_front_edge);
-            new_wire.push_back(new_back_edge);
-
-            let boundary = &boundaries[shared_face_index.boundary_index];
-            let len = boundary.len() - new_wire.len();
-            let top_index = shared_face_index.edge_index + new_wire.len() - 1;
-            (0..len).for_each(|i| {
-                new_wire.push_back(boundary[(top_index + i) % boundary.len()].clone());
-            });
+                let side_surface = Box::new(shell[sides[0]].oriented_surface());
+                let fillet_surface = Box::new(pcurve.surface().clone());
+                let curve = IntersectionCurve::new(side_surface, fillet_surface, pcurve);
+                arc.set_curve(curve.into());
+                side_faces.push(sides[0]);
+            }
+            _ => return None,
         }
-        boundaries[shared_face_index.boundary_index] = new_wire;
-        *shared_face = Face::new(boundaries, shared_face.oriented_surface())
     }
 
-    shell.extend(fillet_faces);
+    // the temporary bridges created by the other fillets, which are replaced by the contact edges of this fillet
+    let bridges = shell
+        .edge_iter()
+        .filter(|edge| corners.iter().any(|corner| corner.is_bridge(edge)))
+        .map(|edge| edge.id())
+        .collect::<Vec<_>>();
+    let wire_edge = |edge: &Edge| wire.edge_iter().position(|e| e.is_same(edge));
+    let replace_edge = |edge: &Edge| match wire_edge(edge) {
+        Some(i) if wire[i] == *edge => Some(contact_edges0[i].clone()),
+        Some(i) => Some(contact_edges1[i].inverse()),
+        None if removed_edges.contains(&edge.id()) || bridges.contains(&edge.id()) => None,
+        None => match new_edges.get(&edge.id()) {
+            Some(new_edge) if edge.orientation() => Some(new_edge.clone()),
+            Some(new_edge) => Some(new_edge.inverse()),
+            None => Some(edge.clone()),
+        },
+    };
+    let is_affected = |face: &Face| {
+        face.edge_iter().any(|edge| {
+            wire_edge(&edge).is_some()
+                || new_edges.contains_key(&edge.id())
+                || removed_edges.contains(&edge.id())
+                || bridges.contains(&edge.id())
+        })
+    };
+    let mut bridge = |v0: &Vertex, v1: &Vertex| {
+        bridge_edge(&end_arcs, v0, v1)
+            .or_else(|| corners.iter_mut().find_map(|corner| corner.bridge(v0, v1)))
+    };
+    let new_faces = shell
+        .iter()
+        .enumerate()
+        .filter(|(i, face)| side_faces.contains(i) || is_affected(face))
+        .map(|(i, face)| {
+            let new_boundaries = face
+                .boundaries()
+                .into_iter()
+                .map(|boundary| {
+                    let mut new_boundary = Wire::new();
+                    for edge in boundary.edge_iter() {
+                        let Some(new_edge) = replace_edge(edge) else {
+                            continue;
+                        };
+                        if let Some(v) = new_boundary.back_vertex().cloned() {
+                            if &v != new_edge.front() {
+                                new_boundary.push_back(bridge(&v, new_edge.front())?);
+                            }
+                        }
+                        new_boundary.push_back(new_edge);
+                    }
+                    let (v0, v1) = new_boundary.ends_vertices()?;
+                    if v0 != v1 {
+                        let (v0, v1) = (v0.clone(), v1.clone());
+                        new_boundary.push_back(bridge(&v1, &v0)?);
+                    }
+                    Some(new_boundary)
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((i, rebuild_face(face, new_boundaries)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
 
+    let fillet_faces = fillet_surfaces
+        .into_iter()
+        .enumerate()
+        .map(|(i, surface)| {
+            let boundary: Wire = [
+                cross_edges[i].clone(),
+                contact_edges1[i].clone(),
+                cross_edges[i + 1].inverse(),
+                contact_edges0[i].inverse(),
+            ]
+            .into();
+            Face::try_new(vec![boundary], surface).ok()
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    new_faces.into_iter().for_each(|(i, face)| shell[i] = face);
+    shell.extend(fillet_faces);
+    // The corner faces are bounded by the end arcs in the opposite orientation to the fillet faces.
+    if let Some(corner) = end_corners[0] {
+        corners[corner].push_arc(cross_edges[0].inverse());
+    }
+    if let Some(corner) = end_corners[1] {
+        corners[corner].push_arc(cross_edges[len].clone());
+    }
     Some(())
 }
 
 #[test]
 fn create_fillet_surface() {
-    use truck_meshalgo::prelude::*;
     #[rustfmt::skip]
-    let surface0 = BSplineSurface::new(
+    let surface0: NurbsSurface<_> = BSplineSurface::new(
         (KnotVec::bezier_knot(2), KnotVec::bezier_knot(2)),
         vec![
             vec![Point3::new(0.2, 0.0, 0.0), Point3::new(0.0, 0.5, 0.0), Point3::new(-0.2, 1.0, 0.0)],
@@ -989,7 +987,7 @@ fn create_fillet_surface() {
     )
     .into();
     #[rustfmt::skip]
-    let surface1 = BSplineSurface::new(
+    let surface1: NurbsSurface<_> = BSplineSurface::new(
         (KnotVec::bezier_knot(2), KnotVec::bezier_knot(2)),
         vec![
             vec![Point3::new(0.2, 0.0, 0.0),  Point3::new(0.0, 0.0, -0.5), Point3::new(-0.2, 0.0, -1.0)],
@@ -1015,8 +1013,17 @@ fn create_fillet_surface() {
             Point3::new(0.2, 0.0, 0.0),
         ],
     );
-    let surface =
-        rolling_ball_fillet_surface(&surface0, &surface1, &curve, 5, |_| 0.3, true).unwrap();
+    let spheres = relay_spheres(
+        &surface0,
+        &surface1,
+        &curve,
+        5,
+        |_| 0.3,
+        (0.0, 1.0),
+        (true, true),
+    )
+    .unwrap();
+    let surface = expand_fillet(&spheres, &surface0, &surface1);
     let poly = StructuredMesh::from_surface(&surface, ((0.0, 1.0), (0.0, 1.0)), 0.01).destruct();
     let file1 = std::fs::File::create("fillet.obj").unwrap();
     obj::write(&poly, file1).unwrap();
@@ -1073,18 +1080,19 @@ fn create_simple_fillet() {
     ]
     .into();
 
-    let shared_edge_id = wire0[0].id();
+    let shared_edge = wire0[0].clone();
     let face0 = Face::new(vec![wire0], surface0);
     let face1 = Face::new(vec![wire1], surface1);
 
-    let shell: Shell = [face0.clone(), face1.clone()].into();
+    let mut shell: Shell = [face0, face1].into();
     let poly = shell.robust_triangulation(0.001).to_polygon();
     let file = std::fs::File::create("edged-shell.obj").unwrap();
     obj::write(&poly, file).unwrap();
 
-    let (face0, face1, fillet) = simple_fillet(&face0, &face1, shared_edge_id, |_| 0.3, 5).unwrap();
+    let wire: Wire = vec![shared_edge].into();
+    fillet_along_wire_with_corners(&mut shell, &wire, |_| 0.3, 5, &mut []).unwrap();
+    assert_eq!(shell.len(), 3);
 
-    let shell: Shell = [face0, face1, fillet].into();
     let poly = shell.robust_triangulation(0.001).to_polygon();
     let file = std::fs::File::create("fillet-shell.obj").unwrap();
     obj::write(&poly, file).unwrap();
@@ -1150,20 +1158,11 @@ fn create_fillet_with_side() {
         Face::new(vec![wire], bsp.into())
     };
 
-    let face = [plane(0, 1, 2, 3), plane(0, 3, 7, 4), plane(0, 4, 5, 1)];
+    let mut shell: Shell = [plane(0, 1, 2, 3), plane(0, 3, 7, 4), plane(0, 4, 5, 1)].into();
 
-    let (face0, face1, fillet, _, side1) = fillet_with_side(
-        &face[0],
-        &face[1],
-        edge[3].id(),
-        None,
-        Some(&face[2]),
-        |t| 0.3 + 0.3 * t,
-        5,
-    )
-    .unwrap();
-
-    let shell: Shell = vec![face0, face1, fillet, side1.unwrap()].into();
+    let wire: Wire = vec![edge[3].clone()].into();
+    fillet_along_wire_with_corners(&mut shell, &wire, |t| 0.3 + 0.3 * t, 5, &mut []).unwrap();
+    assert_eq!(shell.len(), 4);
 
     let poly = shell.robust_triangulation(0.001).to_polygon();
     let file = std::fs::File::create("fillet-with-edge.obj").unwrap();
@@ -1244,7 +1243,7 @@ fn fillet_to_nurbs() {
             .clone(),
         ],
     ));
-    let shell: Shell = [
+    let mut shell: Shell = [
         Face::new(
             vec![[edge[0].clone(), edge[1].clone(), edge[2].clone()].into()],
             bsp0,
@@ -1266,9 +1265,9 @@ fn fillet_to_nurbs() {
     let file = std::fs::File::create("cylinder.obj").unwrap();
     obj::write(&poly, file).unwrap();
 
-    let (face0, face1, fillet) =
-        simple_fillet(&shell[0], &shell[1], edge[1].id(), |_| 0.3, 5).unwrap();
-    let shell: Shell = [face0, face1, fillet].into();
+    let wire: Wire = vec![edge[1].clone()].into();
+    fillet_along_wire_with_corners(&mut shell, &wire, |_| 0.3, 5, &mut []).unwrap();
+    assert_eq!(shell.len(), 3);
 
     let poly = shell.triangulation(0.001).to_polygon();
     let file = std::fs::File::create("fillet-cylinder.obj").unwrap();
@@ -1345,31 +1344,12 @@ fn fillet_semi_cube() {
     let file = std::fs::File::create("semi-cube.obj").unwrap();
     obj::write(&poly, file).unwrap();
 
-    let (face0, face1, face2, _, side1) = fillet_with_side(
-        &shell[1],
-        &shell[2],
-        edge[5].id(),
-        None,
-        Some(&shell[0]),
-        |_| 0.4,
-        5,
-    )
-    .unwrap();
-    (shell[1], shell[2], shell[0]) = (face0, face1, side1.unwrap());
-    shell.push(face2);
-
-    let (face0, face1, face2, _, side1) = fillet_with_side(
-        &shell[2],
-        &shell[3],
-        edge[6].id(),
-        None,
-        Some(&shell[0]),
-        |_| 0.4,
-        5,
-    )
-    .unwrap();
-    (shell[2], shell[3], shell[0]) = (face0, face1, side1.unwrap());
-    shell.push(face2);
+    // the faces are replaced in place, and the fillets are appended.
+    let wire: Wire = vec![edge[5].clone()].into();
+    fillet_along_wire_with_corners(&mut shell, &wire, |_| 0.4, 5, &mut []).unwrap();
+    let wire: Wire = vec![edge[6].clone()].into();
+    fillet_along_wire_with_corners(&mut shell, &wire, |_| 0.4, 5, &mut []).unwrap();
+    assert_eq!(shell.len(), 6);
 
     let mut boundary = shell[0].boundaries().pop().unwrap();
     boundary.pop_back();
@@ -1379,9 +1359,12 @@ fn fillet_semi_cube() {
     let file = std::fs::File::create("pre-fillet-cube.obj").unwrap();
     obj::write(&poly, file).unwrap();
 
-    fillet_along_wire(&mut shell, &boundary, |_| 0.2, 5).unwrap();
+    fillet_along_wire_with_corners(&mut shell, &boundary, |_| 0.2, 5, &mut []).unwrap();
 
     let poly = shell.robust_triangulation(0.001).to_polygon();
     let file = std::fs::File::create("fillet-cube.obj").unwrap();
     obj::write(&poly, file).unwrap();
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,191 @@
+use crate::test_util::{cube, find_edge, volume};
+use crate::{fillet_edges, FilletRadius};
+use itertools::Itertools;
+use std::f64::consts::PI;
+use truck_meshalgo::prelude::*;
+
+#[test]
+fn fillet_one_edge() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(
+        &cube,
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+    );
+    let filleted = fillet_edges(&cube, &[edge], FilletRadius::Constant(0.3)).unwrap();
+    assert_eq!(filleted.face_iter().count(), 7);
+    let answer = 1.0 - 0.3 * 0.3 * (1.0 - PI / 4.0);
+    assert!(f64::abs(volume(&filleted, 0.001) - answer) < 1.0e-3);
+}
+
+#[test]
+fn fillet_variable_radius() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(
+        &cube,
+        Point3::new(1.0, 0.0, 1.0),
+        Point3::new(1.0, 1.0, 1.0),
+    );
+    let radius = FilletRadius::Variable(Box::new(|t| 0.2 + 0.2 * t));
+    let filleted = fillet_edges(&cube, &[edge], radius).unwrap();
+    assert_eq!(filleted.face_iter().count(), 7);
+    let volume = volume(&filleted, 0.001);
+    let (min, max) = (1.0 - 0.16 * (1.0 - PI / 4.0), 1.0 - 0.04 * (1.0 - PI / 4.0));
+    assert!(min < volume && volume < max);
+}
+
+#[test]
+fn fillet_two_edges_at_vertex() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edges = [
+        find_edge(
+            &cube,
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 1.0),
+        ),
+        find_edge(
+            &cube,
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(0.0, 1.0, 1.0),
+        ),
+    ];
+    let filleted = fillet_edges(&cube, &edges, 0.2.into()).unwrap();
+    assert_eq!(filleted.face_iter().count(), 8);
+    volume(&filleted, 0.001);
+}
+
+#[test]
+fn fillet_three_edges_at_corner() {
+    let cube = cube(Point3::origin(), 1.0);
+    let corner = Point3::new(1.0, 1.0, 1.0);
+    let edges = [
+        find_edge(&cube, corner, Point3::new(0.0, 1.0, 1.0)),
+        find_edge(&cube, corner, Point3::new(1.0, 0.0, 1.0)),
+        find_edge(&cube, corner, Point3::new(1.0, 1.0, 0.0)),
+    ];
+    let filleted = fillet_edges(&cube, &edges, 0.2.into()).unwrap();
+    assert_eq!(filleted.face_iter().count(), 10);
+    // the fillets along the edges and the octant of the ball at the corner
+    let (r, area) = (0.2, 0.04 * (1.0 - PI / 4.0));
+    let answer = 1.0 - 3.0 * area * (1.0 - r) - r * r * r * (1.0 - PI / 6.0);
+    assert!(f64::abs(volume(&filleted, 0.001) - answer) < 1.0e-3);
+}
+
+#[test]
+fn fillet_tangent_chain() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(
+        &cube,
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 1.0),
+    );
+    let filleted = fillet_edges(&cube, &[edge], 0.3.into()).unwrap();
+
+    // the top edges around the fillet: line, fillet arc, and line are connected tangentially.
+    let top_edges = filleted
+        .edge_iter()
+        .filter(|edge| {
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            let is_top = |t: f64| curve.subs(t).z.near(&1.0);
+            let (p0, p1) = (edge.front().point(), edge.back().point());
+            let on_left = p0.x.so_small() && p1.x.so_small();
+            let on_back = p0.y.near(&1.0) && p1.y.near(&1.0);
+            is_top(t0) && is_top(t1) && is_top((t0 + t1) / 2.0) && !on_left && !on_back
+        })
+        .map(|edge| edge.id())
+        .unique()
+        .collect::<Vec<_>>();
+    assert_eq!(top_edges.len(), 3);
+    let filleted = fillet_edges(&filleted, &top_edges, 0.1.into()).unwrap();
+    assert_eq!(filleted.face_iter().count(), 10);
+    volume(&filleted, 0.001);
+}
+
+#[test]
+fn fillet_variable_radius_along_chain() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = find_edge(
+        &cube,
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 1.0),
+    );
+    let filleted = fillet_edges(&cube, &[edge], 0.3.into()).unwrap();
+    let top_edges = filleted
+        .edge_iter()
+        .filter(|edge| {
+            let (p0, p1) = (edge.front().point(), edge.back().point());
+            let on_front = p0.y.so_small() || p1.y.so_small();
+            p0.z.near(&1.0) && p1.z.near(&1.0) && (on_front || p0.x.near(&1.0) && p1.x.near(&1.0))
+        })
+        .filter(|edge| !edge.front().point().x.so_small() && !edge.back().point().y.near(&1.0))
+        .map(|edge| edge.id())
+        .unique()
+        .collect::<Vec<_>>();
+    assert_eq!(top_edges.len(), 3);
+
+    // the chain of the line of length 0.7, the quarter arc of radius 0.3, and the line of length 0.7
+    let radius = FilletRadius::Variable(Box::new(|t| 0.05 + 0.1 * t));
+    let filleted = fillet_edges(&filleted, &top_edges, radius).unwrap();
+    volume(&filleted, 0.001);
+
+    // the radius at the junction of the line and the arc is proportional to the arc length.
+    let length = 1.4 + 0.15 * PI;
+    let expected = [0.05 + 0.07 / length, 0.15 - 0.07 / length];
+    let (dist, y) = filleted
+        .edge_iter()
+        .flat_map(|edge| {
+            let curve = edge.curve();
+            let (t0, t1) = curve.range_tuple();
+            (0..=1000)
+                .map(move |i| curve.subs(t0 + (t1 - t0) * i as f64 / 1000.0))
+                .collect::<Vec<_>>()
+        })
+        .filter(|pt| pt.z.near(&1.0) && pt.y < 0.2)
+        .map(|pt| (f64::abs(pt.x - 0.7), pt.y))
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        .unwrap();
+    assert!(dist < 2.0e-3);
+    assert!(expected.iter().any(|r| f64::abs(y - r) < 1.5e-3), "{y}");
+}
+
+#[test]
+fn fillet_edge_not_found() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edge = cube.edge_iter().next().unwrap().id();
+    let other = fillet_edges(&cube, &[edge], 0.1.into()).unwrap();
+    let edge = other.edge_iter().next().unwrap().id();
+    assert_eq!(
+        fillet_edges(&cube, &[edge], 0.1.into()).unwrap_err(),
+        crate::errors::Error::EdgeNotFound,
+    );
+    assert_eq!(
+        fillet_edges(&cube, &[], FilletRadius::Constant(-1.0)).unwrap_err(),
+        crate::errors::Error::InvalidFilletRadius,
+    );
+    // the radius is negative only in the narrow range.
+    let edge = cube.edge_iter().next().unwrap().id();
+    let radius = FilletRadius::Variable(Box::new(|t| match f64::abs(t - 0.55) < 1.0e-3 {
+        true => -0.1,
+        false => 0.1,
+    }));
+    assert_eq!(
+        fillet_edges(&cube, &[edge], radius).unwrap_err(),
+        crate::errors::Error::InvalidFilletRadius,
+    );
+}
+
+#[test]
+fn fillet_all_edges_of_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let edges = cube
+        .edge_iter()
+        .map(|edge| edge.id())
+        .unique()
+        .collect::<Vec<_>>();
+    let filleted = fillet_edges(&cube, &edges, 0.2.into()).unwrap();
+    assert_eq!(filleted.face_iter().count(), 26);
+    let (r, area) = (0.2, 0.04 * (1.0 - PI / 4.0));
+    let answer = 1.0 - 12.0 * area * (1.0 - 2.0 * r) - 8.0 * r * r * r * (1.0 - PI / 6.0);
+    assert!(f64::abs(volume(&filleted, 0.001) - answer) < 2.0e-3);
+}
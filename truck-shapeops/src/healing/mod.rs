@@ -1,6 +1,7 @@
 use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
 use truck_geometry::prelude::*;
 use truck_meshalgo::rexport_polymesh::*;
+use truck_meshalgo::tessellation::{MeshableSurface, PolylineableCurve};
 use truck_topology::compress::*;
 
 type Edge<C> = CompressedEdge<C>;
@@ -18,6 +19,12 @@ use split_closed_edges::split_closed_edges;
 mod split_closed_faces;
 use split_closed_faces::split_closed_faces;
 
+mod sewing;
+
+mod small_features;
+
+mod orientation;
+
 /// Splits closed edges and faces
 ///
 /// # Details
@@ -128,5 +135,148 @@ where
     }
 }
 
+/// Sews the faces whose boundaries geometrically coincide.
+///
+/// # Details
+/// Shapes imported from other CAD systems or built face by face often have the vertices and the edges which
+/// coincide but are distinct, so the shell is not closed. This method merges the vertices within the distance `tol`,
+/// and replaces the edges with the same end vertices whose curves are in the neighborhood of each other with one
+/// edge. The curve of the first edge is adopted. The edges collapsed into a vertex are removed from the boundaries,
+/// and the unused edges are removed from the shell.
+///
+/// Returns the indices of the free edges in `self.edges`, that is, the edges used by only one boundary of the faces.
+/// The shell is closed if there are no free edges and the faces are oriented consistently.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_shapeops::SewFaces;
+/// use truck_topology::shell::ShellCondition;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // the faces which do not share the edges
+/// let faces: Shell = cube
+///     .face_iter()
+///     .map(|face| face.mapped(Point3::clone, Curve::clone, Surface::clone))
+///     .collect();
+/// assert_eq!(faces.shell_condition(), ShellCondition::Oriented);
+///
+/// let mut compressed = faces.compress();
+/// let free_edges = compressed.sew_faces(1.0e-6);
+/// assert!(free_edges.is_empty());
+/// let shell = Shell::extract(compressed).unwrap();
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+/// ```
+pub trait SewFaces {
+    /// Sews the faces, and returns the indices of the free edges.
+    fn sew_faces(&mut self, tol: f64) -> Vec<usize>;
+}
+
+impl<C, S> SewFaces for CompressedShell<Point3, C, S>
+where C: ParametricCurve3D
+        + BoundedCurve
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3>
+{
+    fn sew_faces(&mut self, tol: f64) -> Vec<usize> {
+        nonpositive_tolerance!(tol);
+        sewing::sew_faces(self, tol)
+    }
+}
+
+/// The report of [`RemoveSmallFeatures::remove_small_features`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SmallFeaturesReport {
+    /// the indices of the collapsed edges in the original shell
+    pub collapsed_edges: Vec<usize>,
+    /// the indices of the removed faces in the original shell
+    pub removed_faces: Vec<usize>,
+}
+
+/// Removes the small edges and the sliver faces.
+///
+/// # Details
+/// Shapes imported from other CAD systems often have the micro edges and the faces with almost zero area, which
+/// break the tessellation and the boolean operations. This method collapses the edges shorter than `tol` by merging
+/// their end vertices, and removes them from the boundaries. The curves of the edges adjacent to the merged vertices
+/// are re-fitted by the affine transformations moving their ends onto the merged vertices, so that no gaps remain.
+/// The faces whose boundaries are collapsed are removed.
+///
+/// Then, the sliver faces, that is, the faces with one boundary each edge of which lies within the distance `tol`
+/// from the other edges, are absorbed into the neighboring faces. The edges of a sliver are split at the vertices of
+/// the sliver and re-fitted to them in the same way, the coincident edges of the sliver are identified with each
+/// other, and the sliver is removed.
+///
+/// Returns the report of the collapsed edges and the removed faces.
+pub trait RemoveSmallFeatures {
+    /// Removes the small edges and the sliver faces, and returns the report.
+    fn remove_small_features(&mut self, tol: f64) -> SmallFeaturesReport;
+}
+
+impl<C, S> RemoveSmallFeatures for CompressedShell<Point3, C, S>
+where C: ParametricCurve3D
+        + BoundedCurve
+        + Cut
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3>
+        + Transformed<Matrix4>
+{
+    fn remove_small_features(&mut self, tol: f64) -> SmallFeaturesReport {
+        nonpositive_tolerance!(tol);
+        small_features::remove_small_features(self, tol)
+    }
+}
+
+/// Fixes the orientations of the faces of shells.
+///
+/// # Details
+/// The faces of the shells read from STEP files or sewn together often have inconsistent orientations, and the
+/// shells are not [`Oriented`](truck_topology::shell::ShellCondition::Oriented). This method inverts the faces so
+/// that the adjacent faces run the common edges in the opposite directions, by traversing the faces from the first
+/// face of each connected component. The orientation of the first face is kept, unless the component is closed.
+/// The faces of each closed component are inverted together if the signed volume of the tessellation with the
+/// tolerance `tol` is negative, so that the normals point outward.
+///
+/// Returns the indices of the inverted faces.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_shapeops::FixOrientation;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let mut shell = cube.into_boundaries().pop().unwrap();
+/// shell[1].invert();
+/// shell[4].invert();
+/// assert!(Solid::try_new(vec![shell.clone()]).is_err());
+///
+/// let inverted = shell.fix_orientation(0.01);
+/// assert_eq!(inverted, vec![1, 4]);
+/// assert!(Solid::try_new(vec![shell]).is_ok());
+/// ```
+pub trait FixOrientation {
+    /// Fixes the orientations of the faces, and returns the indices of the inverted faces.
+    fn fix_orientation(&mut self, tol: f64) -> Vec<usize>;
+}
+
+impl<C, S> FixOrientation for truck_topology::Shell<Point3, C, S>
+where
+    C: PolylineableCurve,
+    S: MeshableSurface,
+{
+    fn fix_orientation(&mut self, tol: f64) -> Vec<usize> {
+        nonpositive_tolerance!(tol);
+        orientation::fix_orientation(self, tol)
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,98 @@
+use rustc_hash::FxHashMap as HashMap;
+use std::collections::VecDeque;
+use truck_geometry::prelude::Point3;
+use truck_meshalgo::{analyzers::CalcVolume, tessellation::*};
+use truck_topology::{EdgeID, Shell};
+
+/// The groups of the faces connected by the edges, with the orientations of the faces such that the adjacent faces
+/// run the common edges in the opposite directions.
+fn consistent_orientations<C, S>(
+    shell: &Shell<Point3, C, S>,
+    edge_map: &HashMap<EdgeID<C>, Vec<(usize, bool)>>,
+) -> (Vec<Vec<usize>>, Vec<bool>) {
+    let mut orientations = vec![None; shell.len()];
+    let mut components = Vec::new();
+    for seed in 0..shell.len() {
+        if orientations[seed].is_some() {
+            continue;
+        }
+        orientations[seed] = Some(shell[seed].orientation());
+        let mut component = Vec::new();
+        let mut queue = VecDeque::from([seed]);
+        while let Some(i) = queue.pop_front() {
+            component.push(i);
+            let orientation = orientations[i] == Some(true);
+            shell[i]
+                .absolute_boundaries()
+                .iter()
+                .flatten()
+                .for_each(|edge| {
+                    // whether the edge runs in the direction of its curve on the face `i`.
+                    let forward = edge.orientation() == orientation;
+                    edge_map[&edge.id()]
+                        .iter()
+                        .for_each(|(j, edge_orientation)| {
+                            if orientations[*j].is_none() {
+                                orientations[*j] = Some(*edge_orientation != forward);
+                                queue.push_back(*j);
+                            }
+                        });
+                });
+        }
+        components.push(component);
+    }
+    (components, orientations.into_iter().flatten().collect())
+}
+
+pub(super) fn fix_orientation<C, S>(shell: &mut Shell<Point3, C, S>, tol: f64) -> Vec<usize>
+where
+    C: PolylineableCurve,
+    S: MeshableSurface, {
+    let mut edge_map = HashMap::<EdgeID<C>, Vec<(usize, bool)>>::default();
+    shell.face_iter().enumerate().for_each(|(i, face)| {
+        face.absolute_boundaries()
+            .iter()
+            .flatten()
+            .for_each(|edge| {
+                edge_map
+                    .entry(edge.id())
+                    .or_default()
+                    .push((i, edge.orientation()));
+            })
+    });
+    let (components, mut orientations) = consistent_orientations(shell, &edge_map);
+
+    // the closed components are turned inside out if the signed volumes are negative.
+    let closed = |component: &Vec<usize>| {
+        component.iter().all(|i| {
+            let mut edges = shell[*i].absolute_boundaries().iter().flatten();
+            edges.all(|edge| edge_map[&edge.id()].len() == 2)
+        })
+    };
+    let closed_components = components.into_iter().filter(closed).collect::<Vec<_>>();
+    if !closed_components.is_empty() {
+        let poly_shell = shell.triangulation(tol);
+        closed_components.into_iter().for_each(|component| {
+            let volume = component.iter().try_fold(0.0, |sum, i| {
+                let volume = poly_shell[*i].surface()?.volume();
+                Some(match orientations[*i] {
+                    true => sum + volume,
+                    false => sum - volume,
+                })
+            });
+            if matches!(volume, Some(volume) if volume < 0.0) {
+                component
+                    .into_iter()
+                    .for_each(|i| orientations[i] = !orientations[i]);
+            }
+        });
+    }
+
+    let inverted = (0..shell.len())
+        .filter(|i| shell[*i].orientation() != orientations[*i])
+        .collect::<Vec<_>>();
+    inverted.iter().for_each(|i| {
+        shell[*i].invert();
+    });
+    inverted
+}
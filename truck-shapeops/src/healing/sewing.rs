@@ -0,0 +1,149 @@
+use super::*;
+
+/// Merges the vertices within the distance `tol`, and returns the map from the old indices to the new ones.
+fn merge_vertices(vertices: &mut Vec<Point3>, tol: f64) -> Vec<usize> {
+    let cell = |p: Point3| [p.x, p.y, p.z].map(|x| f64::floor(x / tol) as i64);
+    let mut grid = HashMap::<[i64; 3], Vec<usize>>::default();
+    let mut merged = Vec::<Point3>::new();
+    let map = vertices
+        .iter()
+        .map(|p| {
+            let [x, y, z] = cell(*p);
+            let found = (x - 1..=x + 1)
+                .flat_map(|x| {
+                    (y - 1..=y + 1).flat_map(move |y| (z - 1..=z + 1).map(move |z| [x, y, z]))
+                })
+                .filter_map(|c| grid.get(&c))
+                .flatten()
+                .find(|idx| merged[**idx].distance(*p) <= tol)
+                .copied();
+            found.unwrap_or_else(|| {
+                merged.push(*p);
+                grid.entry([x, y, z]).or_default().push(merged.len() - 1);
+                merged.len() - 1
+            })
+        })
+        .collect();
+    *vertices = merged;
+    map
+}
+
+/// Whether the curve of `edge1` is in the neighborhood of the curve of `edge0` with radius `tol`.
+pub(super) fn near_as_curve<C>(edge0: &Edge<C>, edge1: &Edge<C>, tol: f64) -> bool
+where C: BoundedCurve<Point = Point3>
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3> {
+    let (t0, t1) = edge0.curve.range_tuple();
+    let (_, pts) = edge1
+        .curve
+        .parameter_division(edge1.curve.range_tuple(), tol);
+    pts.into_iter().all(|pt| {
+        let t = edge0
+            .curve
+            .search_nearest_parameter(pt, SPHint1D::Range(t0, t1), 100);
+        matches!(t, Some(t) if edge0.curve.subs(t.clamp(t0, t1)).distance(pt) <= tol)
+    })
+}
+
+/// Whether the direction of `edge1` is opposite to the one of `edge0`, where the edges are coincident.
+pub(super) fn reversed<C>(edge0: &Edge<C>, edge1: &Edge<C>) -> bool
+where C: ParametricCurve3D + BoundedCurve + SearchNearestParameter<D1, Point = Point3> {
+    if edge0.vertices.0 != edge0.vertices.1 {
+        return edge0.vertices != edge1.vertices;
+    }
+    // the closed edges are compared by the tangent vectors.
+    let (t0, t1) = edge0.curve.range_tuple();
+    let (s0, s1) = edge1.curve.range_tuple();
+    let s = (s0 + s1) / 2.0;
+    let hint = SPHint1D::Range(t0, t1);
+    let t = edge0
+        .curve
+        .search_nearest_parameter(edge1.curve.subs(s), hint, 100);
+    let t = t.unwrap_or((t0 + t1) / 2.0);
+    edge0.curve.der(t).dot(edge1.curve.der(s)) < 0.0
+}
+
+/// Whether the edge is collapsed into the vertex.
+fn collapsed<C>(edge: &Edge<C>, tol: f64) -> bool
+where C: BoundedCurve<Point = Point3> + ParameterDivision1D<Point = Point3> {
+    let (v0, v1) = edge.vertices;
+    let (_, pts) = edge.curve.parameter_division(edge.curve.range_tuple(), tol);
+    v0 == v1 && pts.iter().all(|pt| pt.distance(pts[0]) <= tol)
+}
+
+pub(super) fn sew_faces<C, S>(shell: &mut Shell<Point3, C, S>, tol: f64) -> Vec<usize>
+where C: ParametricCurve3D
+        + BoundedCurve
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3> {
+    let vmap = merge_vertices(&mut shell.vertices, tol);
+    shell.edges.iter_mut().for_each(|edge| {
+        let (v0, v1) = edge.vertices;
+        edge.vertices = (vmap[v0], vmap[v1]);
+    });
+
+    // the representatives of the coincident edges, with the flags whether the edges are reversed.
+    let mut groups = HashMap::<(usize, usize), Vec<usize>>::default();
+    let emap = (0..shell.edges.len())
+        .map(|i| {
+            let edge = &shell.edges[i];
+            if collapsed(edge, tol) {
+                return None;
+            }
+            let (v0, v1) = edge.vertices;
+            let group = groups
+                .entry((usize::min(v0, v1), usize::max(v0, v1)))
+                .or_default();
+            let found = group
+                .iter()
+                .find(|j| near_as_curve(&shell.edges[**j], edge, tol))
+                .copied();
+            Some(match found {
+                Some(j) => (j, reversed(&shell.edges[j], edge)),
+                None => {
+                    group.push(i);
+                    (i, false)
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let counter = rewrite_wires(shell, &emap);
+    (0..counter.len()).filter(|i| counter[*i] == 1).collect()
+}
+
+/// Replaces the edges in the boundaries by `emap`, which maps the index of an edge to the index of the new edge with
+/// the flag whether the edge is reversed, or to `None` if the edge is removed. The unused edges are removed and the
+/// used edges are renumbered. Returns the numbers of the uses of the new edges.
+pub(super) fn rewrite_wires<C: Clone, S>(
+    shell: &mut Shell<Point3, C, S>,
+    emap: &[Option<(usize, bool)>],
+) -> Vec<usize> {
+    let mut used = vec![None; shell.edges.len()];
+    let mut edges = Vec::new();
+    let mut counter = Vec::<usize>::new();
+    shell
+        .faces
+        .iter_mut()
+        .flat_map(|face| &mut face.boundaries)
+        .for_each(|wire| {
+            *wire = wire
+                .iter()
+                .filter_map(|edge| {
+                    let (index, reversed) = emap[edge.index]?;
+                    let new_index = *used[index].get_or_insert_with(|| {
+                        edges.push(shell.edges[index].clone());
+                        counter.push(0);
+                        edges.len() - 1
+                    });
+                    counter[new_index] += 1;
+                    Some(EdgeIndex {
+                        index: new_index,
+                        orientation: edge.orientation != reversed,
+                    })
+                })
+                .collect();
+        });
+    shell.edges = edges;
+    counter
+}
@@ -0,0 +1,219 @@
+use super::sewing::{near_as_curve, reversed, rewrite_wires};
+use super::split_closed_edges::replace_edges;
+use super::*;
+
+/// The length of the polyline approximating the curve.
+fn length<C>(curve: &C, tol: f64) -> f64
+where C: BoundedCurve<Point = Point3> + ParameterDivision1D<Point = Point3> {
+    let (_, pts) = curve.parameter_division(curve.range_tuple(), tol);
+    pts.windows(2).map(|p| p[0].distance(p[1])).sum()
+}
+
+/// The distance between `point` and the bounded curve `curve`.
+fn distance_to_curve<C>(curve: &C, point: Point3) -> f64
+where C: BoundedCurve<Point = Point3> + SearchNearestParameter<D1, Point = Point3> {
+    let (t0, t1) = curve.range_tuple();
+    match curve.search_nearest_parameter(point, SPHint1D::Range(t0, t1), 100) {
+        Some(t) => curve.subs(t.clamp(t0, t1)).distance(point),
+        None => f64::INFINITY,
+    }
+}
+
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// The affine transformation moving the ends `p0` and `p1` of a curve onto `q0` and `q1`. Each point is moved by the
+/// interpolation of the displacements of the ends along the chord.
+fn refit_ends(p0: Point3, p1: Point3, q0: Point3, q1: Point3) -> Matrix4 {
+    let (d0, d1) = (q0 - p0, q1 - p1);
+    let chord = p1 - p0;
+    if chord.so_small() {
+        return Matrix4::from_translation(d0);
+    }
+    let (d, w) = (d1 - d0, chord / chord.magnitude2());
+    let linear = Matrix3::identity() + Matrix3::from_cols(d * w.x, d * w.y, d * w.z);
+    Matrix4::from_translation(d0 - d * w.dot(p0.to_vec())) * Matrix4::from(linear)
+}
+
+/// Collapses the edges shorter than `tol` by merging the end vertices. The curves of the edges adjacent to the merged
+/// vertices are re-fitted to the merged vertices. Returns the indices of the collapsed edges.
+fn collapse_small_edges<C, S>(shell: &mut Shell<Point3, C, S>, tol: f64) -> Vec<usize>
+where C: BoundedCurve<Point = Point3> + ParameterDivision1D<Point = Point3> + Transformed<Matrix4> {
+    let small = (0..shell.edges.len())
+        .filter(|i| length(&shell.edges[*i].curve, tol) < tol)
+        .collect::<Vec<_>>();
+    let mut parent = (0..shell.vertices.len()).collect::<Vec<_>>();
+    small.iter().for_each(|i| {
+        let (v0, v1) = shell.edges[*i].vertices;
+        let (r0, r1) = (root(&mut parent, v0), root(&mut parent, v1));
+        parent[r1] = r0;
+    });
+    let vertices = &shell.vertices;
+    shell.edges.iter_mut().for_each(|edge| {
+        let (v0, v1) = edge.vertices;
+        let (r0, r1) = (root(&mut parent, v0), root(&mut parent, v1));
+        if (r0, r1) != (v0, v1) {
+            let (p0, p1) = (edge.curve.front(), edge.curve.back());
+            let matrix = refit_ends(p0, p1, vertices[r0], vertices[r1]);
+            edge.curve.transform_by(matrix);
+        }
+        edge.vertices = (r0, r1);
+    });
+    let mut emap = (0..shell.edges.len())
+        .map(|i| Some((i, false)))
+        .collect::<Vec<_>>();
+    small.iter().for_each(|i| emap[*i] = None);
+    rewrite_wires(shell, &emap);
+    small
+}
+
+/// Whether each edge of `wire` is in the neighborhood of the other edges with radius `tol`.
+fn is_sliver<C>(edges: &[Edge<C>], wire: &Wire, tol: f64) -> bool
+where C: BoundedCurve<Point = Point3>
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3> {
+    wire.iter().all(|edge| {
+        let curve = &edges[edge.index].curve;
+        let (_, pts) = curve.parameter_division(curve.range_tuple(), tol);
+        pts.into_iter().all(|pt| {
+            wire.iter()
+                .filter(|other| other.index != edge.index)
+                .any(|other| distance_to_curve(&edges[other.index].curve, pt) <= tol)
+        })
+    })
+}
+
+/// Splits the edges of `wire` at the vertices of `wire` on their interiors. The split edges are replaced in all the
+/// boundaries of the shell.
+fn split_at_vertices<C, S>(shell: &mut Shell<Point3, C, S>, face_index: usize, tol: f64)
+where C: BoundedCurve<Point = Point3>
+        + Cut
+        + SearchNearestParameter<D1, Point = Point3>
+        + Transformed<Matrix4> {
+    loop {
+        let wire = &shell.faces[face_index].boundaries[0];
+        let vertices = wire.iter().map(|edge| shell.edges[edge.index].vertices.0);
+        let found = vertices.clone().find_map(|v| {
+            let point = shell.vertices[v];
+            wire.iter().find_map(|edge| {
+                let Edge { vertices, curve } = &shell.edges[edge.index];
+                if vertices.0 == v || vertices.1 == v {
+                    return None;
+                }
+                let (t0, t1) = curve.range_tuple();
+                let t = curve.search_nearest_parameter(point, SPHint1D::Range(t0, t1), 100)?;
+                let pt = curve.subs(t);
+                let interior = t0 < t
+                    && t < t1
+                    && pt.distance(shell.vertices[vertices.0]) > tol
+                    && pt.distance(shell.vertices[vertices.1]) > tol;
+                (interior && pt.distance(point) <= tol).then_some((v, edge.index, t))
+            })
+        });
+        let Some((v, index, t)) = found else {
+            return;
+        };
+        let (point, edge) = (shell.vertices[v], &mut shell.edges[index]);
+        let mut curve = edge.curve.cut(t);
+        // the pieces are re-fitted to the vertex, which is apart from the curve within `tol`.
+        let (p0, p1) = (edge.curve.front(), edge.curve.back());
+        edge.curve.transform_by(refit_ends(p0, p1, p0, point));
+        let (p0, p1) = (curve.front(), curve.back());
+        curve.transform_by(refit_ends(p0, p1, point, p1));
+        let new_edge = Edge {
+            vertices: (v, edge.vertices.1),
+            curve,
+        };
+        edge.vertices.1 = v;
+        shell.edges.push(new_edge);
+        let added = HashMap::from_iter([(index, shell.edges.len() - 1)]);
+        shell
+            .faces
+            .iter_mut()
+            .flat_map(|face| &mut face.boundaries)
+            .for_each(|wire| replace_edges(wire, &added));
+    }
+}
+
+/// Pairs the edges of `wire` with the same end vertices and the coincident curves. Returns the map from the second
+/// edges to the first edges, or `None` if there is an edge which is not paired.
+fn pair_edges<C>(
+    edges: &[Edge<C>],
+    wire: &Wire,
+    tol: f64,
+) -> Option<HashMap<usize, (usize, bool)>>
+where
+    C: ParametricCurve3D
+        + BoundedCurve
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3>,
+{
+    let mut rest = wire.iter().map(|edge| edge.index).collect::<Vec<_>>();
+    let mut pairs = HashMap::default();
+    while let Some(i) = rest.pop() {
+        let (v0, v1) = edges[i].vertices;
+        let position = rest.iter().position(|j| {
+            let (w0, w1) = edges[*j].vertices;
+            ((v0, v1) == (w0, w1) || (v0, v1) == (w1, w0))
+                && near_as_curve(&edges[*j], &edges[i], tol)
+        })?;
+        let j = rest.swap_remove(position);
+        pairs.insert(i, (j, reversed(&edges[j], &edges[i])));
+    }
+    Some(pairs)
+}
+
+pub(super) fn remove_small_features<C, S>(
+    shell: &mut Shell<Point3, C, S>,
+    tol: f64,
+) -> SmallFeaturesReport
+where
+    C: ParametricCurve3D
+        + BoundedCurve
+        + Cut
+        + ParameterDivision1D<Point = Point3>
+        + SearchNearestParameter<D1, Point = Point3>
+        + Transformed<Matrix4>,
+{
+    let collapsed_edges = collapse_small_edges(shell, tol);
+    let mut face_indices = (0..shell.faces.len()).collect::<Vec<_>>();
+    let mut removed_faces = Vec::new();
+    let mut i = 0;
+    while i < shell.faces.len() {
+        shell.faces[i].boundaries.retain(|wire| !wire.is_empty());
+        let absorbed = match shell.faces[i].boundaries.len() {
+            // the face is collapsed into the vertices.
+            0 => true,
+            1 if is_sliver(&shell.edges, &shell.faces[i].boundaries[0], tol) => {
+                split_at_vertices(shell, i, tol);
+                match pair_edges(&shell.edges, &shell.faces[i].boundaries[0], tol) {
+                    Some(pairs) => {
+                        let emap = (0..shell.edges.len())
+                            .map(|j| Some(pairs.get(&j).copied().unwrap_or((j, false))))
+                            .collect::<Vec<_>>();
+                        shell.faces[i].boundaries.clear();
+                        rewrite_wires(shell, &emap);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        };
+        if absorbed {
+            shell.faces.remove(i);
+            removed_faces.push(face_indices.remove(i));
+        } else {
+            i += 1;
+        }
+    }
+    SmallFeaturesReport {
+        collapsed_edges,
+        removed_faces,
+    }
+}
@@ -42,7 +42,7 @@ where C: BoundedCurve<Point = P> + Cut {
     (0..len).filter_map(sub_add_edges).collect()
 }
 
-fn replace_edges(wire: &mut Wire, added: &HashMap<usize, usize>) {
+pub(super) fn replace_edges(wire: &mut Wire, added: &HashMap<usize, usize>) {
     let insert_one_edge = |edge: EdgeIndex| {
         let Some(new_edge_index) = added.get(&edge.index) else {
             return vec![edge];
@@ -1533,3 +1533,243 @@ fn step_import() {
         });
     });
 }
+
+fn loose_faces<C: Clone, S: Clone>(shell: &Shell<Point3, C, S>) -> Shell<Point3, C, S> {
+    shell
+        .face_iter()
+        .map(|face| face.mapped(Point3::clone, C::clone, S::clone))
+        .collect()
+}
+
+#[test]
+fn sew_loose_faces() {
+    use truck_modeling::{builder, Solid};
+    use truck_topology::shell::ShellCondition;
+
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let cylinder: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let shell = loose_faces(&cylinder.boundaries()[0]);
+    assert_eq!(shell.shell_condition(), ShellCondition::Oriented);
+
+    let mut compressed = shell.compress();
+    // the vertices are perturbed within the tolerance
+    compressed
+        .vertices
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, p)| {
+            *p += Vector3::new(1.0, -1.0, 1.0) * (i % 3) as f64 * 1.0e-5;
+        });
+    let free_edges = compressed.sew_faces(1.0e-4);
+    assert!(free_edges.is_empty());
+    let sewn = Shell::extract(compressed).unwrap();
+    assert_eq!(sewn.shell_condition(), ShellCondition::Closed);
+    assert_eq!(sewn.edge_iter().count(), cylinder.edge_iter().count());
+}
+
+#[test]
+fn sew_open_faces() {
+    use truck_modeling::{builder, Solid};
+    use truck_topology::shell::ShellCondition;
+
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let f = builder::tsweep(&e, Vector3::unit_y());
+    let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let mut shell = loose_faces(&cube.boundaries()[0]);
+    // removes the top face
+    let top = shell
+        .iter()
+        .position(|face| face.vertex_iter().all(|v| v.point().z.near(&1.0)))
+        .unwrap();
+    shell.remove(top);
+
+    let mut compressed = shell.compress();
+    let free_edges = compressed.sew_faces(1.0e-6);
+    assert_eq!(free_edges.len(), 4);
+    free_edges.iter().for_each(|i| {
+        let (v0, v1) = compressed.edges[*i].vertices;
+        assert_near!(compressed.vertices[v0].z, 1.0);
+        assert_near!(compressed.vertices[v1].z, 1.0);
+    });
+    let sewn = Shell::extract(compressed).unwrap();
+    assert_eq!(sewn.shell_condition(), ShellCondition::Oriented);
+}
+
+fn polyhedron(points: &[Point3], faces: &[&[usize]]) -> truck_modeling::Shell {
+    use truck_modeling::{builder, Edge, Wire};
+    let vertices = points
+        .iter()
+        .map(|p| builder::vertex(*p))
+        .collect::<Vec<_>>();
+    let mut edges = HashMap::<(usize, usize), Edge>::default();
+    faces
+        .iter()
+        .map(|face| {
+            let wire: Wire = (0..face.len())
+                .map(|i| {
+                    let (v0, v1) = (face[i], face[(i + 1) % face.len()]);
+                    match edges.get(&(v1, v0)) {
+                        Some(edge) => edge.inverse(),
+                        None => {
+                            let edge = builder::line(&vertices[v0], &vertices[v1]);
+                            edges.insert((v0, v1), edge.clone());
+                            edge
+                        }
+                    }
+                })
+                .collect();
+            builder::try_attach_plane(&[wire]).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn remove_sliver_face() {
+    use truck_meshalgo::tessellation::*;
+    use truck_topology::shell::ShellCondition;
+
+    // the cube whose top face is divided by the sliver strip with the width `D`.
+    const D: f64 = 1.0e-5;
+    let points = [
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 1.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Point3::new(0.0, 0.0, 1.0),
+        Point3::new(1.0, 0.0, 1.0),
+        Point3::new(1.0, 1.0, 1.0),
+        Point3::new(0.0, 1.0, 1.0),
+        Point3::new(0.5, 0.0, 1.0),
+        Point3::new(0.5 + D, 0.0, 1.0),
+        Point3::new(0.5 + D, 1.0, 1.0),
+        Point3::new(0.5, 1.0, 1.0),
+        Point3::new(0.5, 0.5, 1.0),
+    ];
+    let faces: [&[usize]; 8] = [
+        &[0, 3, 2, 1],
+        &[0, 1, 5, 9, 8, 4],
+        &[1, 2, 6, 5],
+        &[2, 3, 7, 11, 10, 6],
+        &[3, 0, 4, 7],
+        &[4, 8, 12, 11, 7],
+        &[8, 9, 10, 11, 12],
+        &[9, 5, 6, 10],
+    ];
+    let shell = polyhedron(&points, &faces);
+    assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+
+    let mut compressed = shell.compress();
+    let micro_edges = (0..compressed.edges.len())
+        .filter(|i| {
+            let (v0, v1) = compressed.edges[*i].vertices;
+            compressed.vertices[v0].distance(compressed.vertices[v1]) < 1.0e-4
+        })
+        .collect::<Vec<_>>();
+    let report = compressed.remove_small_features(1.0e-4);
+    assert_eq!(report.collapsed_edges, micro_edges);
+    assert_eq!(report.removed_faces, vec![6]);
+
+    let healed = Shell::extract(compressed).unwrap();
+    assert_eq!(healed.len(), 7);
+    assert_eq!(healed.shell_condition(), ShellCondition::Closed);
+    // the edge of the sliver with the vertex on the interior is split.
+    assert_eq!(healed.vertex_iter().collect::<HashSet<_>>().len(), 11);
+    // the curves are re-fitted to the merged vertices and the vertices splitting the edges.
+    assert!(healed.edge_iter().all(|edge| {
+        let curve = edge.curve();
+        curve.front().near(&edge.absolute_front().point())
+            && curve.back().near(&edge.absolute_back().point())
+    }));
+    let poly_shell = healed.triangulation(0.01);
+    assert!(poly_shell.face_iter().all(|face| face.surface().is_some()));
+}
+
+#[test]
+fn remove_nothing() {
+    use truck_modeling::{builder, Solid};
+
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let f = builder::tsweep(&e, Vector3::unit_y());
+    let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let mut compressed = cube.compress().boundaries.pop().unwrap();
+    let report = compressed.remove_small_features(1.0e-4);
+    assert_eq!(report, SmallFeaturesReport::default());
+    assert_eq!(compressed.faces.len(), 6);
+    assert_eq!(compressed.edges.len(), 12);
+}
+
+#[test]
+fn fix_inside_out_shell() {
+    use truck_modeling::{builder, Solid};
+
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let cylinder: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let mut shell = cylinder.into_boundaries().pop().unwrap();
+    let orientations = shell
+        .face_iter()
+        .map(|face| face.orientation())
+        .collect::<Vec<_>>();
+    shell.face_iter_mut().for_each(|face| {
+        face.invert();
+    });
+    assert!(Solid::try_new(vec![shell.clone()]).is_ok());
+
+    let inverted = shell.fix_orientation(0.01);
+    assert_eq!(inverted, (0..shell.len()).collect::<Vec<_>>());
+    let fixed = shell
+        .face_iter()
+        .map(|face| face.orientation())
+        .collect::<Vec<_>>();
+    assert_eq!(fixed, orientations);
+    assert!(Solid::try_new(vec![shell]).is_ok());
+}
+
+#[test]
+fn fix_sewn_faces() {
+    use truck_modeling::{builder, Solid};
+    use truck_topology::shell::ShellCondition;
+
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let f = builder::tsweep(&e, Vector3::unit_y());
+    let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let mut shell = loose_faces(&cube.boundaries()[0]);
+    [0, 2, 3].into_iter().for_each(|i| {
+        shell[i].invert();
+    });
+    let mut compressed = shell.compress();
+    assert!(compressed.sew_faces(1.0e-6).is_empty());
+    let mut shell = Shell::extract(compressed).unwrap();
+    assert_eq!(shell.shell_condition(), ShellCondition::Regular);
+
+    let inverted = shell.fix_orientation(0.01);
+    assert_eq!(inverted, vec![0, 2, 3]);
+    assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+    assert!(Solid::try_new(vec![shell]).is_ok());
+}
+
+#[test]
+fn fix_open_shell() {
+    use truck_modeling::{builder, Solid};
+    use truck_topology::shell::ShellCondition;
+
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let f = builder::tsweep(&e, Vector3::unit_y());
+    let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let mut shell = cube.into_boundaries().pop().unwrap();
+    shell.pop();
+    shell[0].invert();
+    assert_eq!(shell.shell_condition(), ShellCondition::Regular);
+
+    // the orientation of the first face is kept.
+    let inverted = shell.fix_orientation(0.01);
+    assert_eq!(inverted, vec![1, 2, 3, 4]);
+    assert_eq!(shell.shell_condition(), ShellCondition::Oriented);
+}
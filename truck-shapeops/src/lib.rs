@@ -13,9 +13,41 @@
     unused_qualifications
 )]
 
+/// `Result` with crate's errors.
+pub type Result<T> = std::result::Result<T, errors::Error>;
+
+/// declare errors
+pub mod errors;
 mod healing;
-pub use healing::{RobustSplitClosedEdgesAndFaces, SplitClosedEdgesAndFaces};
+pub use healing::{
+    FixOrientation, RemoveSmallFeatures, RobustSplitClosedEdgesAndFaces, SewFaces,
+    SmallFeaturesReport, SplitClosedEdgesAndFaces,
+};
 mod transversal;
-pub use transversal::{and, or, ShapeOpsCurve, ShapeOpsSurface};
+pub use transversal::{
+    and, difference, imprint, or, split_shell_by_solid, symmetric_difference, union_all,
+    AnalyticSurface, ElementarySurface, ShapeOpsCurve, ShapeOpsSurface,
+};
 mod alternative;
 mod fillet;
+pub use fillet::{fillet_edges, FilletRadius};
+mod chamfer;
+pub use chamfer::{chamfer_edges, Chamfer};
+mod offset;
+pub use offset::{offset, shell};
+mod defeature;
+pub use defeature::remove_faces;
+mod draft;
+pub use draft::draft_faces;
+mod extrude;
+pub use extrude::{extrude, ExtrudeExtent, ExtrudeOptions};
+mod section;
+pub use section::{section, section_faces, shell_section};
+mod classify;
+pub use classify::{classify_point, PointClassification};
+mod distance;
+pub use distance::{distance, ClosestPoints, ShapeElement};
+mod unify;
+pub use unify::unify_same_domain;
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,306 @@
+use super::*;
+use std::ops::Bound;
+
+// The approximations are retried with the doubled divisions up to this number of times.
+const APPROXIMATION_TRIALS: usize = 7;
+
+/// The faces whose normals make an angle smaller than this are regarded as tangent.
+pub(crate) const TANGENT_ANGLE_TOLERANCE: f64 = 1.0e-4;
+
+/// Returns the parameter range of the surface if it is bounded.
+pub(crate) fn range_tuple(surface: &impl ParametricSurface) -> Option<((f64, f64), (f64, f64))> {
+    let bounded = |range: ParameterRange| match range {
+        (Bound::Included(t0) | Bound::Excluded(t0), Bound::Included(t1) | Bound::Excluded(t1)) => {
+            Some((t0, t1))
+        }
+        _ => None,
+    };
+    let (urange, vrange) = surface.parameter_range();
+    Some((bounded(urange)?, bounded(vrange)?))
+}
+
+/// The normal of the surface. If the surface degenerates at the parameter, the normal is evaluated at a nearby
+/// parameter toward the center of the parameter range.
+pub(crate) fn normal(surface: &impl ParametricSurface3D, (u, v): (f64, f64)) -> Option<Vector3> {
+    let valid = |n: Vector3| n.magnitude2().is_finite() && n.magnitude2() > 0.5;
+    let n = surface.normal(u, v);
+    if valid(n) {
+        return Some(n);
+    }
+    let ((u0, u1), (v0, v1)) = range_tuple(surface)?;
+    let (uc, vc) = ((u0 + u1) / 2.0, (v0 + v1) / 2.0);
+    [1.0e-8, 1.0e-6, 1.0e-4]
+        .into_iter()
+        .map(|eps| surface.normal(u + (uc - u) * eps, v + (vc - v) * eps))
+        .find(|n| valid(*n))
+}
+
+/// Searches the parameter of the nearest point on the surface. The parameter of the point itself is searched if the
+/// nearest point is not found, e.g. at the degenerate points of the surfaces of revolution.
+pub(crate) fn search_parameter(
+    surface: &Surface,
+    point: Point3,
+    hint: Option<(f64, f64)>,
+) -> Option<(f64, f64)> {
+    // the searches may diverge around the degenerate points. The parameters slightly out of the ranges are
+    // accepted since the points are searched on the extensions of the surfaces, and the planes are not bounded.
+    let in_range = |(u, v): (f64, f64)| match (surface, range_tuple(surface)) {
+        (Surface::Plane(_), _) | (_, None) => u.is_finite() && v.is_finite(),
+        (_, Some(((u0, u1), (v0, v1)))) => {
+            let (du, dv) = (u1 - u0, v1 - v0);
+            u0 - du <= u && u <= u1 + du && v0 - dv <= v && v <= v1 + dv
+        }
+    };
+    let nearest = surface.search_nearest_parameter(point, hint, 100);
+    nearest
+        .filter(|uv| in_range(*uv))
+        .or_else(|| surface.search_parameter(point, hint, 100))
+}
+
+/// Approximates `curve` on the range by a quadratic uniform B-spline curve within `tol`.
+pub(crate) fn approximate_curve(
+    curve: impl Fn(f64) -> Option<Point3>,
+    (t0, t1): (f64, f64),
+    tol: f64,
+) -> Option<BSplineCurve<Point3>> {
+    (1..=APPROXIMATION_TRIALS).find_map(|trial| {
+        let division = 1 << trial;
+        let mut knot_vec = KnotVec::uniform_knot(2, division);
+        knot_vec.transform(t1 - t0, t0);
+        let param = |i: f64| t0 + (t1 - t0) * i / (division + 1) as f64;
+        let points = (0..division + 2)
+            .map(|i| Some((param(i as f64), curve(param(i as f64))?)))
+            .collect::<Option<Vec<_>>>()?;
+        let bsp = BSplineCurve::try_interpole(knot_vec, points).ok()?;
+        let approximated = (0..division + 1).all(|i| {
+            let t = param(i as f64 + 0.5);
+            matches!(curve(t), Some(pt) if pt.distance(bsp.subs(t)) < tol)
+        });
+        approximated.then_some(bsp)
+    })
+}
+
+/// The similar copy of `curve`, the image by a uniform scaling and a translation, passing through the points of
+/// `target` at the parameters dividing the range into `division`. Returns `None` if the points are not similar to the
+/// ones of `curve`.
+pub(crate) fn similar_curve<C>(
+    curve: &C,
+    target: impl Fn(f64) -> Option<Point3>,
+    division: usize,
+) -> Option<C>
+where
+    C: BoundedCurve<Point = Point3> + Transformed<Matrix4>,
+{
+    let (t0, t1) = curve.range_tuple();
+    let samples = (0..=division)
+        .map(|i| {
+            let t = t0 + (t1 - t0) * i as f64 / division as f64;
+            Some((curve.subs(t), target(t)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let (p0, q0) = samples[0];
+    let (pf, qf) = *samples
+        .iter()
+        .max_by(|(p, _), (q, _)| p.distance2(p0).total_cmp(&q.distance2(p0)))?;
+    let scale = (qf - q0).dot(pf - p0) / (pf - p0).magnitude2();
+    let translation = q0 - p0 * scale;
+    let similar = samples
+        .iter()
+        .all(|(p, q)| (p.to_vec() * scale + translation).near(&q.to_vec()));
+    let mat = Matrix4::from_translation(translation) * Matrix4::from_scale(scale);
+    similar.then(|| curve.transformed(mat))
+}
+
+/// Approximates `surface` on the ranges by a quadratic uniform B-spline surface within `tol`. The control points
+/// are interpolated along the `u`-direction first, and then along the `v`-direction.
+fn approximate_surface(
+    surface: impl Fn(f64, f64) -> Option<Point3>,
+    ((u0, u1), (v0, v1)): ((f64, f64), (f64, f64)),
+    tol: f64,
+) -> Option<BSplineSurface<Point3>> {
+    (1..=APPROXIMATION_TRIALS).find_map(|trial| {
+        let division = 1 << trial;
+        let knot_vec = |t0: f64, t1: f64| {
+            let mut knot_vec = KnotVec::uniform_knot(2, division);
+            knot_vec.transform(t1 - t0, t0);
+            knot_vec
+        };
+        let (uknot, vknot) = (knot_vec(u0, u1), knot_vec(v0, v1));
+        let uparam = |i: f64| u0 + (u1 - u0) * i / (division + 1) as f64;
+        let vparam = |j: f64| v0 + (v1 - v0) * j / (division + 1) as f64;
+        let rows = (0..division + 2)
+            .map(|j| {
+                let v = vparam(j as f64);
+                let points = (0..division + 2)
+                    .map(|i| Some((uparam(i as f64), surface(uparam(i as f64), v)?)))
+                    .collect::<Option<Vec<_>>>()?;
+                BSplineCurve::try_interpole(uknot.clone(), points).ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let control_points = (0..division + 2)
+            .map(|i| {
+                let points = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(j, row)| (vparam(j as f64), *row.control_point(i)))
+                    .collect::<Vec<_>>();
+                let column = BSplineCurve::try_interpole(vknot.clone(), points).ok()?;
+                Some(column.control_points().clone())
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let bsp = BSplineSurface::try_new((uknot, vknot), control_points).ok()?;
+        let approximated = (0..division + 1).all(|i| {
+            (0..division + 1).all(|j| {
+                let (u, v) = (uparam(i as f64 + 0.5), vparam(j as f64 + 0.5));
+                matches!(surface(u, v), Some(pt) if pt.distance(bsp.subs(u, v)) < tol)
+            })
+        });
+        approximated.then_some(bsp)
+    })
+}
+
+/// Creates the intersection curve of the two surfaces with the leader through `points`. The exact curve is returned
+/// if it is found, and the leader is approximated by a B-spline curve within `tol` otherwise.
+pub(crate) fn intersection_curve(
+    surfaces: [&Surface; 2],
+    points: Vec<Point3>,
+    tol: f64,
+) -> Option<Curve> {
+    let (surface0, surface1) = (surfaces[0].clone(), surfaces[1].clone());
+    let ic = IntersectionCurve::new(surface0, surface1, PolylineCurve(points));
+    if let Some(curve) = exact_curve(&ic) {
+        return Some(curve);
+    }
+    let range = ic.range_tuple();
+    let bsp = BSplineCurve::quadratic_approximation(&ic, range, tol, 100)?;
+    let (surface0, surface1, _) = ic.destruct();
+    Some(IntersectionCurve::new(surface0, surface1, bsp).into())
+}
+
+/// Offsets the surface of revolution by offsetting the revolved curve in the meridian plane. Returns `None` if the
+/// transform of the surface is not a similarity.
+fn offset_revolution(
+    surface: &Processor<RevolutedCurve<Curve>, Matrix4>,
+    distance: f64,
+    tol: f64,
+) -> Option<Surface> {
+    let trans = *surface.transform();
+    let mat = Matrix3::from_cols(trans.x.truncate(), trans.y.truncate(), trans.z.truncate());
+    let mtm = mat.transpose() * mat;
+    let scale2 = mtm.x.x;
+    let similar = (0..3).all(|i| {
+        (0..3).all(|j| {
+            let expected = if i == j { scale2 } else { 0.0 };
+            f64::abs(mtm[i][j] - expected) < TOLERANCE * scale2
+        })
+    });
+    if !similar {
+        return None;
+    }
+
+    let entity = surface.entity();
+    let curve = entity.entity_curve();
+    let (t0, t1) = curve.range_tuple();
+    let param = |t: f64| match surface.orientation() {
+        true => (t, 0.0),
+        false => (0.0, t),
+    };
+    let subs = |t: f64| {
+        let (u, v) = param(t);
+        surface.subs(u, v)
+    };
+    let offset_curve = match curve {
+        // the normals along the line are parallel in the meridian plane.
+        Curve::Line(_) => {
+            let n = normal(surface, param((t0 + t1) / 2.0))?;
+            Curve::Line(Line(subs(t0) + distance * n, subs(t1) + distance * n))
+        }
+        _ => {
+            let profile = |t: f64| Some(subs(t) + distance * normal(surface, param(t))?);
+            Curve::BSplineCurve(approximate_curve(profile, (t0, t1), tol)?)
+        }
+    };
+    let origin = trans.transform_point(entity.origin());
+    let axis = trans.transform_vector(entity.axis()).normalize();
+    let mut offset = Processor::new(RevolutedCurve::by_revolution(offset_curve, origin, axis));
+    if !surface.orientation() {
+        offset.invert();
+    }
+    Some(Surface::RevolutedCurve(offset))
+}
+
+/// Offsets `surface` by `distance` along its normal. Planes are translated, and surfaces of revolution are
+/// revolutions of the offset curves. The other surfaces are approximated by quadratic B-spline surfaces within
+/// `tol`. The normals of the offset surface are oriented to the same side as the ones of `surface`.
+pub(super) fn offset_surface(surface: &Surface, distance: f64, tol: f64) -> Option<Surface> {
+    if let Surface::Plane(plane) = surface {
+        let trans = Matrix4::from_translation(plane.normal() * distance);
+        return Some(Surface::Plane(plane.transformed(trans)));
+    }
+    let revolution = match surface {
+        Surface::RevolutedCurve(surface) => offset_revolution(surface, distance, tol),
+        _ => None,
+    };
+    let mut offset = match revolution {
+        Some(offset) => offset,
+        None => {
+            let range = range_tuple(surface)?;
+            let exact =
+                |u: f64, v: f64| Some(surface.subs(u, v) + distance * normal(surface, (u, v))?);
+            Surface::BSplineSurface(approximate_surface(exact, range, tol)?)
+        }
+    };
+
+    let ((u0, u1), (v0, v1)) = range_tuple(surface)?;
+    let uv = ((u0 + u1) / 2.0, (v0 + v1) / 2.0);
+    let n = normal(surface, uv)?;
+    let pt = surface.subs(uv.0, uv.1) + distance * n;
+    let (u, v) = offset.search_nearest_parameter(pt, None, 100)?;
+    if offset.subs(u, v).distance(pt) > tol {
+        return None;
+    }
+    if normal(&offset, (u, v))?.dot(n) < 0.0 {
+        offset.invert();
+    }
+    Some(offset)
+}
+
+/// Searches the common point of `surfaces` and `planes`, the pairs of the points and the normals, from `point` by
+/// the Gauss-Newton method for the distances to the tangent planes. `hints` are the hints of the parameters on the
+/// surfaces, and are updated to the parameters of the common point. Returns `None` if the distance from the point
+/// to a surface or a plane is larger than `tol`.
+pub(crate) fn common_point(
+    surfaces: &[&Surface],
+    planes: &[(Point3, Vector3)],
+    mut point: Point3,
+    hints: &mut [Option<(f64, f64)>],
+    tol: f64,
+) -> Option<Point3> {
+    for _ in 0..100 {
+        let mut constraints = planes.to_vec();
+        for (surface, hint) in surfaces.iter().zip(&mut *hints) {
+            let (u, v) = search_parameter(surface, point, *hint)?;
+            *hint = Some((u, v));
+            constraints.push((surface.subs(u, v), normal(*surface, (u, v))?));
+        }
+        // the small damping fixes the point along the directions which no constraints restrict.
+        let init = (Matrix3::identity() * 1.0e-10, Vector3::zero());
+        let (mat, vec) = constraints.iter().fold(init, |(mat, vec), (origin, n)| {
+            let outer = Matrix3::from_cols(n * n.x, n * n.y, n * n.z);
+            (mat + outer, vec + n * n.dot(point - origin))
+        });
+        let step = mat.invert()? * vec;
+        point -= step;
+        if step.magnitude() < TOLERANCE * 1.0e-2 {
+            break;
+        }
+    }
+    let on_surfaces = surfaces.iter().zip(hints).all(|(surface, hint)| {
+        *hint = search_parameter(surface, point, *hint);
+        matches!(*hint, Some((u, v)) if surface.subs(u, v).distance(point) <= tol)
+    });
+    let on_planes = planes
+        .iter()
+        .all(|(origin, n)| f64::abs(n.dot(point - origin)) <= tol);
+    (on_surfaces && on_planes).then_some(point)
+}
@@ -0,0 +1,364 @@
+use crate::{errors::Error, transversal::exact_curve, Result};
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use truck_modeling::*;
+
+pub(crate) mod geometry;
+use geometry::{
+    approximate_curve, common_point, intersection_curve, normal, offset_surface, search_parameter,
+    TANGENT_ANGLE_TOLERANCE,
+};
+
+// The tolerance of the approximations of the offset geometries and of the intersections of the offset surfaces.
+const OFFSET_TOLERANCE: f64 = 1.0e-4;
+// The tolerance and the minimum number of the divisions of the leaders of the intersection curves.
+const LEADER_TOLERANCE: f64 = 1.0e-2;
+const LEADER_DIVISION: usize = 8;
+
+/// Offsets the faces of `solid` by the signed `distance` along the outward normals.
+///
+/// The positive distance grows the solid, and the negative one shrinks it. Planes are translated and the surfaces of
+/// revolution are revolutions of the offset curves. The other surfaces are approximated by B-spline surfaces. The
+/// vertices and the edges are moved to the intersections of the offset surfaces of the neighboring faces, where the
+/// edges between the tangent faces are offset along the normals. The topology of the solid is kept, so the convex
+/// edges are not rounded and the faces do not vanish.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let grown = truck_shapeops::offset(&cube, 0.5).unwrap();
+/// grown.vertex_iter().for_each(|v| {
+///     let pt = v.point();
+///     assert!([pt.x, pt.y, pt.z].iter().all(|x| x.near(&-0.5) || x.near(&1.5)));
+/// });
+/// ```
+///
+/// # Errors
+/// Returns [`Error::OffsetFailed`] if an offset surface cannot be approximated, if the offset surfaces of the faces
+/// around a vertex or an edge do not intersect, or if an edge collapses or is reversed, e.g. when the solid is shrunk
+/// more than the size of its features.
+pub fn offset(solid: &Solid, distance: f64) -> Result<Solid> {
+    let boundaries = solid
+        .boundaries()
+        .iter()
+        .map(|shell| {
+            let distances = vec![distance; shell.len()];
+            offset_shell(shell, &distances).ok_or(Error::OffsetFailed)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Solid::try_new(boundaries)?)
+}
+
+/// Hollows `solid` into a thin-walled body with `thickness`, opening the faces `remove_faces`.
+///
+/// If `thickness` is positive, the walls are inside the solid, i.e. the faces are offset inward by [`offset`] and
+/// the original faces are the outer faces of the walls. If it is negative, the walls are outside the solid. The
+/// removed faces are not offset, and their surfaces are trimmed to the rims between the outer and the inner walls.
+/// If no face of a boundary shell is removed, the shell is hollowed into a closed cavity.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // the open box without the top face
+/// let top = cube
+///     .face_iter()
+///     .find(|face| face.vertex_iter().all(|v| v.point().z.near(&1.0)))
+///     .unwrap();
+/// let open_box = truck_shapeops::shell(&cube, &[top.id()], 0.1).unwrap();
+/// // the outer faces, the inner faces and the rim
+/// assert_eq!(open_box.face_iter().count(), 11);
+/// ```
+///
+/// # Errors
+/// - If `thickness` is zero, returns [`Error::InvalidThickness`].
+/// - If one of `remove_faces` is not contained in `solid`, returns [`Error::FaceNotFound`].
+/// - If the removed faces share edges, or the faces cannot be offset, returns [`Error::OffsetFailed`].
+pub fn shell(solid: &Solid, remove_faces: &[FaceID], thickness: f64) -> Result<Solid> {
+    if thickness.so_small() {
+        return Err(Error::InvalidThickness);
+    }
+    let contained = |id: &FaceID| solid.face_iter().any(|face| face.id() == *id);
+    if !remove_faces.iter().all(contained) {
+        return Err(Error::FaceNotFound);
+    }
+
+    let mut boundaries = Vec::new();
+    for shell in solid.boundaries() {
+        let removed = shell
+            .face_iter()
+            .map(|face| remove_faces.contains(&face.id()))
+            .collect::<Vec<_>>();
+        let mut removed_edges = HashSet::default();
+        let adjacent = shell
+            .face_iter()
+            .zip(&removed)
+            .filter(|(_, removed)| **removed)
+            .flat_map(|(face, _)| face.edge_iter())
+            .any(|edge| !removed_edges.insert(edge.id()));
+        if adjacent {
+            return Err(Error::OffsetFailed);
+        }
+
+        let distances = removed
+            .iter()
+            .map(|removed| if *removed { 0.0 } else { -thickness })
+            .collect::<Vec<_>>();
+        let offset = offset_shell(shell, &distances).ok_or(Error::OffsetFailed)?;
+        let (outer, inner) = match thickness > 0.0 {
+            true => (shell.clone(), offset),
+            false => (offset, shell.clone()),
+        };
+        if !removed.contains(&true) {
+            boundaries.push(outer);
+            boundaries.push(inner.face_iter().map(Face::inverse).collect());
+            continue;
+        }
+
+        let mut faces = Vec::new();
+        for (i, removed) in removed.into_iter().enumerate() {
+            if !removed {
+                faces.push(outer[i].clone());
+                faces.push(inner[i].inverse());
+                continue;
+            }
+            // the rims between the corresponding boundaries of the outer and the inner faces.
+            let inner_boundaries = inner[i].absolute_boundaries().clone();
+            for (wire0, wire1) in outer[i].absolute_boundaries().iter().zip(inner_boundaries) {
+                let mut rim =
+                    Face::try_new(vec![wire0.clone(), wire1.inverse()], outer[i].surface())?;
+                if !outer[i].orientation() {
+                    rim.invert();
+                }
+                faces.push(rim);
+            }
+        }
+        boundaries.push(faces.into());
+    }
+    Ok(Solid::try_new(boundaries)?)
+}
+
+/// Offsets the faces of `shell` by `distances`, the distances of the faces in the order of the faces, along the
+/// outward normals.
+fn offset_shell(shell: &Shell, distances: &[f64]) -> Option<Shell> {
+    let surfaces = shell
+        .face_iter()
+        .zip(distances)
+        .map(|(face, distance)| match distance.so_small() {
+            true => Some(face.surface()),
+            false => {
+                let distance = if face.orientation() {
+                    *distance
+                } else {
+                    -distance
+                };
+                offset_surface(&face.surface(), distance, OFFSET_TOLERANCE)
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let (vertex_faces, edge_faces) = adjacent_faces(shell);
+
+    let vertices = vertex_faces
+        .into_iter()
+        .map(|(id, (vertex, faces))| {
+            let pt = vertex.point();
+            let normals = faces
+                .iter()
+                .map(|i| outward_normal(&shell[*i], pt))
+                .collect::<Option<Vec<_>>>()?;
+            let tangent = faces.iter().zip(&normals).all(|(i, n)| {
+                n.angle(normals[0]) < Rad(TANGENT_ANGLE_TOLERANCE)
+                    && distances[*i].near(&distances[faces[0]])
+            });
+            // the faces around the vertex are offset to the same surface.
+            if tangent {
+                return Some((id, Vertex::new(pt + distances[faces[0]] * normals[0])));
+            }
+            let init = faces.iter().zip(&normals).fold(pt, |init, (i, n)| {
+                init + distances[*i] * n / faces.len() as f64
+            });
+            let surfaces = faces.iter().map(|i| &surfaces[*i]).collect::<Vec<_>>();
+            let mut hints = vec![None; surfaces.len()];
+            let point = common_point(&surfaces, &[], init, &mut hints, OFFSET_TOLERANCE)?;
+            Some((id, Vertex::new(point)))
+        })
+        .collect::<Option<HashMap<_, _>>>()?;
+
+    let edges = edge_faces
+        .into_iter()
+        .map(|(id, (edge, faces))| {
+            let (front, back) = (&vertices[&edge.front().id()], &vertices[&edge.back().id()]);
+            let (p0, p1) = (edge.front().point(), edge.back().point());
+            let (q0, q1) = (front.point(), back.point());
+            let reversed = (q1 - q0).dot(p1 - p0) <= 0.0 || q0.distance(q1) <= OFFSET_TOLERANCE;
+            if p0.distance(p1) > OFFSET_TOLERANCE && reversed {
+                return None;
+            }
+            let (i, j) = (faces[0], faces[faces.len() - 1]);
+            let offset = OffsetFaces {
+                faces: [&shell[i], &shell[j]],
+                surfaces: [&surfaces[i], &surfaces[j]],
+                distances: [distances[i], distances[j]],
+            };
+            let curve = offset.curve(&edge, (q0, q1))?;
+            Some((id, Edge::try_new(front, back, curve).ok()?))
+        })
+        .collect::<Option<HashMap<_, _>>>()?;
+
+    replace_faces(shell, surfaces, &edges)
+}
+
+/// The vertices and the edges of `shell` with the indices of the faces around them. The edges are the absolute
+/// clones.
+pub(crate) type AdjacentFaces = (
+    HashMap<VertexID, (Vertex, Vec<usize>)>,
+    HashMap<EdgeID, (Edge, Vec<usize>)>,
+);
+
+/// Collects the faces around the vertices and the edges of `shell`.
+pub(crate) fn adjacent_faces(shell: &Shell) -> AdjacentFaces {
+    let mut vertex_faces = HashMap::<VertexID, (Vertex, Vec<usize>)>::default();
+    let mut edge_faces = HashMap::<EdgeID, (Edge, Vec<usize>)>::default();
+    shell.face_iter().enumerate().for_each(|(i, face)| {
+        face.edge_iter().for_each(|edge| {
+            let entry = edge_faces.entry(edge.id());
+            entry
+                .or_insert_with(|| (edge.absolute_clone(), Vec::new()))
+                .1
+                .push(i);
+            [edge.front(), edge.back()].into_iter().for_each(|v| {
+                let entry = vertex_faces.entry(v.id());
+                let faces = &mut entry.or_insert_with(|| (v.clone(), Vec::new())).1;
+                if !faces.contains(&i) {
+                    faces.push(i);
+                }
+            });
+        })
+    });
+    (vertex_faces, edge_faces)
+}
+
+/// Replaces the surfaces of the faces of `shell` by `surfaces`, and the edges by `edges`, the new absolute edges
+/// for the ids of the edges. The orientations of the faces are kept.
+pub(crate) fn replace_faces(
+    shell: &Shell,
+    surfaces: Vec<Surface>,
+    edges: &HashMap<EdgeID, Edge>,
+) -> Option<Shell> {
+    shell
+        .face_iter()
+        .zip(surfaces)
+        .map(|(face, surface)| {
+            let wires = face
+                .absolute_boundaries()
+                .iter()
+                .map(|wire| {
+                    wire.iter()
+                        .map(|edge| match edge.orientation() {
+                            true => edges[&edge.id()].clone(),
+                            false => edges[&edge.id()].inverse(),
+                        })
+                        .collect::<Wire>()
+                })
+                .collect();
+            let mut new_face = Face::try_new(wires, surface).ok()?;
+            if !face.orientation() {
+                new_face.invert();
+            }
+            Some(new_face)
+        })
+        .collect()
+}
+
+/// The outward normal of the face at the point on the face.
+pub(crate) fn outward_normal(face: &Face, point: Point3) -> Option<Vector3> {
+    let surface = face.surface();
+    let n = normal(&surface, search_parameter(&surface, point, None)?)?;
+    Some(if face.orientation() { n } else { -n })
+}
+
+// The two faces adjacent to an edge, with their offset surfaces and distances.
+struct OffsetFaces<'a> {
+    faces: [&'a Face; 2],
+    surfaces: [&'a Surface; 2],
+    distances: [f64; 2],
+}
+
+impl OffsetFaces<'_> {
+    fn normal(&self, k: usize, point: Point3) -> Option<Vector3> {
+        outward_normal(self.faces[k], point)
+    }
+
+    // The curve of the offset edge from `front` to `back`.
+    fn curve(&self, edge: &Edge, (front, back): (Point3, Point3)) -> Option<Curve> {
+        let curve = edge.curve();
+        let (t0, t1) = curve.range_tuple();
+        let middle = curve.subs((t0 + t1) / 2.0);
+        let tangent =
+            self.normal(0, middle)?.angle(self.normal(1, middle)?) < Rad(TANGENT_ANGLE_TOLERANCE);
+        let planes = matches!(self.surfaces, [Surface::Plane(_), Surface::Plane(_)]);
+        if tangent {
+            if !self.distances[0].near(&self.distances[1]) {
+                return None;
+            }
+            let distance = self.distances[0];
+            let (n0, n1) = (
+                self.normal(0, curve.front())?,
+                self.normal(0, curve.back())?,
+            );
+            let straight =
+                matches!(curve, Curve::Line(_)) && n0.angle(n1) < Rad(TANGENT_ANGLE_TOLERANCE);
+            if planes || straight {
+                return Some(Curve::Line(Line(front, back)));
+            }
+            let offset = |t: f64| {
+                let pt = curve.subs(t);
+                Some(pt + distance * self.normal(0, pt)?)
+            };
+            let bsp = approximate_curve(offset, (t0, t1), OFFSET_TOLERANCE)?;
+            Some(Curve::BSplineCurve(bsp))
+        } else if planes {
+            Some(Curve::Line(Line(front, back)))
+        } else {
+            intersection_edge_curve(&curve, self.surfaces, (front, back))
+        }
+    }
+}
+
+/// The intersection curve of `surfaces` from `front` to `back`, which are moved from the ends of `curve`. The leader
+/// of the intersection curve is searched on the planes perpendicular to `curve`.
+pub(crate) fn intersection_edge_curve(
+    curve: &Curve,
+    surfaces: [&Surface; 2],
+    (front, back): (Point3, Point3),
+) -> Option<Curve> {
+    let (t0, t1) = curve.range_tuple();
+    let (ts, _) = curve.parameter_division((t0, t1), LEADER_TOLERANCE);
+    let n = usize::max(ts.len(), LEADER_DIVISION + 1);
+    let mut hints = [None; 2];
+    let mut points = (0..n)
+        .map(|k| {
+            let t = t0 + (t1 - t0) * k as f64 / (n - 1) as f64;
+            let pt = curve.subs(t);
+            let plane = (pt, curve.der(t).normalize());
+            common_point(&surfaces, &[plane], pt, &mut hints, OFFSET_TOLERANCE)
+        })
+        .collect::<Option<Vec<_>>>()?;
+    points[0] = front;
+    points[n - 1] = back;
+    intersection_curve(surfaces, points, LEADER_TOLERANCE)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,141 @@
+use super::{offset, shell};
+use crate::errors::Error;
+use crate::test_util::{cube, cylinder, volume};
+use std::f64::consts::PI;
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+fn top_face(solid: &Solid, height: f64) -> FaceID {
+    solid
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().z.near(&height)))
+        .unwrap()
+        .id()
+}
+
+#[test]
+fn offset_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let grown = offset(&cube, 0.1).unwrap();
+    assert_eq!(grown.face_iter().count(), 6);
+    assert!(f64::abs(volume(&grown, 0.01) - 1.2 * 1.2 * 1.2) < 1.0e-6);
+    let shrunk = offset(&cube, -0.1).unwrap();
+    assert!(f64::abs(volume(&shrunk, 0.01) - 0.8 * 0.8 * 0.8) < 1.0e-6);
+    shrunk.vertex_iter().for_each(|v| {
+        let pt = v.point();
+        assert!([pt.x, pt.y, pt.z]
+            .iter()
+            .all(|x| x.near(&0.1) || x.near(&0.9)));
+    });
+}
+
+#[test]
+fn offset_cube_too_much() {
+    let cube = cube(Point3::origin(), 1.0);
+    assert_eq!(offset(&cube, -0.6).unwrap_err(), Error::OffsetFailed);
+}
+
+#[test]
+fn offset_cylinder() {
+    let solid = cylinder(Point3::origin(), 1.0, 1.0);
+    [0.2, -0.2].into_iter().for_each(|d| {
+        let offset = offset(&solid, d).unwrap();
+        assert_eq!(offset.face_iter().count(), solid.face_iter().count());
+        // compared with the cylinder of the same size, which has the same chord errors.
+        let ratio = volume(&offset, 0.01)
+            / volume(&cylinder(Point3::origin(), 1.0 + d, 1.0 + 2.0 * d), 0.01);
+        assert!(f64::abs(ratio - 1.0) < 1.0e-3, "{d}: {ratio}");
+    });
+}
+
+#[test]
+fn offset_sphere() {
+    let v = builder::vertex(Point3::new(0.0, 0.0, 1.0));
+    let wire = builder::rsweep(&v, Point3::origin(), Vector3::unit_y(), Rad(PI));
+    let shell = builder::cone(&wire, Vector3::unit_z(), Rad(7.0));
+    let sphere = Solid::new(vec![shell]);
+    let offset = offset(&sphere, 0.5).unwrap();
+    offset.face_iter().for_each(|face| {
+        let surface = face.surface();
+        let (u, v) = (0.3, 0.4);
+        assert!(f64::abs(surface.subs(u, v).to_vec().magnitude() - 1.5) < 1.0e-3);
+    });
+}
+
+#[test]
+fn shell_open_box() {
+    let cube = cube(Point3::origin(), 1.0);
+    let top = top_face(&cube, 1.0);
+    let open_box = shell(&cube, &[top], 0.1).unwrap();
+    assert_eq!(open_box.boundaries().len(), 1);
+    assert_eq!(open_box.face_iter().count(), 11);
+    assert!(f64::abs(volume(&open_box, 0.01) - (1.0 - 0.8 * 0.8 * 0.9)) < 1.0e-6);
+
+    // the walls outside the cube
+    let open_box = shell(&cube, &[top], -0.1).unwrap();
+    assert!(f64::abs(volume(&open_box, 0.01) - (1.2 * 1.2 * 1.1 - 1.0)) < 1.0e-6);
+}
+
+#[test]
+fn shell_concave_edges() {
+    let v = [
+        (0.0, 0.0),
+        (2.0, 0.0),
+        (2.0, 1.0),
+        (1.0, 1.0),
+        (1.0, 2.0),
+        (0.0, 2.0),
+    ]
+    .map(|(x, y)| builder::vertex(Point3::new(x, y, 0.0)));
+    let wire = (0..6)
+        .map(|i| builder::line(&v[i], &v[(i + 1) % 6]))
+        .collect::<Wire>();
+    let face = builder::try_attach_plane(&[wire]).unwrap();
+    let l_shape: Solid = builder::tsweep(&face, Vector3::unit_z());
+    let top = top_face(&l_shape, 1.0);
+    let open_box = shell(&l_shape, &[top], 0.1).unwrap();
+    assert_eq!(open_box.face_iter().count(), 15);
+    assert!(f64::abs(volume(&open_box, 0.01) - (3.0 - 2.24 * 0.9)) < 1.0e-6);
+}
+
+#[test]
+fn shell_closed_cavity() {
+    let cube = cube(Point3::origin(), 1.0);
+    let hollow = shell(&cube, &[], 0.1).unwrap();
+    assert_eq!(hollow.boundaries().len(), 2);
+    assert!(f64::abs(volume(&hollow, 0.01) - (1.0 - 0.8 * 0.8 * 0.8)) < 1.0e-6);
+}
+
+#[test]
+fn shell_cylinder() {
+    let cylinder = cylinder(Point3::origin(), 1.0, 1.0);
+    let top = top_face(&cylinder, 1.0);
+    let cup = shell(&cylinder, &[top], 0.1).unwrap();
+    let ratio = volume(&cup, 0.01) / (PI * (1.0 - 0.9 * 0.9 * 0.9));
+    assert!(f64::abs(ratio - 1.0) < 1.0e-2, "{ratio}");
+}
+
+#[test]
+fn shell_errors() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(2.0, 0.0, 0.0), 1.0);
+    let top = top_face(&cube0, 1.0);
+    assert_eq!(
+        shell(&cube0, &[top], 0.0).unwrap_err(),
+        Error::InvalidThickness
+    );
+    let other = top_face(&cube1, 1.0);
+    assert_eq!(
+        shell(&cube0, &[other], 0.1).unwrap_err(),
+        Error::FaceNotFound
+    );
+    let side = cube0
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().x.near(&0.0)))
+        .unwrap()
+        .id();
+    assert_eq!(
+        shell(&cube0, &[top, side], 0.1).unwrap_err(),
+        Error::OffsetFailed
+    );
+}
@@ -0,0 +1,282 @@
+use crate::errors::ShapeOpsError;
+use crate::transversal::exact_curve;
+use crate::{ShapeOpsCurve, ShapeOpsSurface};
+use truck_geometry::prelude::*;
+use truck_meshalgo::prelude::PolylineCurve;
+use truck_topology::*;
+
+mod trace;
+
+type Result<T, S> = std::result::Result<T, ShapeOpsError<S>>;
+
+/// Creates the cross-section of `solid` by `plane`.
+///
+/// The surface of each face is intersected with `plane`: the points where the boundaries cross `plane` are searched
+/// on the edge curves, and the section curves are traced from them on the surface by Newton's method, so that the
+/// traced points are on the surface and on `plane` up to [`TOLERANCE`]. The section curves are chained into the
+/// closed wires. The edges of the wires are the exact lines and conics if the surfaces are recognized by
+/// [`AnalyticSurface`](crate::AnalyticSurface), and otherwise intersection curves between the surfaces of the faces
+/// and `plane`, whose leaders are B-spline curves approximated with the tolerance `tol`. The outer boundaries of the
+/// section are counter-clockwise and the holes are clockwise with respect to the normal of `plane`.
+///
+/// The faces lying on `plane` are ignored. The section touching the solid only on its boundary may be empty.
+///
+/// # Examples
+/// ```
+/// use truck_geometry::prelude::*;
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let plane = Plane::new(
+///     Point3::new(0.0, 0.0, 0.5),
+///     Point3::new(1.0, 0.0, 0.5),
+///     Point3::new(0.0, 1.0, 0.5),
+/// );
+/// let wires = truck_shapeops::section(&cube, &plane, 0.05).unwrap();
+/// assert_eq!(wires.len(), 1);
+/// assert!(wires[0].is_closed());
+/// wires[0]
+///     .vertex_iter()
+///     .for_each(|v| assert!(f64::abs(v.point().z - 0.5) < TOLERANCE));
+/// ```
+/// # Errors
+/// Returns the error with the face whose section cannot be traced, or with the polyline of the section curve which
+/// cannot be approximated.
+pub fn section<C, S>(
+    solid: &Solid<Point3, C, S>,
+    plane: &Plane,
+    tol: f64,
+) -> Result<Vec<Wire<Point3, C>>, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface + From<Plane>,
+{
+    let mut wires = Vec::new();
+    for shell in solid.boundaries() {
+        wires.extend(shell_section(shell, plane, tol)?);
+    }
+    Ok(wires)
+}
+
+/// Creates the cross-section of `shell` by `plane`.
+///
+/// The same as [`section`], but the wires are not closed if `shell` is not closed.
+pub fn shell_section<C, S>(
+    shell: &Shell<Point3, C, S>,
+    plane: &Plane,
+    tol: f64,
+) -> Result<Vec<Wire<Point3, C>>, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface + From<Plane>,
+{
+    nonpositive_tolerance!(tol);
+    let plane_surface = S::from(*plane);
+    let mut vertices: Vec<Vertex<Point3>> = Vec::new();
+    let mut vertex = |pt: Point3| match vertices.iter().find(|v| v.point().near(&pt)) {
+        Some(v) => v.clone(),
+        None => {
+            let v = Vertex::new(pt);
+            vertices.push(v.clone());
+            v
+        }
+    };
+    let mut edges = Vec::new();
+    for face in shell.face_iter() {
+        let polylines = trace::face_section(face, plane, tol)
+            .ok_or(ShapeOpsError::SectionFailed { face: face.id() })?;
+        let surface = face.oriented_surface();
+        for polyline in polylines {
+            for polyline in split_closed(polyline) {
+                let (v0, v1) = (vertex(polyline[0]), vertex(polyline[polyline.len() - 1]));
+                let curve = approximate(&surface, &plane_surface, polyline, tol)
+                    .ok_or(ShapeOpsError::SectionFailed { face: face.id() })?;
+                edges.push(Edge::new(&v0, &v1, curve));
+            }
+        }
+    }
+    Ok(chain(edges))
+}
+
+/// Creates the planar faces of the cross-section of `solid` by `plane`.
+///
+/// The boundaries are the wires created by [`section`], and each hole is attached to the smallest outer boundary
+/// surrounding it. The surfaces of the faces are `plane`.
+///
+/// # Examples
+/// ```
+/// use truck_geometry::prelude::*;
+/// use truck_modeling::*;
+///
+/// // a cube with a square hole
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x() * 3.0);
+/// let f = builder::tsweep(&e, Vector3::unit_y() * 3.0);
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+/// let v = builder::vertex(Point3::new(1.0, 1.0, -1.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let mut bar: Solid = builder::tsweep(&f, Vector3::unit_z() * 3.0);
+/// bar.not();
+/// let punched = truck_shapeops::and(&cube, &bar, 0.05).unwrap();
+///
+/// let plane = Plane::new(
+///     Point3::new(0.0, 0.0, 0.5),
+///     Point3::new(1.0, 0.0, 0.5),
+///     Point3::new(0.0, 1.0, 0.5),
+/// );
+/// let faces = truck_shapeops::section_faces(&punched, &plane, 0.05).unwrap();
+/// assert_eq!(faces.len(), 1);
+/// assert_eq!(faces[0].boundaries().len(), 2);
+/// ```
+/// # Errors
+/// - Returns the errors of [`section`].
+/// - If some wires of the section are not closed, returns [`ShapeOpsError::SectionNotClosed`].
+/// - If a hole of the section is not enclosed by any outer boundary, or the wires are not simple, returns
+///   [`ShapeOpsError::InvalidSectionFaces`].
+pub fn section_faces<C, S>(
+    solid: &Solid<Point3, C, S>,
+    plane: &Plane,
+    tol: f64,
+) -> Result<Vec<Face<Point3, C, S>>, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface + From<Plane>,
+{
+    let wires = section(solid, plane, tol)?;
+    if !wires.iter().all(Wire::is_closed) {
+        return Err(ShapeOpsError::SectionNotClosed);
+    }
+    let polygons = wires
+        .iter()
+        .map(|wire| plane_polygon(wire, plane, tol))
+        .collect::<Vec<_>>();
+    let areas = polygons.iter().map(|p| signed_area(p)).collect::<Vec<_>>();
+    let mut boundaries = wires
+        .iter()
+        .zip(&areas)
+        .filter(|(_, area)| **area > 0.0)
+        .map(|(wire, _)| vec![wire.clone()])
+        .collect::<Vec<_>>();
+    let outers = (0..wires.len())
+        .filter(|&i| areas[i] > 0.0)
+        .collect::<Vec<_>>();
+    for i in (0..wires.len()).filter(|&i| areas[i] <= 0.0) {
+        let outer = outers
+            .iter()
+            .enumerate()
+            .filter(|(_, &j)| winding_number(&polygons[j], polygons[i][0]) != 0)
+            .min_by(|(_, &j0), (_, &j1)| areas[j0].total_cmp(&areas[j1]));
+        let (k, _) = outer.ok_or(ShapeOpsError::InvalidSectionFaces)?;
+        boundaries[k].push(wires[i].clone());
+    }
+    boundaries
+        .into_iter()
+        .map(|boundaries| {
+            Face::try_new(boundaries, S::from(*plane))
+                .map_err(|_| ShapeOpsError::InvalidSectionFaces)
+        })
+        .collect()
+}
+
+// Closed polylines are divided into two, since the edges cannot be closed. The polylines shrinking to a point are
+// removed.
+fn split_closed(polyline: PolylineCurve<Point3>) -> Vec<PolylineCurve<Point3>> {
+    if polyline.iter().all(|pt| pt.near(&polyline[0])) {
+        return Vec::new();
+    }
+    let len = polyline.len();
+    match polyline[0].near(&polyline[len - 1]) {
+        true => vec![
+            PolylineCurve(polyline[..=len / 2].to_vec()),
+            PolylineCurve(polyline[len / 2..].to_vec()),
+        ],
+        false => vec![polyline],
+    }
+}
+
+fn approximate<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    surface: &S,
+    plane_surface: &S,
+    polyline: PolylineCurve<Point3>,
+    tol: f64,
+) -> Option<C> {
+    let ic = IntersectionCurve::new(surface.clone(), plane_surface.clone(), polyline);
+    if let Some(curve) = exact_curve(&ic) {
+        return Some(curve);
+    }
+    let bsp = BSplineCurve::quadratic_approximation(&ic, ic.range_tuple(), tol, 100)?;
+    Some(IntersectionCurve::new(surface.clone(), plane_surface.clone(), bsp).into())
+}
+
+// Chains the edges into the wires.
+fn chain<C>(mut edges: Vec<Edge<Point3, C>>) -> Vec<Wire<Point3, C>> {
+    let mut wires = Vec::new();
+    while let Some(edge) = edges.pop() {
+        let mut wire: Wire<Point3, C> = vec![edge].into();
+        while let Some(i) = edges
+            .iter()
+            .position(|edge| Some(edge.front()) == wire.back_vertex())
+        {
+            wire.push_back(edges.swap_remove(i));
+        }
+        while let Some(i) = edges
+            .iter()
+            .position(|edge| Some(edge.back()) == wire.front_vertex())
+        {
+            wire.push_front(edges.swap_remove(i));
+        }
+        wires.push(wire);
+    }
+    wires
+}
+
+// The polygon of `wire` in the parameter space of `plane`.
+fn plane_polygon<C>(wire: &Wire<Point3, C>, plane: &Plane, tol: f64) -> Vec<Point2>
+where C: BoundedCurve + ParameterDivision1D<Point = Point3> + Invertible {
+    wire.edge_iter()
+        .flat_map(|edge| {
+            let curve = edge.oriented_curve();
+            let (_, mut pts) = curve.parameter_division(curve.range_tuple(), tol);
+            pts.pop();
+            pts
+        })
+        .map(|pt| {
+            let prm = plane.get_parameter(pt);
+            Point2::new(prm[0], prm[1])
+        })
+        .collect()
+}
+
+fn signed_area(polygon: &[Point2]) -> f64 {
+    let len = polygon.len();
+    (0..len)
+        .map(|i| {
+            let (p, q) = (polygon[i], polygon[(i + 1) % len]);
+            p.x * q.y - p.y * q.x
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+fn winding_number(polygon: &[Point2], pt: Point2) -> isize {
+    let len = polygon.len();
+    (0..len)
+        .map(|i| {
+            let (p, q) = (polygon[i] - pt, polygon[(i + 1) % len] - pt);
+            let cross = p.x * q.y - p.y * q.x;
+            match (p.y <= 0.0, q.y <= 0.0) {
+                (true, false) if cross > 0.0 => 1,
+                (false, true) if cross < 0.0 => -1,
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,214 @@
+use super::*;
+use crate::test_util::cube;
+use truck_modeling::{builder, Curve, Surface};
+
+fn horizontal_plane(z: f64) -> Plane {
+    Plane::new(
+        Point3::new(0.0, 0.0, z),
+        Point3::new(1.0, 0.0, z),
+        Point3::new(0.0, 1.0, z),
+    )
+}
+
+#[test]
+fn section_of_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let plane = horizontal_plane(0.5);
+    let wires = section(&cube, &plane, 0.05).unwrap();
+    assert_eq!(wires.len(), 1);
+    assert!(wires[0].is_closed());
+    assert_eq!(wires[0].len(), 4);
+    wires[0].edge_iter().for_each(|edge| {
+        let curve = edge.oriented_curve();
+        let (t0, t1) = curve.range_tuple();
+        (0..=10).for_each(|i| {
+            let pt = curve.subs(t0 + (t1 - t0) * i as f64 / 10.0);
+            assert_near!(pt.z, 0.5);
+            let on_side = [pt.x, pt.y]
+                .into_iter()
+                .any(|x| x.near(&0.0) || x.near(&1.0));
+            assert!(on_side, "{pt:?}");
+        })
+    });
+
+    let faces = section_faces(&cube, &plane, 0.05).unwrap();
+    assert_eq!(faces.len(), 1);
+    assert_eq!(faces[0].boundaries().len(), 1);
+}
+
+#[test]
+fn section_of_cylinder() {
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let cylinder = builder::tsweep(&f, Vector3::unit_z() * 2.0);
+    let wires = section(&cylinder, &horizontal_plane(1.0), 0.01).unwrap();
+    assert_eq!(wires.len(), 1);
+    assert!(wires[0].is_closed());
+    wires[0].edge_iter().for_each(|edge| {
+        let curve = edge.oriented_curve();
+        let (t0, t1) = curve.range_tuple();
+        (0..=10).for_each(|i| {
+            let pt = curve.subs(t0 + (t1 - t0) * i as f64 / 10.0);
+            assert!(
+                f64::abs(pt.to_vec().truncate().magnitude() - 1.0) < 0.01,
+                "{pt:?}"
+            );
+            assert_near!(pt.z, 1.0);
+        })
+    });
+    // counter-clockwise with respect to the normal of the plane
+    let polygon = plane_polygon(&wires[0], &horizontal_plane(1.0), 0.01);
+    assert!(f64::abs(signed_area(&polygon) - std::f64::consts::PI) < 0.05);
+}
+
+#[test]
+fn section_of_sphere() {
+    let sphere = builder::sphere(Point3::origin(), 1.0).unwrap();
+    let plane = horizontal_plane(0.5);
+    let wires = section(&sphere, &plane, 0.01).unwrap();
+    assert_eq!(wires.len(), 1);
+    assert!(wires[0].is_closed());
+    wires[0].edge_iter().for_each(|edge| {
+        let curve = edge.oriented_curve();
+        let (t0, t1) = curve.range_tuple();
+        (0..=10).for_each(|i| {
+            let pt = curve.subs(t0 + (t1 - t0) * i as f64 / 10.0);
+            // the points are on the exact intersection, not on the tessellation.
+            assert!(f64::abs(pt.to_vec().magnitude() - 1.0) < 1.0e-6, "{pt:?}");
+            assert_near!(pt.z, 0.5);
+        })
+    });
+    let polygon = plane_polygon(&wires[0], &plane, 0.01);
+    assert!(f64::abs(signed_area(&polygon) - 0.75 * std::f64::consts::PI) < 0.05);
+}
+
+#[test]
+fn section_inside_face() {
+    // the bump whose top is cut off by the plane without touching the boundary
+    let control_points = (0..4)
+        .map(|i| {
+            (0..4)
+                .map(|j| {
+                    let z = match (1..3).contains(&i) && (1..3).contains(&j) {
+                        true => 2.0,
+                        false => 0.0,
+                    };
+                    Point3::new(i as f64, j as f64, z)
+                })
+                .collect()
+        })
+        .collect();
+    let knot_vecs = (KnotVec::bezier_knot(3), KnotVec::bezier_knot(3));
+    let surface = BSplineSurface::new(knot_vecs, control_points);
+    let curves = surface.splitted_boundary();
+    let vertices = curves
+        .iter()
+        .map(|curve| Vertex::new(curve.front()))
+        .collect::<Vec<_>>();
+    let wire: Wire<Point3, Curve> = curves
+        .into_iter()
+        .enumerate()
+        .map(|(i, curve)| Edge::new(&vertices[i], &vertices[(i + 1) % 4], curve.into()))
+        .collect();
+    let face = Face::new(vec![wire], Surface::BSplineSurface(surface));
+    let shell: Shell<Point3, Curve, Surface> = vec![face].into();
+
+    let plane = horizontal_plane(0.5);
+    let wires = shell_section(&shell, &plane, 0.01).unwrap();
+    assert_eq!(wires.len(), 1);
+    assert!(wires[0].is_closed());
+    wires[0]
+        .vertex_iter()
+        .for_each(|v| assert_near!(v.point().z, 0.5));
+    // the material below the bump is on the left.
+    let polygon = plane_polygon(&wires[0], &plane, 0.01);
+    assert!(signed_area(&polygon) > 0.0);
+}
+
+#[test]
+fn section_of_hollow_solid() {
+    let outer = cube(Point3::origin(), 3.0);
+    let mut cavity = cube(Point3::new(1.0, 1.0, 1.0), 1.0);
+    cavity.not();
+    let hollow = Solid::new(vec![
+        outer.into_boundaries().pop().unwrap(),
+        cavity.into_boundaries().pop().unwrap(),
+    ]);
+    let plane = horizontal_plane(1.5);
+
+    let wires = section(&hollow, &plane, 0.05).unwrap();
+    assert_eq!(wires.len(), 2);
+    let faces = section_faces(&hollow, &plane, 0.05).unwrap();
+    assert_eq!(faces.len(), 1);
+    assert_eq!(faces[0].boundaries().len(), 2);
+    let area = faces[0]
+        .boundaries()
+        .iter()
+        .map(|wire| signed_area(&plane_polygon(wire, &plane, 0.05)))
+        .sum::<f64>();
+    assert_near!(area, 8.0);
+}
+
+#[test]
+fn section_hole_without_outer() {
+    let mut cavity = cube(Point3::origin(), 1.0);
+    cavity.not();
+    let plane = horizontal_plane(0.5);
+    assert_eq!(section(&cavity, &plane, 0.05).unwrap().len(), 1);
+    assert!(matches!(
+        section_faces(&cavity, &plane, 0.05),
+        Err(ShapeOpsError::InvalidSectionFaces)
+    ));
+}
+
+#[test]
+fn section_of_two_components() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(2.0, 0.0, 0.0), 1.0);
+    let solid = Solid::new(vec![
+        cube0.into_boundaries().pop().unwrap(),
+        cube1.into_boundaries().pop().unwrap(),
+    ]);
+    let faces = section_faces(&solid, &horizontal_plane(0.5), 0.05).unwrap();
+    assert_eq!(faces.len(), 2);
+    assert!(faces.iter().all(|face| face.boundaries().len() == 1));
+}
+
+#[test]
+fn section_missing_solid() {
+    let cube = cube(Point3::origin(), 1.0);
+    let wires = section(&cube, &horizontal_plane(2.0), 0.05).unwrap();
+    assert!(wires.is_empty());
+}
+
+#[test]
+fn section_of_open_shell() {
+    let cube = cube(Point3::origin(), 1.0);
+    let mut shell = cube.into_boundaries().pop().unwrap();
+    // removes the top face
+    let top = shell
+        .iter()
+        .position(|face| {
+            face.boundaries()[0]
+                .vertex_iter()
+                .all(|v| v.point().z.near(&1.0))
+        })
+        .unwrap();
+    shell.remove(top);
+    let plane = Plane::new(
+        Point3::new(0.5, 0.0, 0.0),
+        Point3::new(0.5, 1.0, 0.0),
+        Point3::new(0.5, 0.0, 1.0),
+    );
+    let wires = shell_section(&shell, &plane, 0.05).unwrap();
+    assert_eq!(wires.len(), 1);
+    assert!(!wires[0].is_closed());
+    assert_eq!(wires[0].len(), 3);
+
+    let solid = Solid::new_unchecked(vec![shell]);
+    assert!(matches!(
+        section_faces(&solid, &plane, 0.05),
+        Err(ShapeOpsError::SectionNotClosed)
+    ));
+}
@@ -0,0 +1,345 @@
+use super::winding_number;
+use crate::ShapeOpsSurface;
+use truck_geometry::prelude::*;
+use truck_meshalgo::prelude::PolylineCurve;
+use truck_topology::*;
+
+// The maximum number of the steps tracing a section curve.
+const MAX_STEPS: usize = 10_000;
+// The number of the trials of Newton's method and of the bisection.
+const TRIALS: usize = 100;
+// The ratio of the maximum step to the size of the face.
+const MAX_STEP_RATIO: f64 = 0.1;
+// The cosine of the maximum turn of the direction in one step.
+const MAX_TURN_COS: f64 = 0.8;
+
+// A point where the section curve enters or exits the face through its boundaries.
+#[derive(Clone, Copy, Debug)]
+struct Crossing {
+    point: Point3,
+    uv: Point2,
+    entry: bool,
+}
+
+// Traces the section curves on `surface` by the plane through `origin` with the normal `normal`.
+struct Tracer<'a, S> {
+    surface: &'a S,
+    origin: Point3,
+    normal: Vector3,
+    tol: f64,
+    max_step: f64,
+}
+
+/// Creates the section curves of `face` by `plane` as the polylines whose points are on the surface and on `plane`.
+///
+/// The curves are traced on the surface from the points where the boundaries cross `plane`, which are searched on the
+/// edge curves. The closed curves inside the face are traced from the points found on the parameter division of the
+/// surface. The polylines are oriented along the normal of `plane` times the normal of the face, so that the material
+/// behind the face is on the left viewed from the normal of `plane`, and the closed ones end at their first points.
+///
+/// Returns `None` if a curve cannot be traced.
+pub(super) fn face_section<C, S>(
+    face: &Face<Point3, C, S>,
+    plane: &Plane,
+    tol: f64,
+) -> Option<Vec<PolylineCurve<Point3>>>
+where
+    C: ParametricCurve3D + BoundedCurve + ParameterDivision1D<Point = Point3> + Invertible,
+    S: ShapeOpsSurface,
+{
+    let surface = face.oriented_surface();
+    let mut tracer = Tracer {
+        surface: &surface,
+        origin: plane.origin(),
+        normal: plane.normal(),
+        tol,
+        max_step: 0.0,
+    };
+    let (crossings, polygons, size) = tracer.crossings(face)?;
+    tracer.max_step = size * MAX_STEP_RATIO;
+
+    let (mut entries, mut exits): (Vec<_>, Vec<_>) = crossings.into_iter().partition(|c| c.entry);
+    // the plane touches the boundary at the crossings entering and exiting at the same point.
+    entries.retain(
+        |entry| match exits.iter().position(|x| x.point.near(&entry.point)) {
+            Some(i) => {
+                exits.swap_remove(i);
+                false
+            }
+            None => true,
+        },
+    );
+    let mut polylines = Vec::new();
+    for entry in entries {
+        let ends = exits.iter().map(|x| x.point).collect::<Vec<_>>();
+        let (points, i) = tracer.trace((entry.point, entry.uv), &ends, false)?;
+        exits.swap_remove(i);
+        polylines.push(PolylineCurve(points));
+    }
+    if !exits.is_empty() {
+        return None;
+    }
+
+    for (point, uv) in tracer.seeds(&polygons) {
+        let traced = polylines.iter().any(|polyline| {
+            let near = |pts: &[Point3]| distance_to_segment(point, pts[0], pts[1]) < 2.0 * tol;
+            polyline.windows(2).any(near)
+        });
+        if !traced {
+            let (points, _) = tracer.trace((point, uv), &[point], true)?;
+            polylines.push(PolylineCurve(points));
+        }
+    }
+    Some(polylines)
+}
+
+impl<S: ShapeOpsSurface> Tracer<'_, S> {
+    fn height(&self, pt: Point3) -> f64 { (pt - self.origin).dot(self.normal) }
+
+    // The points on `plane` are regarded as the ones above `plane`.
+    fn below(&self, pt: Point3) -> bool {
+        let height = self.height(pt);
+        !height.so_small() && height < 0.0
+    }
+
+    fn search(&self, pt: Point3, hint: Option<Point2>) -> Option<Point2> {
+        let hint = hint.map(Into::into);
+        let surface = self.surface;
+        let (u, v) = surface
+            .search_parameter(pt, hint, TRIALS)
+            .or_else(|| surface.search_nearest_parameter(pt, hint, TRIALS))?;
+        Some(Point2::new(u, v))
+    }
+
+    // The unit tangent vector of the section at `uv`.
+    fn direction(&self, uv: Point2) -> Option<Vector3> {
+        let dir = self.normal.cross(self.surface.normal(uv.x, uv.y));
+        match dir.so_small() {
+            true => None,
+            false => Some(dir.normalize()),
+        }
+    }
+
+    // The crossings of the boundaries of `face`, the polygons of the boundaries in the parameter space, and the size
+    // of the bounding box of the boundaries.
+    fn crossings<C>(
+        &self,
+        face: &Face<Point3, C, S>,
+    ) -> Option<(Vec<Crossing>, Vec<Vec<Point2>>, f64)>
+    where
+        C: ParametricCurve3D + BoundedCurve + ParameterDivision1D<Point = Point3> + Invertible,
+    {
+        let mut crossings = Vec::new();
+        let mut polygons = Vec::new();
+        let mut bdb = BoundingBox::new();
+        for wire in face.boundaries() {
+            let mut polygon: Vec<Point2> = Vec::new();
+            for edge in wire.edge_iter() {
+                let curve = edge.oriented_curve();
+                let (ts, pts) = curve.parameter_division(curve.range_tuple(), self.tol);
+                for pt in &pts[..pts.len() - 1] {
+                    // the parameters are not determined at the singular points, e.g. the poles of spheres.
+                    polygon.extend(self.search(*pt, polygon.last().copied()));
+                    bdb.push(*pt);
+                }
+                for i in 1..ts.len() {
+                    let (below0, below1) = (self.below(pts[i - 1]), self.below(pts[i]));
+                    if below0 == below1 {
+                        continue;
+                    }
+                    let point = curve.subs(self.curve_root(&curve, ts[i - 1], ts[i]));
+                    crossings.push(Crossing {
+                        point,
+                        uv: self.search(point, polygon.last().copied())?,
+                        entry: below1,
+                    });
+                }
+            }
+            polygons.push(polygon);
+        }
+        Some((crossings, polygons, bdb.diameter()))
+    }
+
+    // The parameter of the point of `curve` on the plane between `t0` and `t1` on the opposite sides.
+    fn curve_root<C: ParametricCurve3D>(&self, curve: &C, t0: f64, t1: f64) -> f64 {
+        let below0 = self.below(curve.subs(t0));
+        let (mut t0, mut t1) = (t0, t1);
+        for _ in 0..TRIALS {
+            let t = (t0 + t1) / 2.0;
+            let height = self.height(curve.subs(t));
+            if height.so_small() {
+                return t;
+            }
+            match (height < 0.0) == below0 {
+                true => t0 = t,
+                false => t1 = t,
+            }
+        }
+        // the points on the plane are regarded as above the plane.
+        match below0 {
+            true => t1,
+            false => t0,
+        }
+    }
+
+    // The points of the section on the lines of the parameter division of the surface inside the polygons.
+    fn seeds(&self, polygons: &[Vec<Point2>]) -> Vec<(Point3, Point2)> {
+        let bdb: BoundingBox<Point2> = polygons.iter().flatten().collect();
+        if bdb.is_empty() {
+            return Vec::new();
+        }
+        let (min, max) = (bdb.min(), bdb.max());
+        let (us, vs) = self
+            .surface
+            .parameter_division(((min.x, max.x), (min.y, max.y)), self.tol);
+        let mut segments = Vec::new();
+        us.iter().for_each(|u| {
+            let line = vs.iter().map(|v| Point2::new(*u, *v));
+            segments.extend(line.clone().zip(line.skip(1)));
+        });
+        vs.iter().for_each(|v| {
+            let line = us.iter().map(|u| Point2::new(*u, *v));
+            segments.extend(line.clone().zip(line.skip(1)));
+        });
+        segments
+            .into_iter()
+            .filter_map(|(uv0, uv1)| {
+                let below0 = self.below(self.surface.subs(uv0.x, uv0.y));
+                let below1 = self.below(self.surface.subs(uv1.x, uv1.y));
+                if below0 == below1 {
+                    return None;
+                }
+                let line = Line(uv0, uv1);
+                let uv = line.subs(self.curve_root(&SurfaceLine(self.surface, line), 0.0, 1.0));
+                let inside = polygons
+                    .iter()
+                    .map(|p| winding_number(p, uv))
+                    .sum::<isize>()
+                    != 0;
+                match inside {
+                    true => Some((self.surface.subs(uv.x, uv.y), uv)),
+                    false => None,
+                }
+            })
+            .collect()
+    }
+
+    // Traces the section from `start` until it passes one of `ends`. Returns the points of the section with the index
+    // of the end. The closed section starting from the end is not ended within the first two steps.
+    fn trace(
+        &self,
+        start: (Point3, Point2),
+        ends: &[Point3],
+        closed: bool,
+    ) -> Option<(Vec<Point3>, usize)> {
+        let (mut pt, mut uv) = start;
+        let mut points = vec![pt];
+        let mut step = self.max_step;
+        for _ in 0..MAX_STEPS {
+            let dir = self.direction(uv)?;
+            let (next, next_uv) = loop {
+                if step < TOLERANCE {
+                    return None;
+                }
+                match self.advance(pt, uv, dir, step) {
+                    Some(res) => break res,
+                    None => step /= 2.0,
+                }
+            };
+            let end = ends
+                .iter()
+                .enumerate()
+                .filter(|_| !closed || points.len() > 2)
+                .filter(|(_, end)| {
+                    (**end - pt).dot(next - pt) > 0.0
+                        && distance_to_segment(**end, pt, next) < 2.0 * self.tol
+                })
+                .min_by(|(_, end0), (_, end1)| {
+                    pt.distance2(**end0).total_cmp(&pt.distance2(**end1))
+                });
+            if let Some((i, end)) = end {
+                points.push(*end);
+                return Some((points, i));
+            }
+            points.push(next);
+            (pt, uv) = (next, next_uv);
+            step = f64::min(step * 2.0, self.max_step);
+        }
+        None
+    }
+
+    // Advances from `pt` along `dir` by `step`. Returns `None` if the chord of the step is not within the tolerance.
+    fn advance(&self, pt: Point3, uv: Point2, dir: Vector3, step: f64) -> Option<(Point3, Point2)> {
+        let (uder, vder) = (self.surface.uder(uv.x, uv.y), self.surface.vder(uv.x, uv.y));
+        let (a, b, c) = (uder.dot(uder), uder.dot(vder), vder.dot(vder));
+        let rhs = Vector2::new(uder.dot(dir), vder.dot(dir)) * step;
+        let delta = Matrix2::new(a, b, b, c).invert()? * rhs;
+        let (next, next_uv) = self.project(uv + delta, pt + dir * step, dir)?;
+        let chord = next - pt;
+        if chord.dot(dir) <= 0.0 || self.direction(next_uv)?.dot(dir) < MAX_TURN_COS {
+            return None;
+        }
+        let middle = pt.midpoint(next);
+        let (mid, _) = self.project(uv.midpoint(next_uv), middle, chord.normalize())?;
+        match mid.distance(middle) < self.tol {
+            true => Some((next, next_uv)),
+            false => None,
+        }
+    }
+
+    // The point of the section on the plane through `pt` perpendicular to `dir`, by Newton's method from `uv`.
+    fn project(&self, mut uv: Point2, pt: Point3, dir: Vector3) -> Option<(Point3, Point2)> {
+        for _ in 0..TRIALS {
+            let point = self.surface.subs(uv.x, uv.y);
+            let value = Vector2::new(self.height(point), (point - pt).dot(dir));
+            if value.magnitude2() < TOLERANCE2 {
+                return Some((point, uv));
+            }
+            let (uder, vder) = (self.surface.uder(uv.x, uv.y), self.surface.vder(uv.x, uv.y));
+            let jacobian = Matrix2::new(
+                self.normal.dot(uder),
+                dir.dot(uder),
+                self.normal.dot(vder),
+                dir.dot(vder),
+            );
+            uv -= jacobian.invert()? * value;
+        }
+        None
+    }
+}
+
+// The curve on the surface along the line in the parameter space.
+#[derive(Clone, Debug)]
+struct SurfaceLine<'a, S>(&'a S, Line<Point2>);
+
+impl<S: ParametricSurface3D> ParametricCurve for SurfaceLine<'_, S> {
+    type Point = Point3;
+    type Vector = Vector3;
+    fn subs(&self, t: f64) -> Point3 {
+        let uv = self.1.subs(t);
+        self.0.subs(uv.x, uv.y)
+    }
+    fn der(&self, t: f64) -> Vector3 {
+        let (uv, dir) = (self.1.subs(t), self.1.der(t));
+        self.0.uder(uv.x, uv.y) * dir.x + self.0.vder(uv.x, uv.y) * dir.y
+    }
+    fn der2(&self, t: f64) -> Vector3 {
+        let (uv, dir) = (self.1.subs(t), self.1.der(t));
+        let (uu, uv_, vv) = (
+            self.0.uuder(uv.x, uv.y),
+            self.0.uvder(uv.x, uv.y),
+            self.0.vvder(uv.x, uv.y),
+        );
+        uu * dir.x * dir.x + uv_ * 2.0 * dir.x * dir.y + vv * dir.y * dir.y
+    }
+    fn parameter_range(&self) -> ParameterRange { self.1.parameter_range() }
+}
+
+fn distance_to_segment(pt: Point3, p: Point3, q: Point3) -> f64 {
+    let vec = q - p;
+    let t = match vec.so_small() {
+        true => 0.0,
+        false => f64::clamp((pt - p).dot(vec) / vec.magnitude2(), 0.0, 1.0),
+    };
+    pt.distance(p + vec * t)
+}
@@ -0,0 +1,40 @@
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+/// The cube with the edges of length `size` parallel to the axes, whose minimum corner is `origin`.
+pub(crate) fn cube(origin: Point3, size: f64) -> Solid {
+    let v = builder::vertex(origin);
+    let e = builder::tsweep(&v, Vector3::unit_x() * size);
+    let f = builder::tsweep(&e, Vector3::unit_y() * size);
+    builder::tsweep(&f, Vector3::unit_z() * size)
+}
+
+/// The cylinder along the z-axis, whose bottom center is `origin`.
+pub(crate) fn cylinder(origin: Point3, radius: f64, height: f64) -> Solid {
+    let v = builder::vertex(origin + Vector3::new(radius, 0.0, 0.0));
+    let w = builder::rsweep(&v, origin, Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    builder::tsweep(&f, Vector3::unit_z() * height)
+}
+
+/// The id of the edge between `p0` and `p1` in `solid`.
+pub(crate) fn find_edge(solid: &Solid, p0: Point3, p1: Point3) -> EdgeID {
+    solid
+        .edge_iter()
+        .find(|edge| {
+            let (q0, q1) = (edge.front().point(), edge.back().point());
+            (q0.near(&p0) && q1.near(&p1)) || (q0.near(&p1) && q1.near(&p0))
+        })
+        .unwrap()
+        .id()
+}
+
+/// The volume of `solid` triangulated with the tolerance `tol`. The triangulation must be closed.
+pub(crate) fn volume(solid: &Solid, tol: f64) -> f64 {
+    let mut poly = solid.triangulation(tol).to_polygon();
+    poly.put_together_same_attrs(TOLERANCE)
+        .remove_degenerate_faces()
+        .remove_unused_attrs();
+    assert_eq!(poly.shell_condition(), ShellCondition::Closed);
+    poly.volume()
+}
@@ -0,0 +1,655 @@
+use std::f64::consts::PI;
+use truck_geometry::prelude::*;
+use truck_meshalgo::prelude::PolylineCurve;
+
+/// Elementary surfaces, whose intersection curves are computed analytically.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElementarySurface {
+    /// plane
+    Plane {
+        /// a point on the plane
+        origin: Point3,
+        /// the unit normal vector
+        normal: Vector3,
+    },
+    /// sphere
+    Sphere {
+        /// the center of the sphere
+        center: Point3,
+        /// the radius of the sphere
+        radius: f64,
+    },
+    /// circular cylinder
+    Cylinder {
+        /// a point on the axis
+        origin: Point3,
+        /// the unit direction of the axis
+        axis: Vector3,
+        /// the radius of the cylinder
+        radius: f64,
+    },
+    /// torus
+    Torus {
+        /// the center of the torus
+        center: Point3,
+        /// the unit direction of the axis
+        axis: Vector3,
+        /// the distance between the center and the centers of the tube
+        large_radius: f64,
+        /// the radius of the tube
+        small_radius: f64,
+    },
+    /// the other surfaces of revolution
+    Revolution {
+        /// a point on the axis
+        origin: Point3,
+        /// the unit direction of the axis
+        axis: Vector3,
+    },
+}
+
+/// The surfaces which may be recognized as the elementary surfaces.
+///
+/// The intersection curves between the recognized surfaces are created as the exact lines and conics by the set
+/// operations, instead of the approximation by B-spline curves. The default implementation recognizes nothing.
+pub trait AnalyticSurface {
+    /// Returns the elementary surface including `self`.
+    fn elementary_surface(&self) -> Option<ElementarySurface> { None }
+}
+
+impl ElementarySurface {
+    /// Returns the elementary surface transformed by `mat`, if `mat` is a similar transformation.
+    pub fn transformed(self, mat: Matrix4) -> Option<Self> {
+        let linear = Matrix3::from_cols(mat.x.truncate(), mat.y.truncate(), mat.z.truncate());
+        let scale = linear.x.magnitude();
+        let similar = (linear.transpose() * linear / (scale * scale)).near(&Matrix3::identity());
+        if !similar
+            || !mat.w.w.near(&1.0)
+            || !mat.x.w.so_small()
+            || !mat.y.w.so_small()
+            || !mat.z.w.so_small()
+        {
+            return None;
+        }
+        let point = |pt: Point3| mat.transform_point(pt);
+        let vector = |vec: Vector3| (linear * vec).normalize();
+        Some(match self {
+            Self::Plane { origin, normal } => Self::Plane {
+                origin: point(origin),
+                normal: vector(normal),
+            },
+            Self::Sphere { center, radius } => Self::Sphere {
+                center: point(center),
+                radius: radius * scale,
+            },
+            Self::Cylinder {
+                origin,
+                axis,
+                radius,
+            } => Self::Cylinder {
+                origin: point(origin),
+                axis: vector(axis),
+                radius: radius * scale,
+            },
+            Self::Torus {
+                center,
+                axis,
+                large_radius,
+                small_radius,
+            } => Self::Torus {
+                center: point(center),
+                axis: vector(axis),
+                large_radius: large_radius * scale,
+                small_radius: small_radius * scale,
+            },
+            Self::Revolution { origin, axis } => Self::Revolution {
+                origin: point(origin),
+                axis: vector(axis),
+            },
+        })
+    }
+
+    /// Returns `true` if `self` and `other` are the same set of points, regardless of the orientations.
+    ///
+    /// The surfaces of revolution other than the cylinders, the spheres and the tori are never regarded as the same.
+    pub fn same_domain(self, other: Self) -> bool {
+        match (self, other) {
+            (
+                Self::Plane {
+                    origin: o0,
+                    normal: n0,
+                },
+                Self::Plane {
+                    origin: o1,
+                    normal: n1,
+                },
+            ) => near_vector(n0, n1) && (o1 - o0).dot(n0).so_small(),
+            (
+                Self::Sphere {
+                    center: c0,
+                    radius: r0,
+                },
+                Self::Sphere {
+                    center: c1,
+                    radius: r1,
+                },
+            ) => c0.near(&c1) && r0.near(&r1),
+            (
+                Self::Cylinder {
+                    origin: o0,
+                    axis: a0,
+                    radius: r0,
+                },
+                Self::Cylinder {
+                    origin: o1,
+                    axis: a1,
+                    radius: r1,
+                },
+            ) => coaxial((o0, a0), (o1, a1)) && r0.near(&r1),
+            (
+                Self::Torus {
+                    center: c0,
+                    axis: a0,
+                    large_radius: l0,
+                    small_radius: s0,
+                },
+                Self::Torus {
+                    center: c1,
+                    axis: a1,
+                    large_radius: l1,
+                    small_radius: s1,
+                },
+            ) => c0.near(&c1) && near_vector(a0, a1) && l0.near(&l1) && s0.near(&s1),
+            _ => false,
+        }
+    }
+
+    // The axis of the rotational symmetry.
+    fn axis(self) -> Option<(Point3, Vector3)> {
+        match self {
+            Self::Plane { .. } | Self::Sphere { .. } => None,
+            Self::Cylinder { origin, axis, .. } => Some((origin, axis)),
+            Self::Torus { center, axis, .. } => Some((center, axis)),
+            Self::Revolution { origin, axis } => Some((origin, axis)),
+        }
+    }
+}
+
+/// Lines and ellipses, the intersection curves between the elementary surfaces.
+#[derive(Clone, Copy, Debug)]
+enum Conic {
+    // the line through `origin` with the direction `direction`
+    Line {
+        origin: Point3,
+        direction: Vector3,
+    },
+    // the ellipse `center + a cos(t) + b sin(t)`, where `a` and `b` are orthogonal
+    Ellipse {
+        center: Point3,
+        a: Vector3,
+        b: Vector3,
+    },
+}
+
+impl Conic {
+    fn circle(center: Point3, point: Point3, normal: Vector3) -> Self {
+        let a = point - center;
+        Self::Ellipse {
+            center,
+            a,
+            b: normal.normalize().cross(a),
+        }
+    }
+
+    fn ellipse_parameter(center: Point3, a: Vector3, b: Vector3, point: Point3) -> f64 {
+        let v = point - center;
+        f64::atan2(v.dot(b) / b.magnitude2(), v.dot(a) / a.magnitude2())
+    }
+
+    fn distance(self, point: Point3) -> f64 { point.distance(self.project(point)) }
+
+    fn project(self, point: Point3) -> Point3 {
+        match self {
+            Self::Line { origin, direction } => {
+                origin + (point - origin).dot(direction) * direction
+            }
+            Self::Ellipse { center, a, b } => {
+                let t = Self::ellipse_parameter(center, a, b, point);
+                center + a * f64::cos(t) + b * f64::sin(t)
+            }
+        }
+    }
+}
+
+/// Returns the projection onto the conic including the intersection curve through `point` on the both surfaces.
+///
+/// Returns `None` if the surfaces are not recognized, or the intersection curve is not a conic.
+pub(super) fn conic_projection(
+    surface0: &impl AnalyticSurface,
+    surface1: &impl AnalyticSurface,
+    point: Point3,
+) -> Option<impl Fn(Point3) -> Point3> {
+    let surface0 = surface0.elementary_surface()?;
+    let surface1 = surface1.elementary_surface()?;
+    let conic = intersection_conic(surface0, surface1, point)?;
+    Some(move |pt| conic.project(pt))
+}
+
+fn near_vector(v0: Vector3, v1: Vector3) -> bool { v0.cross(v1).so_small() }
+
+fn coaxial((o0, a0): (Point3, Vector3), (o1, a1): (Point3, Vector3)) -> bool {
+    let v = o1 - o0;
+    near_vector(a0, a1) && (v - v.dot(a0) * a0).so_small()
+}
+
+// The conic including the intersection curve through `point`.
+fn intersection_conic(
+    surface0: ElementarySurface,
+    surface1: ElementarySurface,
+    point: Point3,
+) -> Option<Conic> {
+    use ElementarySurface as E;
+    match (surface0, surface1) {
+        (E::Plane { normal: n0, .. }, E::Plane { normal: n1, .. }) => {
+            let direction = n0.cross(n1);
+            match direction.so_small() {
+                true => None,
+                false => Some(Conic::Line {
+                    origin: point,
+                    direction: direction.normalize(),
+                }),
+            }
+        }
+        (E::Plane { origin, normal }, surface) | (surface, E::Plane { origin, normal }) => {
+            plane_intersection(origin, normal, surface, point)
+        }
+        (
+            E::Cylinder {
+                origin: o0,
+                axis: a0,
+                ..
+            },
+            E::Cylinder {
+                origin: o1,
+                axis: a1,
+                ..
+            },
+        ) if near_vector(a0, a1) && !coaxial((o0, a0), (o1, a1)) => Some(Conic::Line {
+            origin: point,
+            direction: a0,
+        }),
+        (E::Sphere { center: c0, .. }, E::Sphere { center: c1, .. }) => {
+            let axis = (c1 - c0).normalize();
+            let center = c0 + (point - c0).dot(axis) * axis;
+            Some(Conic::circle(center, point, axis))
+        }
+        (E::Sphere { center, .. }, surface) | (surface, E::Sphere { center, .. }) => {
+            let (origin, axis) = surface.axis()?;
+            let v = center - origin;
+            if !(v - v.dot(axis) * axis).so_small() {
+                return None;
+            }
+            let center = origin + (point - origin).dot(axis) * axis;
+            Some(Conic::circle(center, point, axis))
+        }
+        _ => {
+            let ((origin, axis), axis1) = (surface0.axis()?, surface1.axis()?);
+            if !coaxial((origin, axis), axis1) {
+                return None;
+            }
+            let center = origin + (point - origin).dot(axis) * axis;
+            Some(Conic::circle(center, point, axis))
+        }
+    }
+}
+
+fn plane_intersection(
+    origin: Point3,
+    normal: Vector3,
+    surface: ElementarySurface,
+    point: Point3,
+) -> Option<Conic> {
+    use ElementarySurface as E;
+    let projection = |pt: Point3| pt - (pt - origin).dot(normal) * normal;
+    match surface {
+        E::Sphere { center, .. } => Some(Conic::circle(projection(center), point, normal)),
+        E::Cylinder {
+            origin: o,
+            axis,
+            radius,
+        } => {
+            let cos = normal.dot(axis);
+            if cos.so_small() {
+                Some(Conic::Line {
+                    origin: point,
+                    direction: axis,
+                })
+            } else {
+                let center = o + (origin - o).dot(normal) / cos * axis;
+                match near_vector(normal, axis) {
+                    true => Some(Conic::circle(center, point, normal)),
+                    false => {
+                        let b = axis.cross(normal).normalize() * radius;
+                        let a = normal.cross(b).normalize() * radius / cos.abs();
+                        Some(Conic::Ellipse { center, a, b })
+                    }
+                }
+            }
+        }
+        E::Torus {
+            center,
+            axis,
+            large_radius,
+            small_radius,
+        } if normal.dot(axis).so_small() && (center - origin).dot(normal).so_small() => {
+            let direction = (point - center) - (point - center).dot(axis) * axis;
+            let tube_center = center + direction.normalize() * large_radius;
+            let a = (point - tube_center).normalize() * small_radius;
+            Some(Conic::circle(tube_center, tube_center + a, normal))
+        }
+        E::Torus { center, axis, .. }
+        | E::Revolution {
+            origin: center,
+            axis,
+        } if near_vector(normal, axis) => {
+            let cos = normal.dot(axis);
+            let center = center + (origin - center).dot(normal) / cos * axis;
+            Some(Conic::circle(center, point, normal))
+        }
+        _ => None,
+    }
+}
+
+// Rational quadratic representation of the elliptic arc `center + a cos(t) + b sin(t)` for `t0 <= t <= t1`.
+pub(crate) fn ellipse_arc(
+    center: Point3,
+    a: Vector3,
+    b: Vector3,
+    (t0, t1): (f64, f64),
+) -> NurbsCurve<Vector4> {
+    let division = f64::ceil((t1 - t0) / (PI / 2.0) - TOLERANCE).max(1.0) as usize;
+    let dt = (t1 - t0) / division as f64;
+    let w = f64::cos(dt / 2.0);
+    let point = |t: f64| center + a * f64::cos(t) + b * f64::sin(t);
+    let mut control_points = vec![point(t0).to_homogeneous()];
+    let mut knots = vec![0.0; 3];
+    for i in 0..division {
+        let (s0, s1) = (t0 + dt * i as f64, t0 + dt * (i + 1) as f64);
+        let middle = center + (a * f64::cos((s0 + s1) / 2.0) + b * f64::sin((s0 + s1) / 2.0)) / w;
+        control_points.push(middle.to_homogeneous() * w);
+        control_points.push(point(s1).to_homogeneous());
+        let knot = (i + 1) as f64 / division as f64;
+        knots.extend([knot, knot]);
+    }
+    knots.push(1.0);
+    NurbsCurve::new(BSplineCurve::new(KnotVec::from(knots), control_points))
+}
+
+/// Creates the exact curve of the intersection curve between the elementary surfaces.
+///
+/// Returns `None` if the surfaces are not recognized, or the intersection curve is not a conic.
+pub(crate) fn exact_curve<C, S>(
+    curve: &IntersectionCurve<PolylineCurve<Point3>, S, S>,
+) -> Option<C>
+where
+    C: From<Line<Point3>> + From<NurbsCurve<Vector4>>,
+    S: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3> + AnalyticSurface, {
+    let surface0 = curve.surface0().elementary_surface()?;
+    let surface1 = curve.surface1().elementary_surface()?;
+    let (t0, t1) = curve.range_tuple();
+    let t = (t0 + t1) / 2.0;
+    let (p0, p1, middle) = (curve.subs(t0), curve.subs(t1), curve.subs(t));
+    let conic = intersection_conic(surface0, surface1, middle)?;
+    if [p0, p1, middle]
+        .into_iter()
+        .any(|pt| !conic.distance(pt).so_small())
+    {
+        return None;
+    }
+    match conic {
+        Conic::Line { .. } => Some(Line(p0, p1).into()),
+        Conic::Ellipse { center, a, mut b } => {
+            let tm = Conic::ellipse_parameter(center, a, b, middle);
+            let tangent = -a * f64::sin(tm) + b * f64::cos(tm);
+            if tangent.dot(curve.der(t)) < 0.0 {
+                b = -b;
+            }
+            let parameter = |pt: Point3| Conic::ellipse_parameter(center, a, b, pt);
+            let s0 = parameter(p0);
+            let angle = |pt: Point3| match (parameter(pt) - s0).rem_euclid(2.0 * PI) {
+                x if x < TOLERANCE => 2.0 * PI,
+                x => x,
+            };
+            let (angle1, anglem) = (angle(p1), angle(middle));
+            if anglem > angle1 {
+                return None;
+            }
+            Some(ellipse_arc(center, a, b, (s0, s0 + angle1)).into())
+        }
+    }
+}
+
+impl AnalyticSurface for Plane {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        Some(ElementarySurface::Plane {
+            origin: self.origin(),
+            normal: self.normal(),
+        })
+    }
+}
+
+impl AnalyticSurface for Sphere {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        Some(ElementarySurface::Sphere {
+            center: self.center(),
+            radius: self.radius(),
+        })
+    }
+}
+
+impl AnalyticSurface for Torus {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        Some(ElementarySurface::Torus {
+            center: self.center(),
+            axis: Vector3::unit_z(),
+            large_radius: self.large_radius(),
+            small_radius: self.small_radius(),
+        })
+    }
+}
+
+impl<C> AnalyticSurface for RevolutedCurve<C>
+where C: ParametricCurve3D + BoundedCurve + ParameterDivision1D<Point = Point3>
+{
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        let (origin, axis) = (self.origin(), self.axis().normalize());
+        let curve = self.entity_curve();
+        let (t0, t1) = curve.range_tuple();
+        // The uniform samples detect the size of the curve, and the curve is divided adaptively by the size.
+        let uniform = (0..=DIVISION).map(|i| t0 + (t1 - t0) * i as f64 / DIVISION as f64);
+        let size = uniform
+            .clone()
+            .map(|t| curve.subs(t))
+            .collect::<BoundingBox<_>>()
+            .diameter();
+        let (adaptive, _) = curve.parameter_division((t0, t1), DIVISION_RATIO * size.max(1.0));
+        let mut params = uniform.chain(adaptive).collect::<Vec<_>>();
+        params.sort_by(f64::total_cmp);
+        let params = params
+            .windows(2)
+            .flat_map(|t| [t[0], (t[0] + t[1]) / 2.0])
+            .chain([t1]);
+        // the height and the radius of the points on the entity curve
+        let points = params
+            .map(|t| {
+                let v = curve.subs(t) - origin;
+                let height = v.dot(axis);
+                (height, (v - height * axis).magnitude())
+            })
+            .collect::<Vec<_>>();
+        let tol = scaled_tolerance(size);
+        let near = |a: f64, b: f64| f64::abs(a - b) < tol;
+        let (h0, r0) = points[0];
+        let (h1, r1) = points[points.len() - 1];
+        if points.iter().all(|(h, _)| near(*h, h0)) {
+            return Some(ElementarySurface::Plane {
+                origin: origin + h0 * axis,
+                normal: axis,
+            });
+        }
+        if points.iter().all(|(_, r)| near(*r, r0)) {
+            return Some(ElementarySurface::Cylinder {
+                origin,
+                axis,
+                radius: r0,
+            });
+        }
+        if !near(h0, h1) {
+            let height = (r1 * r1 - r0 * r0 + h1 * h1 - h0 * h0) / (2.0 * (h1 - h0));
+            let radius = f64::sqrt(r0 * r0 + (h0 - height) * (h0 - height));
+            let on_sphere =
+                |&(h, r): &(f64, f64)| near(f64::sqrt(r * r + (h - height) * (h - height)), radius);
+            if points.iter().all(on_sphere) {
+                return Some(ElementarySurface::Sphere {
+                    center: origin + height * axis,
+                    radius,
+                });
+            }
+        }
+        Some(ElementarySurface::Revolution { origin, axis })
+    }
+}
+
+impl<E: AnalyticSurface> AnalyticSurface for Processor<E, Matrix4> {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        self.entity()
+            .elementary_surface()?
+            .transformed(*self.transform())
+    }
+}
+
+// The minimum number of the divisions of the parameter ranges at which the surfaces are sampled.
+const DIVISION: usize = 8;
+// The ratio of the tolerance of the adaptive divisions to the size of the surfaces.
+const DIVISION_RATIO: f64 = 1.0e-3;
+
+// The tolerance of the recognition, relative to the size of the surface larger than the unit.
+fn scaled_tolerance(size: f64) -> f64 { TOLERANCE * f64::max(1.0, size) }
+
+// The parameters dividing each span of the knot vector into `2 * degree + 1` parts. On each span, the distance from
+// a plane, or the squared distance from a sphere or a cylinder, multiplied by the power of the weight is a
+// polynomial of degree at most `2 * degree`, which vanishes if it vanishes at these parameters.
+fn span_parameters(knot_vec: &KnotVec, degree: usize) -> Vec<f64> {
+    let (knots, _) = knot_vec.to_single_multi();
+    let division = 2 * degree + 1;
+    let mut params = knots
+        .windows(2)
+        .flat_map(|k| (0..division).map(move |i| k[0] + (k[1] - k[0]) * i as f64 / division as f64))
+        .collect::<Vec<_>>();
+    params.extend(knots.last());
+    params
+}
+
+// Recognizes the plane, the sphere and the cylinder by the normal lines at the sample points.
+fn fit_elementary_surface<S>(
+    surface: &S,
+    (uparams, vparams): (Vec<f64>, Vec<f64>),
+) -> Option<ElementarySurface>
+where
+    S: ParametricSurface3D,
+{
+    let samples = uparams
+        .iter()
+        .flat_map(|u| vparams.iter().map(move |v| (*u, *v)))
+        .map(|(u, v)| (surface.subs(u, v), surface.normal(u, v)))
+        .filter(|(_, n)| n.x.is_finite() && n.y.is_finite() && n.z.is_finite())
+        .collect::<Vec<_>>();
+    let size = samples
+        .iter()
+        .map(|(p, _)| *p)
+        .collect::<BoundingBox<_>>()
+        .diameter();
+    let tol = scaled_tolerance(size);
+    let (p0, n0) = *samples.first()?;
+    if samples
+        .iter()
+        .all(|(p, n)| near_vector(*n, n0) && (p - p0).dot(n0).abs() < tol)
+    {
+        return Some(ElementarySurface::Plane {
+            origin: p0,
+            normal: n0,
+        });
+    }
+
+    // the normal lines pass through the center of the sphere, or intersect the axis of the cylinder.
+    let (p1, n1) = *samples.iter().max_by(|(_, n), (_, m)| {
+        n0.cross(*n)
+            .magnitude2()
+            .total_cmp(&n0.cross(*m).magnitude2())
+    })?;
+    let (b, w) = (n0.dot(n1), p0 - p1);
+    let denom = 1.0 - b * b;
+    if denom.so_small() {
+        return None;
+    }
+    let s = (b * n1.dot(w) - n0.dot(w)) / denom;
+    let t = (n1.dot(w) - b * n0.dot(w)) / denom;
+    let (q0, q1) = (p0 + s * n0, p1 + t * n1);
+    let radius = s.abs();
+    if q0.distance(q1) < tol {
+        let on_sphere = |(p, n): &(Point3, Vector3)| {
+            near_vector(p - q0, *n) && f64::abs(p.distance(q0) - radius) < tol
+        };
+        if samples.iter().all(on_sphere) {
+            return Some(ElementarySurface::Sphere { center: q0, radius });
+        }
+    }
+    let axis = n0.cross(n1).normalize();
+    let on_cylinder = |(p, n): &(Point3, Vector3)| {
+        let v = p - q0;
+        let radial = v - v.dot(axis) * axis;
+        n.dot(axis).so_small()
+            && near_vector(radial, *n)
+            && f64::abs(radial.magnitude() - radius) < tol
+    };
+    match samples.iter().all(on_cylinder) {
+        true => Some(ElementarySurface::Cylinder {
+            origin: q0,
+            axis,
+            radius,
+        }),
+        false => None,
+    }
+}
+
+impl AnalyticSurface for BSplineSurface<Point3> {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        let (uknot_vec, vknot_vec) = self.knot_vecs();
+        let uparams = span_parameters(uknot_vec, self.udegree());
+        let vparams = span_parameters(vknot_vec, self.vdegree());
+        fit_elementary_surface(self, (uparams, vparams))
+    }
+}
+
+impl AnalyticSurface for NurbsSurface<Vector4> {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        let (uknot_vec, vknot_vec) = self.knot_vecs();
+        let uparams = span_parameters(uknot_vec, self.udegree());
+        let vparams = span_parameters(vknot_vec, self.vdegree());
+        fit_elementary_surface(self, (uparams, vparams))
+    }
+}
+
+impl AnalyticSurface for truck_modeling::Surface {
+    fn elementary_surface(&self) -> Option<ElementarySurface> {
+        match self {
+            Self::Plane(plane) => plane.elementary_surface(),
+            Self::BSplineSurface(surface) => surface.elementary_surface(),
+            Self::NurbsSurface(surface) => surface.elementary_surface(),
+            Self::RevolutedCurve(surface) => surface.elementary_surface(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,201 @@
+use super::*;
+use crate::test_util::{cube, cylinder};
+use truck_modeling::{builder, Curve, Solid, Surface};
+
+fn surfaces(solid: &Solid) -> Vec<Surface> {
+    solid.face_iter().map(|face| face.surface()).collect()
+}
+
+#[test]
+fn recognize_surfaces() {
+    let cylinder = cylinder(Point3::new(1.0, 2.0, 0.0), 0.5, 1.0);
+    surfaces(&cylinder)
+        .iter()
+        .for_each(|surface| match surface.elementary_surface().unwrap() {
+            ElementarySurface::Plane { origin, normal } => {
+                assert!(near_vector(normal, Vector3::unit_z()));
+                assert!(origin.z.near(&0.0) || origin.z.near(&1.0));
+            }
+            ElementarySurface::Cylinder {
+                origin,
+                axis,
+                radius,
+            } => {
+                assert!(coaxial(
+                    (origin, axis),
+                    (Point3::new(1.0, 2.0, 0.0), Vector3::unit_z())
+                ));
+                assert_near!(radius, 0.5);
+            }
+            surface => panic!("{surface:?}"),
+        });
+
+    let v = builder::vertex(Point3::new(0.0, 0.0, 1.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_y(), Rad(PI));
+    let shell = builder::cone(&w, Vector3::unit_z(), Rad(7.0));
+    let sphere = builder::rotated(&shell, Point3::origin(), Vector3::unit_x(), Rad(0.5));
+    let sphere = builder::translated(&sphere, Vector3::new(1.0, 2.0, 3.0));
+    sphere.face_iter().for_each(|face| {
+        let surface = face.surface().elementary_surface().unwrap();
+        let ElementarySurface::Sphere { center, radius } = surface else {
+            panic!("{surface:?}");
+        };
+        assert_near!(center, Point3::new(1.0, 2.0, 3.0));
+        assert_near!(radius, 1.0);
+    });
+
+    let bsp = BSplineSurface::new(
+        (KnotVec::bezier_knot(1), KnotVec::bezier_knot(1)),
+        vec![
+            vec![Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0)],
+            vec![Point3::new(1.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)],
+        ],
+    );
+    assert!(Surface::from(bsp).elementary_surface().is_none());
+}
+
+#[test]
+fn recognize_by_knot_spans() {
+    // the plane with the bump between the samples of the uniform division
+    let knot_vec = KnotVec::uniform_knot(1, 20);
+    let control_points = (0..21)
+        .map(|i| {
+            let (x, z) = (i as f64 / 20.0, if i == 1 { 0.1 } else { 0.0 });
+            vec![Point3::new(x, 0.0, z), Point3::new(x, 1.0, z)]
+        })
+        .collect::<Vec<_>>();
+    let bsp = BSplineSurface::new((knot_vec, KnotVec::bezier_knot(1)), control_points);
+    assert!(bsp.elementary_surface().is_none());
+
+    // the large cylinders are recognized with the tolerance scaled by the size.
+    let revolved = builder::cylinder(Point3::origin(), Vector3::unit_z(), 1000.0, 1000.0).unwrap();
+    let v = builder::vertex(Point3::new(1000.0, 0.0, 0.0));
+    let arc = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(1.0));
+    let nurbs: truck_modeling::Shell = builder::tsweep(&arc, Vector3::unit_z() * 1000.0);
+    let lateral = revolved
+        .face_iter()
+        .chain(nurbs.face_iter())
+        .filter_map(|face| match face.surface().elementary_surface() {
+            Some(ElementarySurface::Plane { .. }) => None,
+            surface => Some(surface),
+        })
+        .collect::<Vec<_>>();
+    assert!(lateral.len() >= 2);
+    lateral.into_iter().for_each(|surface| match surface {
+        Some(ElementarySurface::Cylinder { radius, .. }) => {
+            assert!(f64::abs(radius - 1000.0) < 1.0e-6)
+        }
+        surface => panic!("{surface:?}"),
+    });
+}
+
+#[test]
+fn non_similar_transform() {
+    let surface = ElementarySurface::Sphere {
+        center: Point3::origin(),
+        radius: 1.0,
+    };
+    let mat = Matrix4::from_translation(Vector3::new(1.0, 0.0, 0.0)) * Matrix4::from_scale(2.0);
+    assert_eq!(
+        surface.transformed(mat),
+        Some(ElementarySurface::Sphere {
+            center: Point3::new(1.0, 0.0, 0.0),
+            radius: 2.0,
+        })
+    );
+    let mat = Matrix4::from_nonuniform_scale(1.0, 2.0, 1.0);
+    assert_eq!(surface.transformed(mat), None);
+}
+
+#[test]
+fn oblique_cylinder_section() {
+    let cylinder = ElementarySurface::Cylinder {
+        origin: Point3::origin(),
+        axis: Vector3::unit_z(),
+        radius: 1.0,
+    };
+    let normal = Vector3::new(0.0, 1.0, 1.0).normalize();
+    let plane = ElementarySurface::Plane {
+        origin: Point3::new(0.0, 0.0, 2.0),
+        normal,
+    };
+    let point = Point3::new(1.0, 0.0, 2.0);
+    let Some(Conic::Ellipse { center, a, b }) = intersection_conic(plane, cylinder, point) else {
+        panic!("the section is not an ellipse");
+    };
+    let arc = ellipse_arc(center, a, b, (0.0, 2.0 * PI));
+    assert_near!(arc.front(), arc.back());
+    (0..=100).for_each(|i| {
+        let pt = arc.subs(i as f64 / 100.0);
+        assert_near!(pt.x * pt.x + pt.y * pt.y, 1.0);
+        assert!((pt - Point3::new(0.0, 0.0, 2.0)).dot(normal).so_small());
+    });
+}
+
+#[test]
+fn exact_difference_of_cubes() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let difference = crate::difference(&cube0, &cube1, 0.05).unwrap();
+    assert!(difference
+        .edge_iter()
+        .all(|edge| matches!(edge.curve(), Curve::Line(_))));
+}
+
+#[test]
+fn exact_punched_cube() {
+    let cube = cube(Point3::origin(), 1.0);
+    let mut cylinder = cylinder(Point3::new(0.5, 0.5, -0.5), 0.25, 2.0);
+    cylinder.not();
+    let punched = crate::and(&cube, &cylinder, 0.05).unwrap();
+    let circles = punched
+        .edge_iter()
+        .filter(|edge| matches!(edge.curve(), Curve::NurbsCurve(_)))
+        .collect::<Vec<_>>();
+    assert!(!circles.is_empty());
+    assert!(punched
+        .edge_iter()
+        .all(|edge| !matches!(edge.curve(), Curve::IntersectionCurve(_))));
+    circles.iter().for_each(|edge| {
+        let curve = edge.curve();
+        (0..=10).for_each(|i| {
+            let pt = curve.subs(i as f64 / 10.0);
+            assert_near!(pt.z * (pt.z - 1.0), 0.0);
+            assert_near!(
+                pt.to_vec().truncate().distance(Vector2::new(0.5, 0.5)),
+                0.25
+            );
+        });
+    });
+}
+
+#[test]
+fn same_domain_surfaces() {
+    let plane = ElementarySurface::Plane {
+        origin: Point3::origin(),
+        normal: Vector3::unit_z(),
+    };
+    let moved = ElementarySurface::Plane {
+        origin: Point3::new(1.0, 2.0, 0.0),
+        normal: -Vector3::unit_z(),
+    };
+    let lifted = ElementarySurface::Plane {
+        origin: Point3::new(0.0, 0.0, 1.0),
+        normal: Vector3::unit_z(),
+    };
+    assert!(plane.same_domain(moved));
+    assert!(!plane.same_domain(lifted));
+
+    let cylinder = ElementarySurface::Cylinder {
+        origin: Point3::origin(),
+        axis: Vector3::unit_z(),
+        radius: 1.0,
+    };
+    let shifted = ElementarySurface::Cylinder {
+        origin: Point3::new(0.0, 0.0, 3.0),
+        axis: -Vector3::unit_z(),
+        radius: 1.0,
+    };
+    assert!(cylinder.same_domain(shifted));
+    assert!(!cylinder.same_domain(plane));
+}
@@ -99,33 +99,46 @@ where
     Some(vec)
 }
 
+/// The divided faces with the indices of the original faces.
+pub type DividedFaces<C, S> = (FacesClassification<Point3, C, S>, Vec<usize>);
+
+/// Divides the faces by the loops, and returns the indices of the original faces of the divided faces in order. If
+/// failed, returns the index of the face. The faces are divided independently of each other.
 pub fn divide_faces<C, S>(
     shell: &Shell<Point3, C, S>,
     loops_store: &LoopsStore<Point3, C>,
     tol: f64,
-) -> Option<FacesClassification<Point3, C, S>>
+) -> std::result::Result<DividedFaces<C, S>, usize>
 where
-    C: BoundedCurve<Point = Point3> + ParameterDivision1D<Point = Point3>,
-    S: Clone + SearchParameter<D2, Point = Point3>,
+    C: BoundedCurve<Point = Point3> + ParameterDivision1D<Point = Point3> + Send + Sync,
+    S: Clone + SearchParameter<D2, Point = Point3> + Send + Sync,
 {
-    let mut res = FacesClassification::<Point3, C, S>::default();
-    shell
-        .iter()
-        .zip(loops_store)
-        .try_for_each(|(face, loops)| {
-            if loops
-                .iter()
-                .all(|wire| wire.status() == ShapesOpStatus::Unknown)
-            {
-                res.push(face.clone(), ShapesOpStatus::Unknown);
-            } else {
-                let vec = divide_one_face(face, loops, tol)?;
-                vec.into_iter()
-                    .for_each(|(face, status)| res.push(face, status));
+    let faces = shell.iter().zip(loops_store).collect::<Vec<_>>();
+    let divided = super::parallel::map(&faces, |(face, loops)| {
+        if loops
+            .iter()
+            .all(|wire| wire.status() == ShapesOpStatus::Unknown)
+        {
+            // The face is not divided, but the boundary edges may be cut or shared with the other shell.
+            let wires = loops.iter().map(|wire| wire.deref().clone()).collect();
+            let mut new_face = Face::debug_new(wires, face.surface());
+            if !face.orientation() {
+                new_face.invert();
             }
-            Some(())
-        })?;
-    Some(res)
+            Some(vec![(new_face, ShapesOpStatus::Unknown)])
+        } else {
+            divide_one_face(face, loops, tol)
+        }
+    });
+    let mut res = FacesClassification::<Point3, C, S>::default();
+    let mut origins = Vec::new();
+    for (i, vec) in divided.into_iter().enumerate() {
+        vec.ok_or(i)?.into_iter().for_each(|(face, status)| {
+            res.push(face, status);
+            origins.push(i);
+        });
+    }
+    Ok((res, origins))
 }
 
 #[cfg(test)]
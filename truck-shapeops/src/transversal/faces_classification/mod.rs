@@ -23,15 +23,34 @@ impl<P, C, S> FacesClassification<P, C, S> {
         self.shell.push(face);
     }
 
-    pub fn and_or_unknown(&self) -> [Shell<P, C, S>; 3] {
-        let [mut and, mut or, mut unknown] = <[Shell<P, C, S>; 3]>::default();
-        for face in &self.shell {
-            match self.status.get(&face.id()).unwrap() {
+    pub fn iter(&self) -> impl Iterator<Item = (&Face<P, C, S>, ShapesOpStatus)> {
+        self.shell
+            .iter()
+            .map(|face| (face, *self.status.get(&face.id()).unwrap()))
+    }
+
+    pub fn set_status(&mut self, face_id: FaceID<S>, status: ShapesOpStatus) {
+        *self.status.get_mut(&face_id).unwrap() = status;
+    }
+
+    /// Returns `[and, or, unknown, on the same, on the opposite]`.
+    pub fn shells(&self) -> [Shell<P, C, S>; 5] {
+        let [mut and, mut or, mut unknown, mut same, mut opposite] =
+            <[Shell<P, C, S>; 5]>::default();
+        for (face, status) in self.iter() {
+            match status {
                 ShapesOpStatus::And => and.push(face.clone()),
                 ShapesOpStatus::Or => or.push(face.clone()),
                 ShapesOpStatus::Unknown => unknown.push(face.clone()),
+                ShapesOpStatus::OnSame => same.push(face.clone()),
+                ShapesOpStatus::OnOpposite => opposite.push(face.clone()),
             }
         }
+        [and, or, unknown, same, opposite]
+    }
+
+    pub fn and_or_unknown(&self) -> [Shell<P, C, S>; 3] {
+        let [and, or, unknown, ..] = self.shells();
         [and, or, unknown]
     }
 
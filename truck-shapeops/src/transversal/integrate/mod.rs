@@ -1,5 +1,9 @@
 use crate::alternative::Alternative;
+use crate::errors::ShapeOpsError;
+use loops_store::{LoopsStoreError, ShapesOpStatus};
+use rustc_hash::FxHashMap as HashMap;
 
+use super::parallel::join;
 use super::*;
 use truck_geometry::prelude::*;
 use truck_meshalgo::prelude::*;
@@ -12,6 +16,7 @@ pub trait ShapeOpsSurface:
     + SearchParameter<D2, Point = Point3>
     + SearchNearestParameter<D2, Point = Point3>
     + Invertible
+    + AnalyticSurface
     + Send
     + Sync {
 }
@@ -20,6 +25,7 @@ impl<S> ShapeOpsSurface for S where S: ParametricSurface3D
         + SearchParameter<D2, Point = Point3>
         + SearchNearestParameter<D2, Point = Point3>
         + Invertible
+        + AnalyticSurface
         + Send
         + Sync
 {
@@ -32,6 +38,8 @@ pub trait ShapeOpsCurve<S: ShapeOpsSurface>:
     + Cut
     + Invertible
     + From<IntersectionCurve<BSplineCurve<Point3>, S, S>>
+    + From<Line<Point3>>
+    + From<NurbsCurve<Vector4>>
     + SearchParameter<D1, Point = Point3>
     + SearchNearestParameter<D1, Point = Point3>
     + Send
@@ -42,6 +50,8 @@ impl<C, S: ShapeOpsSurface> ShapeOpsCurve<S> for C where C: ParametricCurve3D
         + Cut
         + Invertible
         + From<IntersectionCurve<BSplineCurve<Point3>, S, S>>
+        + From<Line<Point3>>
+        + From<NurbsCurve<Vector4>>
         + SearchParameter<D1, Point = Point3>
         + SearchNearestParameter<D1, Point = Point3>
         + Send
@@ -52,34 +62,229 @@ impl<C, S: ShapeOpsSurface> ShapeOpsCurve<S> for C where C: ParametricCurve3D
 type AltCurveShell<C, S> =
     Shell<Point3, Alternative<C, IntersectionCurve<PolylineCurve<Point3>, S, S>>, S>;
 
+type Result<T, S> = std::result::Result<T, ShapeOpsError<S>>;
+
+// The faces of the first and the second shells on which the intersection curves lie, indexed by the edges.
+type IntersectionFaces<C, S> =
+    HashMap<EdgeID<Alternative<C, IntersectionCurve<PolylineCurve<Point3>, S, S>>>, [FaceID<S>; 2]>;
+
+fn approximate_curve<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    ic: &IntersectionCurve<PolylineCurve<Point3>, S, S>,
+    tol: f64,
+) -> Option<C> {
+    if let Some(curve) = exact_curve(ic) {
+        return Some(curve);
+    }
+    let bsp = BSplineCurve::quadratic_approximation(ic, ic.range_tuple(), tol, 100)?;
+    Some(IntersectionCurve::new(ic.surface0().clone(), ic.surface1().clone(), bsp).into())
+}
+
 fn altshell_to_shell<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
     altshell: &AltCurveShell<C, S>,
+    faces: &IntersectionFaces<C, S>,
     tol: f64,
-) -> Option<Shell<Point3, C, S>> {
-    altshell.try_mapped(
+) -> Result<Shell<Point3, C, S>, S> {
+    let shell = altshell.try_mapped(
         |p| Some(*p),
         |c| match c {
             Alternative::FirstType(c) => Some(c.clone()),
-            Alternative::SecondType(ic) => {
-                let bsp = BSplineCurve::quadratic_approximation(ic, ic.range_tuple(), tol, 100)?;
-                Some(
-                    IntersectionCurve::new(ic.surface0().clone(), ic.surface1().clone(), bsp)
-                        .into(),
-                )
-            }
+            Alternative::SecondType(ic) => approximate_curve(ic, tol),
         },
         |s| Some(s.clone()),
-    )
+    );
+    shell.ok_or_else(|| approximation_failed(altshell, faces, tol))
 }
 
-fn process_one_pair_of_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
-    shell0: &Shell<Point3, C, S>,
-    shell1: &Shell<Point3, C, S>,
+// The error of the intersection curve of `altshell` which is not approximated.
+fn approximation_failed<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    altshell: &AltCurveShell<C, S>,
+    faces: &IntersectionFaces<C, S>,
     tol: f64,
-) -> Option<[Shell<Point3, C, S>; 2]> {
-    nonpositive_tolerance!(tol);
-    let poly_shell0 = shell0.triangulation(tol);
-    let poly_shell1 = shell1.triangulation(tol);
+) -> ShapeOpsError<S> {
+    altshell
+        .edge_iter()
+        .find_map(|edge| {
+            let Alternative::SecondType(ic) = edge.curve() else {
+                return None;
+            };
+            let [face0, face1] = *faces.get(&edge.id())?;
+            match approximate_curve::<C, S>(&ic, tol) {
+                Some(_) => None,
+                None => Some(ShapeOpsError::ApproximationFailed {
+                    face0,
+                    face1,
+                    polyline: ic.leader().clone(),
+                }),
+            }
+        })
+        .expect("only the intersection curves may fail to be approximated")
+}
+
+// The polygons of the faces of `shell`, oriented by the faces.
+pub(super) fn polygons<C, S>(
+    shell: &Shell<Point3, C, S>,
+    poly_shell: &Shell<Point3, PolylineCurve<Point3>, Option<PolygonMesh>>,
+) -> Result<Vec<PolygonMesh>, S> {
+    shell
+        .face_iter()
+        .zip(poly_shell.face_iter())
+        .map(|(face, poly_face)| {
+            let mut polygon = poly_face
+                .surface()
+                .ok_or(ShapeOpsError::TriangulationFailed { face: face.id() })?;
+            if !poly_face.orientation() {
+                polygon.invert();
+            }
+            Ok(polygon)
+        })
+        .collect()
+}
+
+// The signed number of times the ray from `pt` crosses `polygons`.
+fn crossing_count(polygons: &[PolygonMesh], pt: Point3) -> isize {
+    let dir = hash::take_one_unit(pt);
+    polygons
+        .iter()
+        .map(|poly| poly.signed_crossing_faces(pt, dir))
+        .sum()
+}
+
+// The center of the largest triangle, which is regarded as a point in the interior of the face.
+fn interior_point(polygon: &PolygonMesh) -> Option<Point3> {
+    let positions = polygon.positions();
+    polygon
+        .faces()
+        .triangle_iter()
+        .map(|tri| tri.map(|v| positions[v.pos]))
+        .max_by(|[a0, b0, c0], [a1, b1, c1]| {
+            let area0 = (b0 - a0).cross(c0 - a0).magnitude2();
+            let area1 = (b1 - a1).cross(c1 - a1).magnitude2();
+            area0.total_cmp(&area1)
+        })
+        .map(|tri| Point3::centroid(&tri))
+}
+
+// Returns `true` if `pt` is on a triangle of `polygon` up to `tol`.
+fn on_polygon(polygon: &PolygonMesh, pt: Point3, tol: f64) -> bool {
+    let positions = polygon.positions();
+    polygon.faces().triangle_iter().any(|tri| {
+        let [a, b, c] = tri.map(|v| positions[v.pos]);
+        let normal = (b - a).cross(c - a);
+        if normal.so_small() {
+            return false;
+        }
+        let normal = normal.normalize();
+        let inside = [(a, b), (b, c), (c, a)]
+            .into_iter()
+            .all(|(p, q)| (q - p).cross(pt - p).dot(normal) >= 0.0);
+        inside && f64::abs((pt - a).dot(normal)) < tol
+    })
+}
+
+// The normal of the face at `pt`, which is oriented by the face.
+fn face_normal<C, S: ShapeOpsSurface>(face: &Face<Point3, C, S>, pt: Point3) -> Option<Vector3> {
+    let surface = face.surface();
+    let (u, v) = surface.search_nearest_parameter(pt, None, 100)?;
+    let normal = surface.normal(u, v);
+    Some(if face.orientation() { normal } else { -normal })
+}
+
+type AltCurveClassification<C, S> = faces_classification::FacesClassification<
+    Point3,
+    Alternative<C, IntersectionCurve<PolylineCurve<Point3>, S, S>>,
+    S,
+>;
+
+// Classifies the divided faces of a shell by the other shell: `[and, or, same, opposite]`, where `same` and
+// `opposite` are the faces on the faces of the other shell with the same or the opposite orientation. The faces are
+// on the other shell only if their surfaces are the same domains as the other faces, and their interior points are on
+// the other faces. The others which are not classified by the intersection curves are judged by their interior points.
+fn classify_faces<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    mut cls: AltCurveClassification<C, S>,
+    shell: &Shell<Point3, C, S>,
+    polygons: &[PolygonMesh],
+    tol: f64,
+) -> [AltCurveShell<C, S>; 4] {
+    let others: Vec<_> = shell
+        .face_iter()
+        .zip(polygons)
+        .filter_map(|(face, polygon)| Some((face.surface().elementary_surface()?, face, polygon)))
+        .collect();
+    let candidates: AltCurveShell<C, S> = cls
+        .iter()
+        .filter(|(face, status)| {
+            let same_domain = || {
+                let Some(surface) = face.surface().elementary_surface() else {
+                    return false;
+                };
+                others.iter().any(|(x, _, _)| surface.same_domain(*x))
+            };
+            *status == ShapesOpStatus::Unknown || same_domain()
+        })
+        .map(|(face, _)| face.clone())
+        .collect();
+    let poly_candidates = candidates.triangulation(tol);
+    let points: HashMap<_, _> = candidates
+        .face_iter()
+        .zip(poly_candidates.face_iter())
+        .filter_map(|(face, poly_face)| Some((face.id(), interior_point(&poly_face.surface()?)?)))
+        .collect();
+    candidates.face_iter().for_each(|face| {
+        let Some(&pt) = points.get(&face.id()) else {
+            return;
+        };
+        let Some(surface) = face.surface().elementary_surface() else {
+            return;
+        };
+        let other = others
+            .iter()
+            .find(|(x, _, polygon)| surface.same_domain(*x) && on_polygon(polygon, pt, tol));
+        let Some((_, other, _)) = other else {
+            return;
+        };
+        if let (Some(normal0), Some(normal1)) = (face_normal(face, pt), face_normal(other, pt)) {
+            match normal0.dot(normal1) > 0.0 {
+                true => cls.set_status(face.id(), ShapesOpStatus::OnSame),
+                false => cls.set_status(face.id(), ShapesOpStatus::OnOpposite),
+            }
+        }
+    });
+    cls.integrate_by_component();
+    let [mut and, mut or, unknown, same, opposite] = cls.shells();
+    unknown.into_iter().for_each(|face| {
+        let pt = match points.get(&face.id()) {
+            Some(pt) => *pt,
+            None => face.boundaries()[0].vertex_iter().next().unwrap().point(),
+        };
+        if crossing_count(polygons, pt) >= 1 {
+            and.push(face);
+        } else {
+            or.push(face);
+        }
+    });
+    [and, or, same, opposite]
+}
+
+type PolyShell = Shell<Point3, PolylineCurve<Point3>, Option<PolygonMesh>>;
+
+type AltCurveDividedFaces<C, S> =
+    divide_face::DividedFaces<Alternative<C, IntersectionCurve<PolylineCurve<Point3>, S, S>>, S>;
+
+type AltCurveLoopsStore<C, S> =
+    loops_store::LoopsStore<Point3, Alternative<C, IntersectionCurve<PolylineCurve<Point3>, S, S>>>;
+
+type ShellWithLoops<C, S> = (AltCurveShell<C, S>, AltCurveLoopsStore<C, S>);
+
+type ShellsWithLoops<C, S> = ([ShellWithLoops<C, S>; 2], IntersectionFaces<C, S>);
+
+type ClassifiedShells<C, S> = ([[AltCurveShell<C, S>; 4]; 2], IntersectionFaces<C, S>);
+
+// The faces of two shells with the loops of the boundaries and the intersection curves of the shells.
+fn loops_stores<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    (shell0, poly_shell0): (&Shell<Point3, C, S>, &PolyShell),
+    (shell1, poly_shell1): (&Shell<Point3, C, S>, &PolyShell),
+    tol: f64,
+) -> Result<ShellsWithLoops<C, S>, S> {
     let altshell0: AltCurveShell<C, S> =
         shell0.mapped(|x| *x, |c| Alternative::FirstType(c.clone()), Clone::clone);
     let altshell1: AltCurveShell<C, S> =
@@ -88,93 +293,429 @@ fn process_one_pair_of_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
         geom_loops_store0: loops_store0,
         geom_loops_store1: loops_store1,
         ..
-    } = loops_store::create_loops_stores(&altshell0, &poly_shell0, &altshell1, &poly_shell1)?;
-    let mut cls0 = divide_face::divide_faces(&altshell0, &loops_store0, tol)?;
-    cls0.integrate_by_component();
-    let mut cls1 = divide_face::divide_faces(&altshell1, &loops_store1, tol)?;
-    cls1.integrate_by_component();
-    let [mut and0, mut or0, unknown0] = cls0.and_or_unknown();
-    unknown0.into_iter().try_for_each(|face| {
-        let pt = face.boundaries()[0].vertex_iter().next().unwrap().point();
-        let dir = hash::take_one_unit(pt);
-        let count = poly_shell1.iter().try_fold(0, |count, face| {
-            let poly = face.surface()?;
-            Some(count + poly.signed_crossing_faces(pt, dir))
-        })?;
-        if count >= 1 {
-            and0.push(face);
-        } else {
-            or0.push(face);
-        }
-        Some(())
-    })?;
-    let [mut and1, mut or1, unknown1] = cls1.and_or_unknown();
-    unknown1.into_iter().try_for_each(|face| {
-        let pt = face.boundaries()[0].vertex_iter().next().unwrap().point();
-        let dir = hash::take_one_unit(pt);
-        let count = poly_shell0.iter().try_fold(0, |count, face| {
-            let poly = face.surface()?;
-            Some(count + poly.signed_crossing_faces(pt, dir))
+    } = loops_store::create_loops_stores(&altshell0, poly_shell0, &altshell1, poly_shell1, tol)
+        .map_err(|error| match error {
+            LoopsStoreError::NoPolygon(0, i) => ShapeOpsError::TriangulationFailed {
+                face: shell0[i].id(),
+            },
+            LoopsStoreError::NoPolygon(_, i) => ShapeOpsError::TriangulationFailed {
+                face: shell1[i].id(),
+            },
+            LoopsStoreError::Intersection(i, j) => ShapeOpsError::IntersectionFailed {
+                face0: shell0[i].id(),
+                face1: shell1[j].id(),
+            },
+            LoopsStoreError::Insertion(i, j, polyline) => ShapeOpsError::LoopsStoreFailed {
+                face0: shell0[i].id(),
+                face1: shell1[j].id(),
+                polyline,
+            },
         })?;
-        if count >= 1 {
-            and1.push(face);
-        } else {
-            or1.push(face);
+    let faces = intersection_faces((shell0, &loops_store0), (shell1, &loops_store1));
+    Ok((
+        [(altshell0, loops_store0), (altshell1, loops_store1)],
+        faces,
+    ))
+}
+
+// The intersection curves are the edges shared by the loops of both shells.
+fn intersection_faces<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    (shell0, loops_store0): (&Shell<Point3, C, S>, &AltCurveLoopsStore<C, S>),
+    (shell1, loops_store1): (&Shell<Point3, C, S>, &AltCurveLoopsStore<C, S>),
+) -> IntersectionFaces<C, S> {
+    let face_indices = |loops_store: &AltCurveLoopsStore<C, S>| {
+        loops_store
+            .iter()
+            .enumerate()
+            .flat_map(|(i, loops)| {
+                loops
+                    .iter()
+                    .flat_map(move |wire| wire.edge_iter().map(move |edge| (edge.id(), i)))
+            })
+            .collect::<HashMap<_, _>>()
+    };
+    let indices0 = face_indices(loops_store0);
+    face_indices(loops_store1)
+        .into_iter()
+        .filter_map(|(id, j)| Some((id, [shell0[*indices0.get(&id)?].id(), shell1[j].id()])))
+        .collect()
+}
+
+// Divides the faces of `shell` by the loops. Returns the divided faces with the indices of the original faces.
+fn divide_faces<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell: &Shell<Point3, C, S>,
+    (altshell, loops_store): &ShellWithLoops<C, S>,
+    tol: f64,
+) -> Result<AltCurveDividedFaces<C, S>, S> {
+    divide_face::divide_faces(altshell, loops_store, tol).map_err(|i| {
+        ShapeOpsError::DivideFaceFailed {
+            face: shell[i].id(),
         }
-        Some(())
-    })?;
+    })
+}
+
+// Classifies the faces of two shells. Returns `[[and0, or0, same0, opposite0], [and1, or1, same1, opposite1]]`, where
+// `and0` is the faces of `shell0` inside `shell1`, `or0` is the faces of `shell0` outside `shell1`, `same0` and
+// `opposite0` are the faces of `shell0` on the faces of `shell1` with the same or the opposite orientation, and so on.
+// The faces in `same1` and `opposite1` are the copies of the ones in `same0` and `opposite0`. The faces on which the
+// intersection curves lie are also returned.
+fn process_one_pair_of_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell0: &Shell<Point3, C, S>,
+    shell1: &Shell<Point3, C, S>,
+    tol: f64,
+) -> Result<ClassifiedShells<C, S>, S> {
+    nonpositive_tolerance!(tol);
+    let (poly_shell0, poly_shell1) =
+        join(|| shell0.triangulation(tol), || shell1.triangulation(tol));
+    let polygons0 = polygons(shell0, &poly_shell0)?;
+    let polygons1 = polygons(shell1, &poly_shell1)?;
+    let ([loops0, loops1], faces) =
+        loops_stores((shell0, &poly_shell0), (shell1, &poly_shell1), tol)?;
+    let (divided0, divided1) = join(
+        || divide_faces(shell0, &loops0, tol),
+        || divide_faces(shell1, &loops1, tol),
+    );
+    let ((cls0, _), (cls1, _)) = (divided0?, divided1?);
+    let classified = [
+        classify_faces(cls0, shell1, &polygons1, tol),
+        classify_faces(cls1, shell0, &polygons0, tol),
+    ];
+    Ok((classified, faces))
+}
+
+fn and_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell0: &Shell<Point3, C, S>,
+    shell1: &Shell<Point3, C, S>,
+    tol: f64,
+) -> Result<Shell<Point3, C, S>, S> {
+    let ([[mut and0, _, mut same0, _], [mut and1, ..]], faces) =
+        process_one_pair_of_shells(shell0, shell1, tol)?;
     and0.append(&mut and1);
+    and0.append(&mut same0);
+    altshell_to_shell(&and0, &faces, tol)
+}
+
+pub(super) fn or_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell0: &Shell<Point3, C, S>,
+    shell1: &Shell<Point3, C, S>,
+    tol: f64,
+) -> Result<Shell<Point3, C, S>, S> {
+    let ([[_, mut or0, mut same0, _], [_, mut or1, ..]], faces) =
+        process_one_pair_of_shells(shell0, shell1, tol)?;
     or0.append(&mut or1);
-    Some([
-        altshell_to_shell(&and0, tol)?,
-        altshell_to_shell(&or0, tol)?,
-    ])
+    or0.append(&mut same0);
+    altshell_to_shell(&or0, &faces, tol)
+}
+
+// The faces of `shell0` outside `shell1`, and the inverted faces of `shell1` inside `shell0`. The faces of `shell0` on
+// `shell1` remain only if they face the opposite way, i.e. they are outside the complement of `shell1`.
+fn difference_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell0: &Shell<Point3, C, S>,
+    shell1: &Shell<Point3, C, S>,
+    tol: f64,
+) -> Result<Shell<Point3, C, S>, S> {
+    let ([[_, mut or0, _, mut opposite0], [and1, ..]], faces) =
+        process_one_pair_of_shells(shell0, shell1, tol)?;
+    or0.extend(and1.iter().map(Face::inverse));
+    or0.append(&mut opposite0);
+    altshell_to_shell(&or0, &faces, tol)
 }
 
 /// AND operation between two solids.
+///
+/// # Errors
+/// Returns [`ShapeOpsError`] with the failing stage and the faces involved.
 pub fn and<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
     solid0: &Solid<Point3, C, S>,
     solid1: &Solid<Point3, C, S>,
     tol: f64,
-) -> Option<Solid<Point3, C, S>> {
+) -> Result<Solid<Point3, C, S>, S> {
     let mut iter0 = solid0.boundaries().iter();
     let mut iter1 = solid1.boundaries().iter();
     let shell0 = iter0.next().unwrap();
     let shell1 = iter1.next().unwrap();
-    let [mut and_shell, _] = process_one_pair_of_shells(shell0, shell1, tol)?;
+    let mut and_shell = and_shells(shell0, shell1, tol)?;
     for shell in iter0 {
-        let [res, _] = process_one_pair_of_shells(&and_shell, shell, tol)?;
-        and_shell = res;
+        and_shell = and_shells(&and_shell, shell, tol)?;
     }
     for shell in iter1 {
-        let [res, _] = process_one_pair_of_shells(&and_shell, shell, tol)?;
-        and_shell = res;
+        and_shell = and_shells(&and_shell, shell, tol)?;
     }
     let boundaries = and_shell.connected_components();
-    Some(Solid::new(boundaries))
+    Ok(Solid::new(boundaries))
 }
 
 /// OR operation between two solids.
+///
+/// # Errors
+/// Returns [`ShapeOpsError`] with the failing stage and the faces involved.
 pub fn or<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
     solid0: &Solid<Point3, C, S>,
     solid1: &Solid<Point3, C, S>,
     tol: f64,
-) -> Option<Solid<Point3, C, S>> {
+) -> Result<Solid<Point3, C, S>, S> {
     let mut iter0 = solid0.boundaries().iter();
     let mut iter1 = solid1.boundaries().iter();
     let shell0 = iter0.next().unwrap();
     let shell1 = iter1.next().unwrap();
-    let [_, mut or_shell] = process_one_pair_of_shells(shell0, shell1, tol)?;
+    let mut or_shell = or_shells(shell0, shell1, tol)?;
     for shell in iter0 {
-        let [_, res] = process_one_pair_of_shells(&or_shell, shell, tol)?;
-        or_shell = res;
+        or_shell = or_shells(&or_shell, shell, tol)?;
     }
     for shell in iter1 {
-        let [_, res] = process_one_pair_of_shells(&or_shell, shell, tol)?;
-        or_shell = res;
+        or_shell = or_shells(&or_shell, shell, tol)?;
     }
     let boundaries = or_shell.connected_components();
-    Some(Solid::new(boundaries))
+    Ok(Solid::new(boundaries))
+}
+
+// The outer shell with the cavities inside it.
+type Body<C, S> = Vec<Shell<Point3, C, S>>;
+
+// The boundary shells of `shells` grouped by the bodies: each outer shell with the cavities inside it. The outer
+// shells enclose positive volumes, and each cavity belongs to the smallest outer shell containing it.
+fn bodies<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shells: &[Shell<Point3, C, S>],
+    tol: f64,
+) -> Result<Vec<Body<C, S>>, S> {
+    let polygons = shells
+        .iter()
+        .map(|shell| polygons(shell, &shell.triangulation(tol)))
+        .collect::<Result<Vec<_>, S>>()?;
+    let volumes = polygons
+        .iter()
+        .map(|polygons| polygons.iter().map(PolygonMesh::volume).sum::<f64>())
+        .collect::<Vec<_>>();
+    let (outers, cavities): (Vec<usize>, Vec<usize>) =
+        (0..shells.len()).partition(|i| volumes[*i] >= 0.0);
+    let mut bodies = outers
+        .iter()
+        .map(|i| vec![shells[*i].clone()])
+        .collect::<Vec<_>>();
+    cavities.into_iter().for_each(|i| {
+        let Some(pt) = polygons[i]
+            .iter()
+            .find_map(|polygon| polygon.positions().first())
+        else {
+            return;
+        };
+        let container = (0..outers.len())
+            .filter(|j| crossing_count(&polygons[outers[*j]], *pt) >= 1)
+            .min_by(|j, k| volumes[outers[*j]].total_cmp(&volumes[outers[*k]]));
+        match container {
+            Some(j) => bodies[j].push(shells[i].clone()),
+            // the cavity without outer shells is the complement of a bounded region.
+            None => bodies.push(vec![shells[i].clone()]),
+        }
+    });
+    Ok(bodies)
+}
+
+/// Difference operation between two solids: the part of `solid0` outside `solid1`.
+///
+/// The boundary shells of the solids are grouped by the bodies, i.e. the outer shells with their cavities. The
+/// bodies of `solid1` are subtracted one after another, since the complement of `solid1` is the intersection of the
+/// complements of its bodies. The complement of a body is the disjoint union of the complements of its boundary
+/// shells, so each body of `solid0` is intersected with each of them independently. The caller does not need to
+/// invert `solid1`.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_meshalgo::prelude::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+/// let moved = builder::translated(&cube, Vector3::new(0.5, 0.5, 0.5));
+///
+/// let difference = truck_shapeops::difference(&cube, &moved, 0.05).unwrap();
+/// let volume = difference.triangulation(0.01).to_polygon().volume();
+/// assert!(f64::abs(volume - 0.875) < 0.01);
+/// ```
+pub fn difference<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    solid0: &Solid<Point3, C, S>,
+    solid1: &Solid<Point3, C, S>,
+    tol: f64,
+) -> Result<Solid<Point3, C, S>, S> {
+    nonpositive_tolerance!(tol);
+    let mut boundaries = solid0.boundaries().clone();
+    for body1 in bodies(solid1.boundaries(), tol)? {
+        let mut remains = Vec::new();
+        for body0 in bodies(&boundaries, tol)? {
+            for shell1 in &body1 {
+                let mut iter0 = body0.iter();
+                let mut diff_shell = difference_shells(iter0.next().unwrap(), shell1, tol)?;
+                for shell in iter0 {
+                    diff_shell = and_shells(&diff_shell, shell, tol)?;
+                }
+                remains.extend(diff_shell.connected_components());
+            }
+        }
+        boundaries = remains;
+    }
+    Ok(Solid::new(boundaries))
+}
+
+/// Symmetric difference operation between two solids: the part of `solid0` outside `solid1` and
+/// the part of `solid1` outside `solid0`.
+///
+/// The two parts touch each other only along the intersection curves, so they are returned as the separated
+/// boundary shells of one solid.
+pub fn symmetric_difference<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    solid0: &Solid<Point3, C, S>,
+    solid1: &Solid<Point3, C, S>,
+    tol: f64,
+) -> Result<Solid<Point3, C, S>, S> {
+    let difference0 = difference(solid0, solid1, tol)?;
+    let difference1 = difference(solid1, solid0, tol)?;
+    let boundaries = difference0.into_boundaries().into_iter();
+    Ok(Solid::new(
+        boundaries.chain(difference1.into_boundaries()).collect(),
+    ))
+}
+
+type ImprintedShell<C, S> = (Shell<Point3, C, S>, Vec<usize>);
+
+// The faces of `shell0` divided by `shell1`, with the indices of the original faces.
+fn imprint_shells<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell0: &Shell<Point3, C, S>,
+    shell1: &Shell<Point3, C, S>,
+    tol: f64,
+) -> Result<ImprintedShell<C, S>, S> {
+    let poly_shell0 = shell0.triangulation(tol);
+    let poly_shell1 = shell1.triangulation(tol);
+    let ([loops0, _], faces) = loops_stores((shell0, &poly_shell0), (shell1, &poly_shell1), tol)?;
+    let (cls0, origins) = divide_faces(shell0, &loops0, tol)?;
+    let divided: AltCurveShell<C, S> = cls0.iter().map(|(face, _)| face.clone()).collect();
+    Ok((altshell_to_shell(&divided, &faces, tol)?, origins))
+}
+
+/// Imprints `solid1` on `solid0`: divides the faces of `solid0` by the intersection curves with the faces of
+/// `solid1`, without removing any material.
+///
+/// All the divided faces of `solid0` are kept, and no faces of `solid1` are added. Returns the imprinted solid and the
+/// ids of the original faces of `solid0`, where the `i`-th id is the original of the `i`-th face of
+/// [`Solid::face_iter`] of the imprinted solid. The faces touching the faces of `solid1` on the same surfaces, e.g. the
+/// faces of stacked blocks, are divided along the boundaries of the contact regions, which are shared with the
+/// adjacent faces of `solid1`.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+/// let moved = builder::translated(&cube, Vector3::new(0.5, 0.5, 0.5));
+///
+/// let (imprinted, origins) = truck_shapeops::imprint(&cube, &moved, 0.05).unwrap();
+/// // the top, right and back faces are divided into two faces each.
+/// assert_eq!(imprinted.face_iter().count(), 9);
+/// assert_eq!(origins.len(), 9);
+/// assert!(cube.face_iter().all(|face| origins.contains(&face.id())));
+/// ```
+///
+/// # Errors
+/// Returns [`ShapeOpsError`] with the failing stage and the faces involved.
+#[allow(clippy::type_complexity)]
+pub fn imprint<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    solid0: &Solid<Point3, C, S>,
+    solid1: &Solid<Point3, C, S>,
+    tol: f64,
+) -> Result<(Solid<Point3, C, S>, Vec<FaceID<S>>), S> {
+    nonpositive_tolerance!(tol);
+    let mut origins = Vec::new();
+    let boundaries = solid0
+        .boundaries()
+        .iter()
+        .map(|shell| {
+            let mut shell = shell.clone();
+            let mut ids = shell.face_iter().map(Face::id).collect::<Vec<_>>();
+            for shell1 in solid1.boundaries() {
+                let (imprinted, indices) = imprint_shells(&shell, shell1, tol)?;
+                ids = indices.into_iter().map(|i| ids[i]).collect();
+                shell = imprinted;
+            }
+            origins.extend(ids);
+            Ok(shell)
+        })
+        .collect::<Result<Vec<_>, S>>()?;
+    Ok((Solid::new(boundaries), origins))
+}
+
+// Divides the faces of `shell` by the boundary shell `boundary` of a solid. Returns `[inside, outside]`, where the
+// faces on `boundary` are regarded as inside.
+fn split_shell<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell: &Shell<Point3, C, S>,
+    boundary: &Shell<Point3, C, S>,
+    tol: f64,
+) -> Result<[Shell<Point3, C, S>; 2], S> {
+    let poly_shell = shell.triangulation(tol);
+    let poly_boundary = boundary.triangulation(tol);
+    let polygons = polygons(boundary, &poly_boundary)?;
+    let ([loops, _], faces) = loops_stores((shell, &poly_shell), (boundary, &poly_boundary), tol)?;
+    let (cls, _) = divide_faces(shell, &loops, tol)?;
+    let [mut and, or, mut same, mut opposite] = classify_faces(cls, boundary, &polygons, tol);
+    and.append(&mut same);
+    and.append(&mut opposite);
+    Ok([
+        altshell_to_shell(&and, &faces, tol)?,
+        altshell_to_shell(&or, &faces, tol)?,
+    ])
+}
+
+/// Splits the faces of `shell` by `solid`, and returns the part inside `solid` and the part outside `solid`.
+///
+/// Unlike the boolean operations, `shell` does not need to be closed: it may be a surface patch or a sheet body with
+/// open boundaries. The faces of `shell` are divided by the intersection curves with the boundaries of `solid` in
+/// the same way as the boolean operations, but the faces of `solid` are not divided, so the intersection curves may
+/// end at the open boundaries of `shell`. The faces of `shell` lying on the faces of `solid` are regarded as inside.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // the square sheet crossing the cube at the middle height
+/// let v = builder::vertex(Point3::new(-1.0, -1.0, 0.5));
+/// let e = builder::tsweep(&v, Vector3::new(3.0, 0.0, 0.0));
+/// let sheet: Shell = vec![builder::tsweep(&e, Vector3::new(0.0, 3.0, 0.0))].into();
+///
+/// let (inside, outside) = truck_shapeops::split_shell_by_solid(&sheet, &cube, 0.05).unwrap();
+/// assert_eq!(inside.len(), 1);
+/// assert_eq!(outside.len(), 1);
+/// // the face outside the cube has the hole.
+/// assert_eq!(outside[0].boundaries().len(), 2);
+/// ```
+///
+/// # Errors
+/// Returns [`ShapeOpsError`] with the failing stage and the faces involved.
+#[allow(clippy::type_complexity)]
+pub fn split_shell_by_solid<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    shell: &Shell<Point3, C, S>,
+    solid: &Solid<Point3, C, S>,
+    tol: f64,
+) -> Result<(Shell<Point3, C, S>, Shell<Point3, C, S>), S> {
+    nonpositive_tolerance!(tol);
+    let mut inside = shell.clone();
+    let mut outside = Shell::new();
+    // the interior of the solid is the intersection of the interiors of the boundary shells.
+    for boundary in solid.boundaries() {
+        if inside.is_empty() {
+            break;
+        }
+        let [and, mut or] = split_shell(&inside, boundary, tol)?;
+        inside = and;
+        outside.append(&mut or);
+    }
+    Ok((inside, outside))
 }
 
 #[cfg(test)]
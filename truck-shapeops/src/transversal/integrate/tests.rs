@@ -1,3 +1,5 @@
+use crate::errors::ShapeOpsError;
+use crate::test_util::{cube, cylinder, volume};
 use truck_meshalgo::prelude::*;
 use truck_modeling::*;
 
@@ -19,3 +21,364 @@ fn punched_cube() {
     let file = std::fs::File::create("punched-cube.obj").unwrap();
     obj::write(&poly, file).unwrap();
 }
+
+#[test]
+fn difference_of_cubes() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let difference = crate::difference(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(difference.boundaries().len(), 1);
+    assert!(f64::abs(volume(&difference, 0.01) - 0.875) < 0.01);
+
+    let mut not1 = cube1.clone();
+    not1.not();
+    let and = crate::and(&cube0, &not1, 0.05).unwrap();
+    assert!(f64::abs(volume(&difference, 0.01) - volume(&and, 0.01)) < 0.01);
+}
+
+#[test]
+fn difference_by_hollow_solid() {
+    // a cube with a cubic cavity
+    let outer = builder::scaled(
+        &cube(Point3::origin(), 1.0),
+        Point3::origin(),
+        Vector3::new(3.0, 3.0, 3.0),
+    );
+    let mut cavity = cube(Point3::new(1.0, 1.0, 1.0), 1.0);
+    cavity.not();
+    let hollow = Solid::new(vec![
+        outer.into_boundaries().pop().unwrap(),
+        cavity.into_boundaries().pop().unwrap(),
+    ]);
+
+    // a bar through the cavity
+    let bar = builder::scaled(
+        &cube(Point3::origin(), 1.0),
+        Point3::origin(),
+        Vector3::new(0.5, 0.5, 5.0),
+    );
+    let bar = builder::translated(&bar, Vector3::new(1.25, 1.25, -1.0));
+
+    // the part of the bar outside the hollow cube and the part in the cavity
+    let difference = crate::difference(&bar, &hollow, 0.05).unwrap();
+    assert_eq!(difference.boundaries().len(), 3);
+    assert!(f64::abs(volume(&difference, 0.01) - 0.25 * 3.0) < 0.01);
+}
+
+#[test]
+fn difference_by_two_bodies() {
+    let block = cube(Point3::origin(), 3.0);
+    // two unit cubes sticking into the opposite faces
+    let cube0 = cube(Point3::new(-0.5, 1.0, 1.0), 1.0);
+    let cube1 = cube(Point3::new(2.5, 1.0, 1.0), 1.0);
+    let or = crate::or(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(or.boundaries().len(), 2);
+    let difference = crate::difference(&block, &or, 0.05).unwrap();
+    assert_eq!(difference.boundaries().len(), 1);
+    assert!(f64::abs(volume(&difference, 0.01) - 26.0) < 0.01);
+
+    let union = crate::union_all(&[cube0, cube1], 0.05).unwrap();
+    let difference = crate::difference(&block, &union, 0.05).unwrap();
+    assert_eq!(difference.boundaries().len(), 1);
+    assert!(f64::abs(volume(&difference, 0.01) - 26.0) < 0.01);
+
+    // the two bodies cut the bar into three pieces.
+    let bar = builder::scaled(
+        &cube(Point3::new(-1.0, 1.25, 1.25), 1.0),
+        Point3::new(-1.0, 1.25, 1.25),
+        Vector3::new(5.0, 0.5, 0.5),
+    );
+    let difference = crate::difference(&bar, &union, 0.05).unwrap();
+    assert_eq!(difference.boundaries().len(), 3);
+    assert!(f64::abs(volume(&difference, 0.01) - 0.25 * 3.0) < 0.01);
+}
+
+#[test]
+fn symmetric_difference_of_cubes() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let symmetric_difference = crate::symmetric_difference(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(symmetric_difference.boundaries().len(), 2);
+    // the two parts touch along the edges, so they are triangulated separately.
+    let volume = symmetric_difference
+        .boundaries()
+        .iter()
+        .map(|shell| volume(&Solid::new(vec![shell.clone()]), 0.01))
+        .sum::<f64>();
+    assert!(f64::abs(volume - 1.75) < 0.01);
+}
+
+#[test]
+fn coplanar_faces() {
+    let cube0 = cube(Point3::origin(), 1.0);
+
+    // stacked cubes sharing the whole face
+    let cube1 = cube(Point3::new(0.0, 0.0, 1.0), 1.0);
+    let or = crate::or(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(or.boundaries().len(), 1);
+    assert!(f64::abs(volume(&or, 0.01) - 2.0) < 0.01);
+    let and = crate::and(&cube0, &cube1, 0.05).unwrap();
+    assert!(and.boundaries().is_empty());
+    let difference = crate::difference(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(difference.boundaries().len(), 1);
+    assert!(f64::abs(volume(&difference, 0.01) - 1.0) < 0.01);
+
+    // stacked cubes sharing a part of the face
+    let cube1 = cube(Point3::new(0.5, 0.5, 1.0), 1.0);
+    let or = crate::or(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(or.boundaries().len(), 1);
+    assert!(f64::abs(volume(&or, 0.01) - 2.0) < 0.01);
+
+    // overlapping cubes on the same plane
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.0), 1.0);
+    let and = crate::and(&cube0, &cube1, 0.05).unwrap();
+    assert!(f64::abs(volume(&and, 0.01) - 0.25) < 0.01);
+    let or = crate::or(&cube0, &cube1, 0.05).unwrap();
+    assert!(f64::abs(volume(&or, 0.01) - 1.75) < 0.01);
+    let difference = crate::difference(&cube0, &cube1, 0.05).unwrap();
+    assert!(f64::abs(volume(&difference, 0.01) - 0.75) < 0.01);
+
+    // the same cubes
+    let and = crate::and(&cube0, &cube0.clone(), 0.05).unwrap();
+    assert!(f64::abs(volume(&and, 0.01) - 1.0) < 0.01);
+    let difference = crate::difference(&cube0, &cube0.clone(), 0.05).unwrap();
+    assert!(difference.boundaries().is_empty());
+}
+
+#[test]
+fn cylinder_on_plane() {
+    let cube = cube(Point3::origin(), 1.0);
+    let cylinder_volume = std::f64::consts::PI * 0.25 * 0.25;
+
+    // a cylinder standing on the top face
+    let cylinder = cylinder(Point3::new(0.5, 0.5, 1.0), 0.25, 1.0);
+    let or = crate::or(&cube, &cylinder, 0.05).unwrap();
+    assert_eq!(or.boundaries().len(), 1);
+    assert!(f64::abs(volume(&or, 0.01) - 1.0 - cylinder_volume) < 0.01);
+    let difference = crate::difference(&cube, &cylinder, 0.05).unwrap();
+    assert!(f64::abs(volume(&difference, 0.01) - 1.0) < 0.01);
+
+    // a cylinder lying on the top face, touching it along a line
+    let v = builder::vertex(Point3::new(0.5, 0.0, 1.0));
+    let w = builder::rsweep(&v, Point3::new(0.5, 0.0, 1.25), Vector3::unit_y(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let cylinder = builder::tsweep(&f, Vector3::unit_y());
+    let and = crate::and(&cube, &cylinder, 0.05).unwrap();
+    assert!(and.boundaries().is_empty());
+    let or = crate::or(&cube, &cylinder, 0.05).unwrap();
+    assert!(f64::abs(volume(&or, 0.01) - 1.0 - cylinder_volume) < 0.01);
+}
+
+#[test]
+fn failing_stage() {
+    // the top face whose surface does not contain the boundary cannot be triangulated.
+    let cube0 = cube(Point3::origin(), 1.0);
+    let shell = &cube0.boundaries()[0];
+    let top = shell
+        .iter()
+        .find(|face| {
+            face.boundaries()[0]
+                .vertex_iter()
+                .all(|v| v.point().z.near(&1.0))
+        })
+        .unwrap();
+    let surface = top
+        .surface()
+        .transformed(Matrix4::from_translation(Vector3::unit_z()));
+    top.set_surface(surface);
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let err = crate::and(&cube0, &cube1, 0.05).unwrap_err();
+    let ShapeOpsError::TriangulationFailed { face } = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(face, top.id());
+}
+
+#[test]
+fn intersection_faces() {
+    // the faces on which the intersection curves lie are recorded for the errors of the approximations.
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let (shell0, shell1) = (&cube0.boundaries()[0], &cube1.boundaries()[0]);
+    let (classified, faces) = super::process_one_pair_of_shells(shell0, shell1, 0.05).unwrap();
+    let mut count = 0;
+    classified.iter().flatten().for_each(|altshell| {
+        altshell.edge_iter().for_each(|edge| {
+            if let crate::alternative::Alternative::SecondType(_) = edge.curve() {
+                let [face0, face1] = faces[&edge.id()];
+                assert!(shell0.face_iter().any(|face| face.id() == face0));
+                assert!(shell1.face_iter().any(|face| face.id() == face1));
+                count += 1;
+            }
+        })
+    });
+    assert!(count > 0);
+}
+
+#[test]
+fn imprint_cylinder() {
+    use truck_topology::shell::ShellCondition;
+
+    let cube = cube(Point3::origin(), 1.0);
+    let v = builder::vertex(Point3::new(0.5, 0.25, 0.5));
+    let w = builder::rsweep(&v, Point3::new(0.5, 0.5, 0.0), Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let cylinder = builder::tsweep(&f, Vector3::unit_z());
+
+    let (imprinted, origins) = crate::imprint(&cube, &cylinder, 0.05).unwrap();
+    // the top face is divided into the disk and the face with the hole.
+    assert_eq!(imprinted.face_iter().count(), 7);
+    assert_eq!(origins.len(), 7);
+    let top = cube
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().z.near(&1.0)))
+        .unwrap();
+    assert_eq!(origins.iter().filter(|id| **id == top.id()).count(), 2);
+    imprinted.face_iter().zip(&origins).for_each(|(face, id)| {
+        let original = cube.face_iter().find(|face| face.id() == *id).unwrap();
+        assert_eq!(face.orientation(), original.orientation());
+    });
+    assert_eq!(
+        imprinted.boundaries()[0].shell_condition(),
+        ShellCondition::Closed
+    );
+    assert!(f64::abs(volume(&imprinted, 0.01) - 1.0) < 0.01);
+}
+
+#[test]
+fn imprint_separated_cube() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let cube1 = cube(Point3::new(2.0, 0.0, 0.0), 1.0);
+    let (imprinted, origins) = crate::imprint(&cube0, &cube1, 0.05).unwrap();
+    let ids = cube0.face_iter().map(|face| face.id()).collect::<Vec<_>>();
+    assert_eq!(origins, ids);
+    assert!(f64::abs(volume(&imprinted, 0.01) - 1.0) < 0.01);
+}
+
+fn area(shell: &Shell) -> f64 {
+    let poly_shell = shell.triangulation(0.01);
+    poly_shell
+        .face_iter()
+        .map(|face| {
+            let polygon = face.surface().unwrap();
+            let positions = polygon.positions();
+            polygon
+                .faces()
+                .triangle_iter()
+                .map(|tri| tri.map(|v| positions[v.pos]))
+                .map(|[a, b, c]| (b - a).cross(c - a).magnitude() / 2.0)
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+fn sheet(origin: Point3, size: f64) -> Shell {
+    let v = builder::vertex(origin);
+    let e = builder::tsweep(&v, Vector3::unit_x() * size);
+    vec![builder::tsweep(&e, Vector3::unit_y() * size)].into()
+}
+
+#[test]
+fn split_sheet_ending_inside() {
+    // the open boundary of the sheet is in the interior of the cube.
+    let cube = cube(Point3::origin(), 1.0);
+    let sheet = sheet(Point3::new(0.5, 0.5, 0.5), 1.0);
+    let (inside, outside) = crate::split_shell_by_solid(&sheet, &cube, 0.05).unwrap();
+    assert_eq!(inside.len(), 1);
+    assert_eq!(outside.len(), 1);
+    assert!(f64::abs(area(&inside) - 0.25) < 1.0e-3);
+    assert!(f64::abs(area(&outside) - 0.75) < 1.0e-3);
+}
+
+#[test]
+fn split_sheet_by_hollow_solid() {
+    // a cube with a cubic cavity
+    let outer = builder::scaled(
+        &cube(Point3::origin(), 1.0),
+        Point3::origin(),
+        Vector3::new(3.0, 3.0, 3.0),
+    );
+    let mut inner = cube(Point3::new(1.0, 1.0, 1.0), 1.0);
+    inner.not();
+    let boundaries = [outer, inner]
+        .into_iter()
+        .flat_map(Solid::into_boundaries)
+        .collect();
+    let hollow = Solid::new(boundaries);
+
+    let sheet = sheet(Point3::new(-1.0, -1.0, 1.5), 5.0);
+    let (inside, outside) = crate::split_shell_by_solid(&sheet, &hollow, 0.05).unwrap();
+    assert!(f64::abs(area(&inside) - 8.0) < 1.0e-3);
+    assert!(f64::abs(area(&outside) - 17.0) < 1.0e-3);
+}
+
+#[test]
+fn split_separated_sheet() {
+    let cube = cube(Point3::origin(), 1.0);
+    let sheet = sheet(Point3::new(2.0, 0.0, 0.5), 1.0);
+    let (inside, outside) = crate::split_shell_by_solid(&sheet, &cube, 0.05).unwrap();
+    assert!(inside.is_empty());
+    assert_eq!(outside.len(), 1);
+}
+
+#[test]
+fn imprint_stacked_cubes() {
+    let cube0 = cube(Point3::origin(), 1.0);
+    let top = cube0
+        .face_iter()
+        .find(|face| face.vertex_iter().all(|v| v.point().z.near(&1.0)))
+        .unwrap()
+        .id();
+    let contact_faces = |imprinted: &Solid, origins: &[FaceID]| {
+        imprinted
+            .face_iter()
+            .zip(origins)
+            .filter(|(_, id)| **id == top)
+            .map(|(face, _)| face.clone())
+            .collect::<Shell>()
+    };
+
+    // the cube sharing a corner of the top face
+    let cube1 = cube(Point3::new(0.5, 0.5, 1.0), 1.0);
+    let (imprinted, origins) = crate::imprint(&cube0, &cube1, 0.05).unwrap();
+    let divided = contact_faces(&imprinted, &origins);
+    assert_eq!(divided.len(), 2);
+    let contact = divided
+        .iter()
+        .find(|face| {
+            face.vertex_iter()
+                .all(|v| v.point().x > 0.4 && v.point().y > 0.4)
+        })
+        .unwrap();
+    assert!(f64::abs(area(&vec![contact.clone()].into()) - 0.25) < 1.0e-3);
+    assert!(f64::abs(area(&divided) - 1.0) < 1.0e-3);
+    assert!(f64::abs(volume(&imprinted, 0.01) - 1.0) < 0.01);
+
+    // the smaller cube in the interior of the top face
+    let cube1 = cube(Point3::new(0.25, 0.25, 1.0), 0.5);
+    let (imprinted, origins) = crate::imprint(&cube0, &cube1, 0.05).unwrap();
+    let divided = contact_faces(&imprinted, &origins);
+    assert_eq!(divided.len(), 2);
+    let holed = divided
+        .iter()
+        .find(|face| face.boundaries().len() == 2)
+        .unwrap();
+    assert!(f64::abs(area(&vec![holed.clone()].into()) - 0.75) < 1.0e-3);
+    assert!(f64::abs(volume(&imprinted, 0.01) - 1.0) < 0.01);
+
+    // the cube sharing the whole top face does not divide it.
+    let cube1 = cube(Point3::new(0.0, 0.0, 1.0), 1.0);
+    let (imprinted, origins) = crate::imprint(&cube0, &cube1, 0.05).unwrap();
+    assert_eq!(imprinted.face_iter().count(), 6);
+    assert_eq!(contact_faces(&imprinted, &origins).len(), 1);
+
+    // the cube put side by side, sharing a half of the right face
+    let cube1 = cube(Point3::new(1.0, 0.5, 0.0), 1.0);
+    let (imprinted, _) = crate::imprint(&cube0, &cube1, 0.05).unwrap();
+    let right = imprinted
+        .face_iter()
+        .filter(|face| face.vertex_iter().all(|v| v.point().x.near(&1.0)))
+        .count();
+    assert_eq!(right, 2);
+    assert!(f64::abs(volume(&imprinted, 0.01) - 1.0) < 0.01);
+}
@@ -1,3 +1,4 @@
+use super::analytic::{conic_projection, AnalyticSurface};
 use truck_base::cgmath64::*;
 use truck_geometry::prelude::*;
 use truck_meshalgo::prelude::*;
@@ -49,6 +50,73 @@ where
             params1,
         })
     }
+
+    /// Creates the intersection curve between the elementary surfaces without marching along the polyline.
+    /// Only the middle point is projected onto the both surfaces, and the other points are projected onto the conic
+    /// through it.
+    ///
+    /// Returns `None` if the surfaces are not recognized, or the intersection curve is not a conic.
+    pub fn try_new_analytic(
+        surface0: S0,
+        surface1: S1,
+        poly: PolylineCurve<Point3>,
+    ) -> Option<Self>
+    where
+        S0: AnalyticSurface,
+        S1: AnalyticSurface,
+    {
+        let len = poly.len();
+        let closed = poly[0].near(&poly[len - 1]);
+        let ic = IntersectionCurve::new(&surface0, &surface1, poly);
+        let (middle, _, _) = ic.search_triple((len / 2) as f64, 100)?;
+        let project = conic_projection(&surface0, &surface1, middle)?;
+        let mut polyline = PolylineCurve(Vec::new());
+        let mut params0 = PolylineCurve(Vec::new());
+        let mut params1 = PolylineCurve(Vec::new());
+        let (mut hint0, mut hint1) = (None, None);
+        for (i, pt) in ic.leader().iter().enumerate() {
+            let q = project(*pt);
+            let p0 = search_on_surface(&surface0, q, hint0);
+            let p1 = search_on_surface(&surface1, q, hint1);
+            // The point is marched only if the parameters are not found, e.g. at the singular points.
+            let (q, p0, p1) = match (p0, p1) {
+                (Some(p0), Some(p1)) => (q, Point2::from(p0), Point2::from(p1)),
+                _ => ic.search_triple(i as f64, 100)?,
+            };
+            polyline.push(q);
+            params0.push(p0);
+            params1.push(p1);
+            (hint0, hint1) = (Some(p0.into()), Some(p1.into()));
+        }
+        if closed {
+            polyline[len - 1] = polyline[0];
+            params0[len - 1] = params0[0];
+            params1[len - 1] = params1[0];
+        }
+        Some(Self {
+            ic: IntersectionCurve::new(surface0, surface1, polyline),
+            params0,
+            params1,
+        })
+    }
+}
+
+// The parameter of `point` on `surface`, searched from the hint first.
+fn search_on_surface<S>(
+    surface: &S,
+    point: Point3,
+    hint: Option<(f64, f64)>,
+) -> Option<(f64, f64)>
+where
+    S: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
+{
+    let (u, v) = hint
+        .and_then(|hint| surface.search_nearest_parameter(point, hint, 100))
+        .or_else(|| surface.search_nearest_parameter(point, None, 100))?;
+    match surface.subs(u, v).near(&point) {
+        true => Some((u, v)),
+        false => None,
+    }
 }
 
 impl<S0, S1> ParametricCurve for IntersectionCurveWithParameters<S0, S1>
@@ -153,25 +221,49 @@ pub fn intersection_curves<S0, S1>(
     polygon1: &PolygonMesh,
 ) -> Option<Vec<IntersectionTuple<S0, S1>>>
 where
-    S0: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
-    S1: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
+    S0: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3> + AnalyticSurface,
+    S1: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3> + AnalyticSurface,
 {
     let interferences = polygon0.extract_interference(polygon1);
     let polylines = super::polyline_construction::construct_polylines(&interferences);
     polylines
         .into_iter()
+        .filter(|polyline| !is_degenerate(polyline) && !is_tangent(&surface0, &surface1, polyline))
         .map(|polyline| {
-            Some((
-                polyline.clone(),
-                IntersectionCurveWithParameters::try_new(
-                    surface0.clone(),
-                    surface1.clone(),
-                    polyline,
-                )?,
-            ))
+            let (s0, s1) = (surface0.clone(), surface1.clone());
+            // The marching is skipped for the conics between the elementary surfaces.
+            let curve = IntersectionCurveWithParameters::try_new_analytic(s0, s1, polyline.clone())
+                .or_else(|| {
+                    let (s0, s1) = (surface0.clone(), surface1.clone());
+                    IntersectionCurveWithParameters::try_new(s0, s1, polyline.clone())
+                })?;
+            Some((polyline, curve))
         })
         .collect()
 }
 
+// The polyline shrinks to a point, e.g. the faces touching at a vertex.
+fn is_degenerate(polyline: &PolylineCurve<Point3>) -> bool {
+    polyline.windows(2).all(|pts| pts[0].near(&pts[1]))
+}
+
+// The surfaces are tangent along the polyline, e.g. a cylinder put on a plane. Such contacts do not divide the faces.
+fn is_tangent<S0, S1>(surface0: &S0, surface1: &S1, polyline: &PolylineCurve<Point3>) -> bool
+where
+    S0: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
+    S1: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>, {
+    let (t0, t1) = polyline.range_tuple();
+    let pt = polyline.subs((t0 + t1) / 2.0);
+    let params0 = surface0.search_nearest_parameter(pt, None, 100);
+    let params1 = surface1.search_nearest_parameter(pt, None, 100);
+    match (params0, params1) {
+        (Some((u0, v0)), Some((u1, v1))) => surface0
+            .normal(u0, v0)
+            .cross(surface1.normal(u1, v1))
+            .so_small(),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests;
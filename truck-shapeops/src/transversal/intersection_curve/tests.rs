@@ -60,6 +60,44 @@ fn intersection_curve_sphere_case() {
     assert_near!(curve1.back(), curve.back());
 }
 
+#[test]
+fn intersection_curve_analytic_case() {
+    let sphere = Sphere::new(Point3::new(0.0, 0.0, 1.0), f64::sqrt(2.0));
+    let plane = Plane::new(
+        Point3::origin(),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+    );
+    const M: usize = 5;
+    let polyline = (0..=M)
+        .map(|i| {
+            let t = 2.0 * PI * i as f64 / M as f64;
+            Point3::new(0.8 * f64::cos(t), 0.8 * f64::sin(t), 0.01)
+        })
+        .collect::<PolylineCurve<_>>();
+    let curve =
+        IntersectionCurveWithParameters::try_new_analytic(sphere, plane, polyline.clone()).unwrap();
+    // the points of the polyline are on the unit circle, and the parameters are on the both surfaces.
+    curve.leader().iter().enumerate().for_each(|(i, pt)| {
+        assert_near!(pt.distance(Point3::origin()), 1.0);
+        assert!(pt.z.so_small());
+        assert_near!(sphere.subs(curve.params0[i].x, curve.params0[i].y), *pt);
+        assert_near!(plane.subs(curve.params1[i].x, curve.params1[i].y), *pt);
+    });
+    assert_near!(curve.leader()[0], curve.leader()[M]);
+
+    // the curve between the general surfaces is not created.
+    let surface = BSplineSurface::new(
+        (KnotVec::bezier_knot(2), KnotVec::bezier_knot(1)),
+        vec![
+            vec![Point3::new(-2.0, -2.0, 0.0), Point3::new(-2.0, 2.0, 0.0)],
+            vec![Point3::new(0.0, -2.0, 1.0), Point3::new(0.0, 2.0, 1.0)],
+            vec![Point3::new(2.0, -2.0, 0.0), Point3::new(2.0, 2.0, 0.0)],
+        ],
+    );
+    assert!(IntersectionCurveWithParameters::try_new_analytic(sphere, surface, polyline).is_none());
+}
+
 #[test]
 fn collide_parabola() {
     const TOL: f64 = 0.05;
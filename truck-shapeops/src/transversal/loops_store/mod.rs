@@ -14,6 +14,10 @@ pub enum ShapesOpStatus {
     Unknown,
     And,
     Or,
+    // on a face of the other shell with the same orientation
+    OnSame,
+    // on a face of the other shell with the opposite orientation
+    OnOpposite,
 }
 
 impl ShapesOpStatus {
@@ -22,6 +26,8 @@ impl ShapesOpStatus {
             Self::Unknown => Self::Unknown,
             Self::And => Self::Or,
             Self::Or => Self::And,
+            Self::OnSame => Self::OnSame,
+            Self::OnOpposite => Self::OnOpposite,
         }
     }
 }
@@ -284,6 +290,79 @@ impl<P: Copy, C: Clone> Loops<P, C> {
     }
 }
 
+impl Loops<Point3, PolylineCurve> {
+    // Returns `true` if `polyline` runs along the boundary.
+    fn includes(&self, polyline: &PolylineCurve) -> bool {
+        let midpoints = polyline.windows(2).map(|pts| pts[0].midpoint(pts[1]));
+        polyline
+            .iter()
+            .copied()
+            .chain(midpoints)
+            .all(|pt| self.search_parameter(pt).is_some())
+    }
+
+    // The edges from `v0` to `v1` along `polyline`: the index of the wire and the indices of the edges.
+    fn forward_path(
+        &self,
+        v0: &Vertex<Point3>,
+        v1: &Vertex<Point3>,
+        polyline: &PolylineCurve,
+    ) -> Option<(usize, Vec<usize>)> {
+        self.iter().enumerate().find_map(|(i, wire)| {
+            let len = wire.len();
+            (0..len).filter(|&j| wire[j].front() == v0).find_map(|j| {
+                let mut indices = Vec::new();
+                for idx in (j..j + len).map(|idx| idx % len) {
+                    if !along(&wire[idx], polyline) {
+                        return None;
+                    }
+                    indices.push(idx);
+                    if wire[idx].back() == v1 {
+                        return Some((i, indices));
+                    }
+                }
+                None
+            })
+        })
+    }
+
+    // The edges from `v0` to `v1` along `polyline`. If the edges run from `v1` to `v0`, `true` is also returned.
+    fn path(
+        &self,
+        v0: &Vertex<Point3>,
+        v1: &Vertex<Point3>,
+        polyline: &PolylineCurve,
+    ) -> Option<(usize, Vec<usize>, bool)> {
+        if let Some((i, indices)) = self.forward_path(v0, v1, polyline) {
+            return Some((i, indices, false));
+        }
+        let (i, indices) = self.forward_path(v1, v0, polyline)?;
+        Some((i, indices, true))
+    }
+
+    // The wire along the closed `polyline`. If the wire runs backward, `true` is also returned.
+    fn closed_path(&self, polyline: &PolylineCurve) -> Option<(usize, Vec<usize>, bool)> {
+        let (i, wire) = self
+            .iter()
+            .enumerate()
+            .find(|(_, wire)| wire.edge_iter().all(|edge| along(edge, polyline)))?;
+        let curve = wire.front_edge()?.oriented_curve();
+        let (p, q) = (curve[0], curve[1]);
+        let t = polyline.search_parameter(p.midpoint(q), None, 1)?;
+        let reversed = polyline.der(t).dot(q - p) < 0.0;
+        Some((i, (0..wire.len()).collect(), reversed))
+    }
+}
+
+// Returns `true` if `edge` runs along `polyline`.
+fn along(edge: &Edge<Point3, PolylineCurve>, polyline: &PolylineCurve) -> bool {
+    let curve = edge.curve();
+    let (t0, t1) = curve.range_tuple();
+    polyline
+        .search_parameter(curve.subs((t0 + t1) / 2.0), None, 1)
+        .is_some()
+}
+
 impl<P: Copy + Tolerance, C: Clone> LoopsStore<P, C> {
     #[inline(always)]
     fn change_vertex(
@@ -318,13 +397,17 @@ impl<P: Copy + Tolerance, C: Clone> LoopsStore<P, C> {
                 let old_vertex = self[loops_index][wire_index][edge_index]
                     .absolute_front()
                     .clone();
-                self.change_vertex(&old_vertex, v, emap);
+                if old_vertex != *v {
+                    self.change_vertex(&old_vertex, v, emap);
+                }
             }
             ParameterKind::Back => {
                 let old_vertex = self[loops_index][wire_index][edge_index]
                     .absolute_back()
                     .clone();
-                self.change_vertex(&old_vertex, v, emap);
+                if old_vertex != *v {
+                    self.change_vertex(&old_vertex, v, emap);
+                }
             }
             ParameterKind::Inner(t) => {
                 let edge = self[loops_index][wire_index][edge_index].absolute_clone();
@@ -356,15 +439,19 @@ impl<C> LoopsStore<Point3, C> {
                 let old_vertex = self[loops_index][wire_index][edge_index]
                     .absolute_front()
                     .clone();
-                v.set_point(old_vertex.point());
-                self.change_vertex(&old_vertex, v, emap);
+                if old_vertex != *v {
+                    v.set_point(old_vertex.point());
+                    self.change_vertex(&old_vertex, v, emap);
+                }
             }
             ParameterKind::Back => {
                 let old_vertex = self[loops_index][wire_index][edge_index]
                     .absolute_back()
                     .clone();
-                v.set_point(old_vertex.point());
-                self.change_vertex(&old_vertex, v, emap);
+                if old_vertex != *v {
+                    v.set_point(old_vertex.point());
+                    self.change_vertex(&old_vertex, v, emap);
+                }
             }
             ParameterKind::Inner(_) => {
                 let curve = self[loops_index][wire_index][edge_index].curve();
@@ -432,6 +519,178 @@ where
     wire![edge0, edge1]
 }
 
+// The loops of one of the pair of faces, with the surface of the other face.
+struct FaceLoops<'a, C, S> {
+    poly_loops_store: &'a mut LoopsStore<Point3, PolylineCurve>,
+    geom_loops_store: &'a mut LoopsStore<Point3, C>,
+    face_index: usize,
+    another_surface: &'a S,
+    status: ShapesOpStatus,
+}
+
+type EdgePair<C> = (Edge<Point3, PolylineCurve>, Edge<Point3, C>);
+type VertexPair<'a> = (&'a Vertex<Point3>, &'a Vertex<Point3>);
+type EdgeMaps<C> = (
+    HashMap<EdgeID<PolylineCurve>, Edge<Point3, PolylineCurve>>,
+    HashMap<EdgeID<C>, Edge<Point3, C>>,
+);
+
+impl<C, S> FaceLoops<'_, C, S>
+where
+    C: SearchNearestParameter<D1, Point = Point3> + Cut<Point = Point3, Vector = Vector3>,
+    S: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
+{
+    // Adds the vertex to the boundary of the face, if the vertex is on the boundary.
+    fn add_vertex(
+        &mut self,
+        (pv, gv): VertexPair<'_>,
+        (pemap, gemap): &mut EdgeMaps<C>,
+    ) -> Option<()> {
+        let idx = self
+            .poly_loops_store
+            .add_polygon_vertex(self.face_index, pv, pemap);
+        if let Some((wire_index, edge_index, kind)) = idx {
+            self.geom_loops_store.add_geom_vertex(
+                (self.face_index, wire_index, edge_index),
+                gv,
+                kind,
+                self.another_surface,
+                gemap,
+            )?;
+        }
+        Some(())
+    }
+
+    // The boundary edges along `polyline`, from its front to its back.
+    fn path(
+        &self,
+        ends: Option<VertexPair<'_>>,
+        polyline: &PolylineCurve,
+    ) -> Option<Vec<EdgePair<C>>> {
+        let loops = &self.poly_loops_store[self.face_index];
+        let (wire_index, indices, reversed) = match ends {
+            Some((v0, v1)) => loops.path(v0, v1, polyline)?,
+            None => loops.closed_path(polyline)?,
+        };
+        let poly_wire = &self.poly_loops_store[self.face_index][wire_index];
+        let geom_wire = &self.geom_loops_store[self.face_index][wire_index];
+        let mut path: Vec<_> = indices
+            .into_iter()
+            .map(|idx| (poly_wire[idx].clone(), geom_wire[idx].clone()))
+            .collect();
+        if reversed {
+            path.reverse();
+            path.iter_mut().for_each(|(pedge, gedge)| {
+                pedge.invert();
+                gedge.invert();
+            });
+        }
+        Some(path)
+    }
+
+    // Imprints the edges of the other face.
+    fn add_path(&mut self, path: Vec<EdgePair<C>>, closed: bool) {
+        let loops_index = self.face_index;
+        if closed {
+            let (poly_wire, geom_wire): (Vec<_>, Vec<_>) = path.into_iter().unzip();
+            self.poly_loops_store[loops_index]
+                .add_independent_loop(BoundaryWire::new(poly_wire.into(), self.status));
+            self.geom_loops_store[loops_index]
+                .add_independent_loop(BoundaryWire::new(geom_wire.into(), self.status));
+        } else {
+            path.into_iter().for_each(|(pedge, gedge)| {
+                self.poly_loops_store[loops_index].add_edge(pedge, self.status);
+                self.geom_loops_store[loops_index].add_edge(gedge, self.status);
+            });
+        }
+    }
+
+    // Replaces the edges in `old` by the corresponding ones in `new`.
+    fn replace_path(&mut self, old: &[EdgePair<C>], new: &[EdgePair<C>]) {
+        old.iter()
+            .zip(new)
+            .filter(|((pold, _), (pnew, _))| pold.id() != pnew.id())
+            .for_each(|((pold, gold), (pnew, gnew))| {
+                let (poly_wire, geom_wire) = match pold.orientation() {
+                    true => (wire![pnew.clone()], wire![gnew.clone()]),
+                    false => (wire![pnew.inverse()], wire![gnew.inverse()]),
+                };
+                self.poly_loops_store
+                    .swap_edge_into_wire(pold.id(), &poly_wire);
+                self.geom_loops_store
+                    .swap_edge_into_wire(gold.id(), &geom_wire);
+            })
+    }
+}
+
+// Adds `polyline` running along the boundary of the faces. The intersection curve is not added, and the boundary
+// edges are shared instead: the edges of one face are imprinted on the other face, or the coincident edges of the
+// both faces are identified.
+fn add_boundary_polyline<C, S>(
+    [mut face_loops0, mut face_loops1]: [FaceLoops<'_, C, S>; 2],
+    on_boundary: [bool; 2],
+    polyline: &PolylineCurve,
+) -> Option<()>
+where
+    C: SearchNearestParameter<D1, Point = Point3> + Cut<Point = Point3, Vector = Vector3>,
+    S: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
+{
+    let closed = polyline.front().near(&polyline.back());
+    let pv = [Vertex::new(polyline.front()), Vertex::new(polyline.back())];
+    let gv = [Vertex::new(polyline.front()), Vertex::new(polyline.back())];
+    let ends = (!closed).then_some((&pv[0], &pv[1]));
+    // adds the vertex to the both faces with the common maps, so that the shared edges are replaced by the same edges.
+    let add_vertex = |face_loops0: &mut FaceLoops<'_, C, S>,
+                      face_loops1: &mut FaceLoops<'_, C, S>,
+                      vertices: VertexPair<'_>| {
+        let mut emaps = (HashMap::default(), HashMap::default());
+        face_loops0.add_vertex(vertices, &mut emaps)?;
+        face_loops1.add_vertex(vertices, &mut emaps)
+    };
+    if !closed {
+        add_vertex(&mut face_loops0, &mut face_loops1, (&pv[0], &gv[0]))?;
+        add_vertex(&mut face_loops0, &mut face_loops1, (&pv[1], &gv[1]))?;
+    }
+    match on_boundary {
+        [true, false] => face_loops1.add_path(face_loops0.path(ends, polyline)?, closed),
+        [false, true] => face_loops0.add_path(face_loops1.path(ends, polyline)?, closed),
+        _ => {
+            let path0 = face_loops0.path(ends, polyline)?;
+            let path1 = face_loops1.path(ends, polyline)?;
+            // shares the vertices on the paths
+            let vertices = |path: &[EdgePair<C>]| -> Vec<(Vertex<Point3>, Vertex<Point3>)> {
+                path.iter()
+                    .map(|(pedge, gedge)| (pedge.front().clone(), gedge.front().clone()))
+                    .collect()
+            };
+            let (vertices0, vertices1) = (vertices(&path0), vertices(&path1));
+            vertices0
+                .iter()
+                .chain(&vertices1)
+                .filter(|(pv, _)| {
+                    let in0 = vertices0.iter().any(|(v, _)| v == pv);
+                    let in1 = vertices1.iter().any(|(v, _)| v == pv);
+                    !(in0 && in1)
+                })
+                .try_for_each(|(pv, gv)| {
+                    add_vertex(&mut face_loops0, &mut face_loops1, (pv, gv))
+                })?;
+            let path0 = face_loops0.path(ends, polyline)?;
+            let mut path1 = face_loops1.path(ends, polyline)?;
+            if path0.len() != path1.len() {
+                return None;
+            }
+            let start = path1
+                .iter()
+                .position(|(pedge, _)| pedge.front() == path0[0].0.front())?;
+            path1.rotate_left(start);
+            face_loops0.replace_path(&path1, &path0);
+            face_loops1.replace_path(&path1, &path0);
+        }
+    }
+    Some(())
+}
+
 #[allow(dead_code)]
 pub struct LoopsStoreQuadruple<C> {
     pub geom_loops_store0: LoopsStore<Point3, C>,
@@ -440,133 +699,211 @@ pub struct LoopsStoreQuadruple<C> {
     pub poly_loops_store1: LoopsStore<Point3, PolylineCurve>,
 }
 
+/// The failure of [`create_loops_stores`] with the indices of the faces.
+#[derive(Clone, Debug)]
+pub enum LoopsStoreError {
+    /// the polygon of the face is not created: `(index of shell, index of face)`.
+    NoPolygon(usize, usize),
+    /// failed to compute the intersection curves between the faces.
+    Intersection(usize, usize),
+    /// failed to add the intersection curve to the boundaries of the faces.
+    Insertion(usize, usize, PolylineCurve),
+}
+
 pub fn create_loops_stores<C, S>(
     geom_shell0: &Shell<Point3, C, S>,
     poly_shell0: &Shell<Point3, PolylineCurve, Option<PolygonMesh>>,
     geom_shell1: &Shell<Point3, C, S>,
     poly_shell1: &Shell<Point3, PolylineCurve, Option<PolygonMesh>>,
-) -> Option<LoopsStoreQuadruple<C>>
+    tol: f64,
+) -> std::result::Result<LoopsStoreQuadruple<C>, LoopsStoreError>
 where
     C: SearchNearestParameter<D1, Point = Point3>
         + SearchParameter<D1, Point = Point3>
         + Cut<Point = Point3, Vector = Vector3>
-        + From<IntersectionCurve<PolylineCurve, S, S>>,
-    S: ParametricSurface3D + SearchNearestParameter<D2, Point = Point3>,
+        + From<IntersectionCurve<PolylineCurve, S, S>>
+        + Send
+        + Sync,
+    S: ParametricSurface3D
+        + SearchNearestParameter<D2, Point = Point3>
+        + AnalyticSurface
+        + Send
+        + Sync,
 {
     let mut geom_loops_store0: LoopsStore<_, _> = geom_shell0.face_iter().collect();
     let mut poly_loops_store0: LoopsStore<_, _> = poly_shell0.face_iter().collect();
     let mut geom_loops_store1: LoopsStore<_, _> = geom_shell1.face_iter().collect();
     let mut poly_loops_store1: LoopsStore<_, _> = poly_shell1.face_iter().collect();
-    let store0_len = geom_loops_store0.len();
-    let store1_len = geom_loops_store1.len();
-    (0..store0_len)
-        .flat_map(move |i| (0..store1_len).map(move |j| (i, j)))
-        .try_for_each(|(face_index0, face_index1)| {
+    let polygons = |poly_shell: &Shell<Point3, PolylineCurve, Option<PolygonMesh>>, shell_index| {
+        poly_shell
+            .face_iter()
+            .enumerate()
+            .map(|(face_index, face)| {
+                let polygon = face
+                    .surface()
+                    .ok_or(LoopsStoreError::NoPolygon(shell_index, face_index))?;
+                let bdb = polygon.bounding_box();
+                Ok((polygon, bdb))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+    };
+    let polygons0 = polygons(poly_shell0, 0)?;
+    let polygons1 = polygons(poly_shell1, 1)?;
+    // The faces do not intersect if the bounding boxes of their polygons are apart by more than the deviations of the
+    // polygons, which are up to `tol` for each. The intersection curves of the other pairs are computed independently,
+    // and are added to the loops one after another.
+    let pairs = (0..polygons0.len())
+        .flat_map(|i| (0..polygons1.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| overlap(&polygons0[i].1, &polygons1[j].1, 2.0 * tol))
+        .collect::<Vec<_>>();
+    let curves = parallel::map(&pairs, |&(face_index0, face_index1)| {
+        intersection_curve::intersection_curves(
+            geom_shell0[face_index0].surface(),
+            &polygons0[face_index0].0,
+            geom_shell1[face_index1].surface(),
+            &polygons1[face_index1].0,
+        )
+    });
+    pairs
+        .into_iter()
+        .zip(curves)
+        .try_for_each(|((face_index0, face_index1), curves)| {
             let ori0 = geom_shell0[face_index0].orientation();
             let ori1 = geom_shell1[face_index1].orientation();
             let surface0 = geom_shell0[face_index0].surface();
             let surface1 = geom_shell1[face_index1].surface();
-            let polygon0 = poly_shell0[face_index0].surface()?;
-            let polygon1 = poly_shell1[face_index1].surface()?;
-            intersection_curve::intersection_curves(
-                surface0.clone(),
-                &polygon0,
-                surface1.clone(),
-                &polygon1,
-            )?
-            .into_iter()
-            .try_for_each(|(polyline, intersection_curve)| {
-                let mut intersection_curve = intersection_curve.into();
-                let status = ShapesOpStatus::from_is_curve(&intersection_curve)?;
-                let (status0, status1) = match (ori0, ori1) {
-                    (true, true) => (status, status.not()),
-                    (true, false) => (status.not(), status.not()),
-                    (false, true) => (status, status),
-                    (false, false) => (status.not(), status),
-                };
-                if polyline.front().near(&polyline.back()) {
-                    let poly_wire = create_independent_loop(polyline);
-                    poly_loops_store0[face_index0]
-                        .add_independent_loop(BoundaryWire::new(poly_wire.clone(), status0));
-                    poly_loops_store1[face_index1]
-                        .add_independent_loop(BoundaryWire::new(poly_wire, status1));
-                    let geom_wire = create_independent_loop(intersection_curve);
-                    geom_loops_store0[face_index0]
-                        .add_independent_loop(BoundaryWire::new(geom_wire.clone(), status0));
-                    geom_loops_store1[face_index1]
-                        .add_independent_loop(BoundaryWire::new(geom_wire, status1));
-                } else {
-                    let pv0 = Vertex::new(polyline.front());
-                    let pv1 = Vertex::new(polyline.back());
-                    let gv0 = Vertex::new(polyline.front());
-                    let gv1 = Vertex::new(polyline.back());
-                    let mut pemap0 = HashMap::default();
-                    let mut pemap1 = HashMap::default();
-                    let mut gemap0 = HashMap::default();
-                    let mut gemap1 = HashMap::default();
-                    let idx00 =
-                        poly_loops_store0.add_polygon_vertex(face_index0, &pv0, &mut pemap0);
-                    if let Some((wire_index, edge_index, kind)) = idx00 {
-                        geom_loops_store0.add_geom_vertex(
-                            (face_index0, wire_index, edge_index),
-                            &gv0,
-                            kind,
-                            &surface1,
-                            &mut gemap0,
-                        )?;
-                        let polyline = intersection_curve.leader_mut();
-                        *polyline.first_mut().unwrap() = gv0.point();
-                    }
-                    let idx01 =
-                        poly_loops_store0.add_polygon_vertex(face_index0, &pv1, &mut pemap1);
-                    if let Some((wire_index, edge_index, kind)) = idx01 {
-                        geom_loops_store0.add_geom_vertex(
-                            (face_index0, wire_index, edge_index),
-                            &gv1,
-                            kind,
-                            &surface1,
-                            &mut gemap1,
-                        )?;
-                        let polyline = intersection_curve.leader_mut();
-                        *polyline.last_mut().unwrap() = gv1.point();
+            let curves = curves.ok_or(LoopsStoreError::Intersection(face_index0, face_index1))?;
+            curves
+                .into_iter()
+                .try_for_each(|(polyline, intersection_curve)| {
+                    let error =
+                        || LoopsStoreError::Insertion(face_index0, face_index1, polyline.clone());
+                    let mut intersection_curve = intersection_curve.into();
+                    let status =
+                        ShapesOpStatus::from_is_curve(&intersection_curve).ok_or_else(error)?;
+                    let (status0, status1) = match (ori0, ori1) {
+                        (true, true) => (status, status.not()),
+                        (true, false) => (status.not(), status.not()),
+                        (false, true) => (status, status),
+                        (false, false) => (status.not(), status),
+                    };
+                    let on_boundary = [
+                        poly_loops_store0[face_index0].includes(&polyline),
+                        poly_loops_store1[face_index1].includes(&polyline),
+                    ];
+                    if on_boundary.contains(&true) {
+                        let face_loops0 = FaceLoops {
+                            poly_loops_store: &mut poly_loops_store0,
+                            geom_loops_store: &mut geom_loops_store0,
+                            face_index: face_index0,
+                            another_surface: &surface1,
+                            status: status0,
+                        };
+                        let face_loops1 = FaceLoops {
+                            poly_loops_store: &mut poly_loops_store1,
+                            geom_loops_store: &mut geom_loops_store1,
+                            face_index: face_index1,
+                            another_surface: &surface0,
+                            status: status1,
+                        };
+                        return add_boundary_polyline(
+                            [face_loops0, face_loops1],
+                            on_boundary,
+                            &polyline,
+                        )
+                        .ok_or_else(error);
                     }
-                    let idx10 =
-                        poly_loops_store1.add_polygon_vertex(face_index1, &pv0, &mut pemap0);
-                    if let Some((wire_index, edge_index, kind)) = idx10 {
-                        geom_loops_store1.add_geom_vertex(
-                            (face_index1, wire_index, edge_index),
-                            &gv0,
-                            kind,
-                            &surface0,
-                            &mut gemap0,
-                        )?;
-                        let polyline = intersection_curve.leader_mut();
-                        *polyline.first_mut().unwrap() = gv0.point();
+                    if polyline.front().near(&polyline.back()) {
+                        let poly_wire = create_independent_loop(polyline);
+                        poly_loops_store0[face_index0]
+                            .add_independent_loop(BoundaryWire::new(poly_wire.clone(), status0));
+                        poly_loops_store1[face_index1]
+                            .add_independent_loop(BoundaryWire::new(poly_wire, status1));
+                        let geom_wire = create_independent_loop(intersection_curve);
+                        geom_loops_store0[face_index0]
+                            .add_independent_loop(BoundaryWire::new(geom_wire.clone(), status0));
+                        geom_loops_store1[face_index1]
+                            .add_independent_loop(BoundaryWire::new(geom_wire, status1));
+                    } else {
+                        let pv0 = Vertex::new(polyline.front());
+                        let pv1 = Vertex::new(polyline.back());
+                        let gv0 = Vertex::new(polyline.front());
+                        let gv1 = Vertex::new(polyline.back());
+                        let mut pemap0 = HashMap::default();
+                        let mut pemap1 = HashMap::default();
+                        let mut gemap0 = HashMap::default();
+                        let mut gemap1 = HashMap::default();
+                        let idx00 =
+                            poly_loops_store0.add_polygon_vertex(face_index0, &pv0, &mut pemap0);
+                        if let Some((wire_index, edge_index, kind)) = idx00 {
+                            geom_loops_store0
+                                .add_geom_vertex(
+                                    (face_index0, wire_index, edge_index),
+                                    &gv0,
+                                    kind,
+                                    &surface1,
+                                    &mut gemap0,
+                                )
+                                .ok_or_else(error)?;
+                            let polyline = intersection_curve.leader_mut();
+                            *polyline.first_mut().unwrap() = gv0.point();
+                        }
+                        let idx01 =
+                            poly_loops_store0.add_polygon_vertex(face_index0, &pv1, &mut pemap1);
+                        if let Some((wire_index, edge_index, kind)) = idx01 {
+                            geom_loops_store0
+                                .add_geom_vertex(
+                                    (face_index0, wire_index, edge_index),
+                                    &gv1,
+                                    kind,
+                                    &surface1,
+                                    &mut gemap1,
+                                )
+                                .ok_or_else(error)?;
+                            let polyline = intersection_curve.leader_mut();
+                            *polyline.last_mut().unwrap() = gv1.point();
+                        }
+                        let idx10 =
+                            poly_loops_store1.add_polygon_vertex(face_index1, &pv0, &mut pemap0);
+                        if let Some((wire_index, edge_index, kind)) = idx10 {
+                            geom_loops_store1
+                                .add_geom_vertex(
+                                    (face_index1, wire_index, edge_index),
+                                    &gv0,
+                                    kind,
+                                    &surface0,
+                                    &mut gemap0,
+                                )
+                                .ok_or_else(error)?;
+                            let polyline = intersection_curve.leader_mut();
+                            *polyline.first_mut().unwrap() = gv0.point();
+                        }
+                        let idx11 =
+                            poly_loops_store1.add_polygon_vertex(face_index1, &pv1, &mut pemap1);
+                        if let Some((wire_index, edge_index, kind)) = idx11 {
+                            geom_loops_store1
+                                .add_geom_vertex(
+                                    (face_index1, wire_index, edge_index),
+                                    &gv1,
+                                    kind,
+                                    &surface0,
+                                    &mut gemap1,
+                                )
+                                .ok_or_else(error)?;
+                            let polyline = intersection_curve.leader_mut();
+                            *polyline.last_mut().unwrap() = gv1.point();
+                        }
+                        let pedge = Edge::new(&pv0, &pv1, polyline);
+                        let gedge = Edge::new(&gv0, &gv1, intersection_curve.into());
+                        poly_loops_store0[face_index0].add_edge(pedge.clone(), status0);
+                        geom_loops_store0[face_index0].add_edge(gedge.clone(), status0);
+                        poly_loops_store1[face_index1].add_edge(pedge, status1);
+                        geom_loops_store1[face_index1].add_edge(gedge, status1);
                     }
-                    let idx11 =
-                        poly_loops_store1.add_polygon_vertex(face_index1, &pv1, &mut pemap1);
-                    if let Some((wire_index, edge_index, kind)) = idx11 {
-                        geom_loops_store1.add_geom_vertex(
-                            (face_index1, wire_index, edge_index),
-                            &gv1,
-                            kind,
-                            &surface0,
-                            &mut gemap1,
-                        )?;
-                        let polyline = intersection_curve.leader_mut();
-                        *polyline.last_mut().unwrap() = gv1.point();
-                    }
-                    let pedge = Edge::new(&pv0, &pv1, polyline);
-                    let gedge = Edge::new(&gv0, &gv1, intersection_curve.into());
-                    poly_loops_store0[face_index0].add_edge(pedge.clone(), status0);
-                    geom_loops_store0[face_index0].add_edge(gedge.clone(), status0);
-                    poly_loops_store1[face_index1].add_edge(pedge, status1);
-                    geom_loops_store1[face_index1].add_edge(gedge, status1);
-                }
-                Some(())
-            })
+                    Ok(())
+                })
         })?;
-    Some(LoopsStoreQuadruple {
+    Ok(LoopsStoreQuadruple {
         geom_loops_store0,
         poly_loops_store0,
         geom_loops_store1,
@@ -1,7 +1,29 @@
+mod analytic;
 mod divide_face;
 mod faces_classification;
 mod integrate;
 mod intersection_curve;
 mod loops_store;
+mod parallel;
 mod polyline_construction;
-pub use integrate::{and, or, ShapeOpsCurve, ShapeOpsSurface};
+mod union_all;
+pub(crate) use analytic::{ellipse_arc, exact_curve};
+pub use analytic::{AnalyticSurface, ElementarySurface};
+pub use integrate::{
+    and, difference, imprint, or, split_shell_by_solid, symmetric_difference, ShapeOpsCurve,
+    ShapeOpsSurface,
+};
+pub use union_all::union_all;
+
+use truck_base::{bounding_box::BoundingBox, cgmath64::Point3};
+
+// Whether the bounding boxes overlap, or they are apart by at most `margin` along each axis. The margin absorbs the
+// deviations of the tessellations from the surfaces, so that the shapes touching each other are not skipped.
+fn overlap(bdb0: &BoundingBox<Point3>, bdb1: &BoundingBox<Point3>, margin: f64) -> bool {
+    let (min0, max0) = (bdb0.min(), bdb0.max());
+    let (min1, max1) = (bdb1.min(), bdb1.max());
+    (0..3).all(|i| min0[i] <= max1[i] + margin && min1[i] <= max0[i] + margin)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,23 @@
+//! The independent jobs run in parallel by [`rayon`] if the feature `parallel` is enabled, and one after another
+//! otherwise, e.g. in the wasm build.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+pub(super) use rayon::join;
+
+#[cfg(not(feature = "parallel"))]
+pub(super) fn join<A, B>(a: impl FnOnce() -> A, b: impl FnOnce() -> B) -> (A, B) { (a(), b()) }
+
+/// Maps the elements of `slice` by `f`, and collects the results in the same order.
+#[cfg(feature = "parallel")]
+pub(super) fn map<T: Sync, U: Send>(slice: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    slice.par_iter().map(f).collect()
+}
+
+/// Maps the elements of `slice` by `f`, and collects the results in the same order.
+#[cfg(not(feature = "parallel"))]
+pub(super) fn map<T: Sync, U: Send>(slice: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    slice.iter().map(f).collect()
+}
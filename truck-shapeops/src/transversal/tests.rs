@@ -0,0 +1,24 @@
+use super::overlap;
+use truck_base::{bounding_box::BoundingBox, cgmath64::Point3};
+
+#[test]
+fn overlap_with_margin() {
+    let bdb = |min: (f64, f64, f64), max: (f64, f64, f64)| {
+        let (min, max) = (
+            Point3::new(min.0, min.1, min.2),
+            Point3::new(max.0, max.1, max.2),
+        );
+        [min, max].into_iter().collect::<BoundingBox<Point3>>()
+    };
+    let bdb0 = bdb((0.0, 0.0, 0.0), (1.0, 1.0, 1.0));
+    // touching each other
+    assert!(overlap(&bdb0, &bdb((1.0, 0.0, 0.0), (2.0, 1.0, 1.0)), 0.0));
+    // apart by the gap smaller than the margin
+    let bdb1 = bdb((1.01, 0.5, 0.5), (2.0, 2.0, 2.0));
+    assert!(!overlap(&bdb0, &bdb1, 0.0));
+    assert!(overlap(&bdb0, &bdb1, 0.02));
+    assert!(overlap(&bdb1, &bdb0, 0.02));
+    // apart along one axis
+    let bdb1 = bdb((0.0, 1.05, 0.0), (1.0, 2.0, 1.0));
+    assert!(!overlap(&bdb0, &bdb1, 0.02));
+}
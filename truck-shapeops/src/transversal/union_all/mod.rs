@@ -0,0 +1,138 @@
+use super::integrate::{or_shells, polygons, ShapeOpsCurve, ShapeOpsSurface};
+use super::{overlap, parallel::join};
+use crate::errors::ShapeOpsError;
+use truck_base::bounding_box::BoundingBox;
+use truck_geometry::prelude::*;
+use truck_meshalgo::prelude::*;
+use truck_topology::*;
+
+type Result<T, S> = std::result::Result<T, ShapeOpsError<S>>;
+
+// The boundary shells of a solid, an outer shell with its cavities or a union of such ones, with the bounding box.
+// The shells are kept together, since the cavities are classified only with the outer shells surrounding them.
+type Component<C, S> = (Vec<Shell<Point3, C, S>>, BoundingBox<Point3>);
+
+/// OR operation among all `solids`.
+///
+/// The solids are merged in a balanced tree order, and the independent merges run in parallel. The solids whose
+/// bounding boxes do not overlap are not intersected, and are put together as the boundaries of the union. All the
+/// boundaries of a solid, including the cavities, are merged together, so the cavities of a hollow solid remain as
+/// far as they are not filled by the other solids. Returns the empty solid if `solids` is empty.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_meshalgo::prelude::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // two pairs of overlapping cubes, and a separated cube
+/// let solids = [
+///     Vector3::new(0.0, 0.0, 0.0),
+///     Vector3::new(0.5, 0.5, 0.5),
+///     Vector3::new(3.0, 0.0, 0.0),
+///     Vector3::new(3.5, 0.5, 0.5),
+///     Vector3::new(0.0, 3.0, 0.0),
+/// ]
+/// .map(|vector| builder::translated(&cube, vector));
+/// let union = truck_shapeops::union_all(&solids, 0.05).unwrap();
+/// assert_eq!(union.boundaries().len(), 3);
+///
+/// let volume = union.triangulation(0.01).to_polygon().volume();
+/// assert!(f64::abs(volume - 4.75) < 0.01);
+/// ```
+/// # Errors
+/// Returns [`ShapeOpsError`] with the failing stage and the faces involved.
+pub fn union_all<C: ShapeOpsCurve<S>, S: ShapeOpsSurface>(
+    solids: &[Solid<Point3, C, S>],
+    tol: f64,
+) -> Result<Solid<Point3, C, S>, S> {
+    nonpositive_tolerance!(tol);
+    let leaves = solids
+        .iter()
+        .filter(|solid| !solid.boundaries().is_empty())
+        .map(|solid| component(solid.boundaries().clone(), tol))
+        .collect::<Result<Vec<_>, S>>()?;
+    let components = union_tree(leaves, tol)?;
+    Ok(Solid::new(
+        components
+            .into_iter()
+            .flat_map(|(shells, _)| shells)
+            .collect(),
+    ))
+}
+
+fn component<C, S>(shells: Vec<Shell<Point3, C, S>>, tol: f64) -> Result<Component<C, S>, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface, {
+    let mut bdb = BoundingBox::new();
+    for shell in &shells {
+        polygons(shell, &shell.triangulation(tol))?
+            .iter()
+            .for_each(|polygon| polygon.positions().iter().for_each(|pt| bdb.push(*pt)));
+    }
+    Ok((shells, bdb))
+}
+
+// Merges the leaves by dividing them into two halves along the longest side of their bounding box.
+fn union_tree<C, S>(mut leaves: Vec<Component<C, S>>, tol: f64) -> Result<Vec<Component<C, S>>, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface, {
+    if leaves.len() <= 1 {
+        return Ok(leaves);
+    }
+    let center = |(_, bdb): &Component<C, S>| bdb.center();
+    let bdb: BoundingBox<Point3> = leaves.iter().map(center).collect();
+    let diagonal = bdb.diagonal();
+    let axis = (0..3)
+        .max_by(|&i, &j| diagonal[i].total_cmp(&diagonal[j]))
+        .unwrap();
+    leaves.sort_by(|leaf0, leaf1| center(leaf0)[axis].total_cmp(&center(leaf1)[axis]));
+    let others = leaves.split_off(leaves.len() / 2);
+    let (components0, components1) = join(|| union_tree(leaves, tol), || union_tree(others, tol));
+    merge(components0?, components1?, tol)
+}
+
+// Merges each component of `components1` with the components of `components0` overlapping it. The shells of a
+// component are merged at once, so that the faces are classified by the outer shells and the cavities together. The
+// bounding box of the merged components is the union of the original ones, which is enough to skip the separated
+// pairs.
+fn merge<C, S>(
+    mut components0: Vec<Component<C, S>>,
+    components1: Vec<Component<C, S>>,
+    tol: f64,
+) -> Result<Vec<Component<C, S>>, S>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    for (shells1, bdb1) in components1 {
+        let (overlapped, separated) = components0
+            .into_iter()
+            .partition::<Vec<_>, _>(|(_, bdb0)| overlap(bdb0, &bdb1, 2.0 * tol));
+        components0 = separated;
+        if overlapped.is_empty() {
+            components0.push((shells1, bdb1));
+            continue;
+        }
+        let (mut shell, mut bdb) = (
+            shells1.into_iter().flatten().collect::<Shell<_, _, _>>(),
+            bdb1,
+        );
+        for (shells0, bdb0) in overlapped {
+            let shell0 = shells0.into_iter().flatten().collect::<Shell<_, _, _>>();
+            shell = or_shells(&shell0, &shell, tol)?;
+            bdb += bdb0;
+        }
+        components0.push((shell.connected_components(), bdb));
+    }
+    Ok(components0)
+}
+
+#[cfg(test)]
+mod tests;
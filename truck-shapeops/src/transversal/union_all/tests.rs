@@ -0,0 +1,69 @@
+use crate::test_util::{cube, volume};
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+#[test]
+fn chain_of_cubes() {
+    // the adjacent cubes overlap in the box of the size 0.4 x 0.8 x 0.75.
+    let cubes = (0..6)
+        .map(|i| {
+            let shift = (i % 2) as f64;
+            cube(Point3::new(0.6 * i as f64, 0.2 * shift, 0.25 * shift), 1.0)
+        })
+        .collect::<Vec<_>>();
+    let union = crate::union_all(&cubes, 0.05).unwrap();
+    assert_eq!(union.boundaries().len(), 1);
+    assert!(f64::abs(volume(&union, 0.01) - (6.0 - 5.0 * 0.24)) < 0.01);
+}
+
+#[test]
+fn separated_cubes() {
+    let cubes = (0..3)
+        .flat_map(|i| {
+            (0..3).map(move |j| cube(Point3::new(2.0 * i as f64, 2.0 * j as f64, 0.0), 1.0))
+        })
+        .collect::<Vec<_>>();
+    let union = crate::union_all(&cubes, 0.05).unwrap();
+    assert_eq!(union.boundaries().len(), 9);
+    assert_eq!(union.face_iter().count(), 54);
+    assert!(f64::abs(volume(&union, 0.01) - 9.0) < 0.01);
+}
+
+#[test]
+fn hollow_cube() {
+    // the hollow cube `[0, 1]^3` with the cavity `[0.1, 0.9]^3`
+    let hollow = crate::shell(&cube(Point3::origin(), 1.0), &[], 0.1).unwrap();
+
+    // the cube in the cavity remains as an island.
+    let island = builder::scaled(
+        &cube(Point3::origin(), 1.0),
+        Point3::new(0.5, 0.5, 0.5),
+        Vector3::new(0.4, 0.4, 0.4),
+    );
+    let union = crate::union_all(&[hollow.clone(), island], 0.05).unwrap();
+    assert_eq!(union.boundaries().len(), 3);
+    assert!(f64::abs(volume(&union, 0.01) - (1.0 - 0.512 + 0.064)) < 0.01);
+
+    // the slab through the wall fills a part of the cavity.
+    let slab = builder::scaled(
+        &cube(Point3::new(0.5, 0.5, 0.3), 1.0),
+        Point3::new(0.5, 0.5, 0.3),
+        Vector3::new(1.0, 1.0, 0.3),
+    );
+    let union = crate::union_all(&[hollow, slab], 0.05).unwrap();
+    assert_eq!(union.boundaries().len(), 2);
+    let outer = 1.0 + 0.3 - 0.5 * 0.5 * 0.3;
+    let cavity = 0.512 - 0.4 * 0.4 * 0.3;
+    assert!(f64::abs(volume(&union, 0.01) - (outer - cavity)) < 0.01);
+}
+
+#[test]
+fn trivial_inputs() {
+    let union = crate::union_all::<Curve, Surface>(&[], 0.05).unwrap();
+    assert!(union.boundaries().is_empty());
+
+    let cube = cube(Point3::origin(), 1.0);
+    let union = crate::union_all(&[cube], 0.05).unwrap();
+    assert_eq!(union.boundaries().len(), 1);
+    assert_eq!(union.face_iter().count(), 6);
+}
@@ -0,0 +1,380 @@
+use crate::{fillet::circum_center, transversal::ellipse_arc, ShapeOpsCurve, ShapeOpsSurface};
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use std::f64::consts::PI;
+use truck_geometry::prelude::*;
+use truck_meshalgo::prelude::*;
+use truck_topology::*;
+
+/// Merges the adjacent faces of `solid` lying on the same surfaces.
+///
+/// The faces of the boundary shells are merged if they share edges, their surfaces are recognized as the same
+/// [`ElementarySurface`](crate::ElementarySurface), and they are oriented to the same side. The shared edges are
+/// removed, and the surface and the orientation of the first face are adopted for the merged face. Then, the vertices
+/// between two collinear straight edges or two arcs of the same circle, which become redundant, are removed by
+/// replacing the edges with one line or one arc. The vertex is kept if the faces cannot be rebuilt with the new edge.
+///
+/// The faces are kept as they are if the merged face cannot be tessellated with the tolerance `tol` or its area
+/// differs from the sum of the areas of the original faces, e.g. if the faces cover the whole of a closed surface.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube: Solid = builder::tsweep(&f, Vector3::unit_z());
+/// let moved = builder::translated(&cube, Vector3::new(0.5, 0.0, 0.0));
+///
+/// let mut union = truck_shapeops::or(&cube, &moved, 0.05).unwrap();
+/// assert!(union.face_iter().count() > 6);
+///
+/// truck_shapeops::unify_same_domain(&mut union, 0.05);
+/// assert_eq!(union.face_iter().count(), 6);
+/// assert_eq!(union.edge_iter().count(), 24);
+/// ```
+pub fn unify_same_domain<C, S>(solid: &mut Solid<Point3, C, S>, tol: f64)
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface, {
+    nonpositive_tolerance!(tol);
+    let boundaries = solid
+        .boundaries()
+        .iter()
+        .map(|shell| unify_shell(shell, tol).unwrap_or_else(|| shell.clone()))
+        .collect();
+    *solid = Solid::new(boundaries);
+}
+
+fn unify_shell<C, S>(shell: &Shell<Point3, C, S>, tol: f64) -> Option<Shell<Point3, C, S>>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface, {
+    let surfaces = shell
+        .face_iter()
+        .map(|face| face.surface().elementary_surface())
+        .collect::<Vec<_>>();
+    let mut edge_faces = HashMap::<EdgeID<C>, (Edge<Point3, C>, Vec<usize>)>::default();
+    shell.face_iter().enumerate().for_each(|(i, face)| {
+        face.edge_iter().for_each(|edge| {
+            let entry = edge_faces.entry(edge.id());
+            entry.or_insert_with(|| (edge, Vec::new())).1.push(i);
+        })
+    });
+
+    let mut parent = (0..shell.len()).collect::<Vec<_>>();
+    let mut removed = HashSet::default();
+    edge_faces.values().for_each(|(edge, faces)| {
+        let [i, j] = faces[..] else {
+            return;
+        };
+        let same_domain = match (surfaces[i], surfaces[j]) {
+            (Some(surface0), Some(surface1)) => surface0.same_domain(surface1),
+            _ => false,
+        };
+        if i != j && same_domain && same_side(&shell[i], &shell[j], edge) {
+            let (root0, root1) = (root(&mut parent, i), root(&mut parent, j));
+            parent[root1] = root0;
+            removed.insert(edge.id());
+        }
+    });
+    if removed.is_empty() {
+        return None;
+    }
+
+    let mut groups = HashMap::<usize, Vec<usize>>::default();
+    (0..shell.len()).for_each(|i| groups.entry(root(&mut parent, i)).or_default().push(i));
+    let mut merged = HashMap::<usize, Option<Face<Point3, C, S>>>::default();
+    groups
+        .values()
+        .filter(|group| group.len() > 1)
+        .for_each(|group| {
+            let face = merge_faces(shell, group, &removed)
+                .filter(|face| same_area(group.iter().map(|i| shell[*i].clone()), face, tol));
+            match face {
+                Some(face) => {
+                    merged.insert(group[0], Some(face));
+                    group[1..].iter().for_each(|i| {
+                        merged.insert(*i, None);
+                    });
+                }
+                None => {
+                    let ids = group
+                        .iter()
+                        .flat_map(|i| shell[*i].edge_iter())
+                        .map(|edge| edge.id());
+                    let ids = ids.collect::<HashSet<_>>();
+                    removed.retain(|id| !ids.contains(id));
+                }
+            }
+        });
+    let mut unified = (0..shell.len())
+        .filter_map(|i| match merged.get(&i) {
+            Some(face) => face.clone(),
+            None => Some(shell[i].clone()),
+        })
+        .collect::<Shell<Point3, C, S>>();
+
+    let candidates = edge_faces
+        .values()
+        .filter(|(edge, _)| removed.contains(&edge.id()))
+        .flat_map(|(edge, _)| [edge.front().clone(), edge.back().clone()])
+        .collect::<Vec<_>>();
+    candidates
+        .iter()
+        .for_each(|v| remove_redundant_vertex(&mut unified, v, tol));
+    match unified.shell_condition() == shell.shell_condition() {
+        true => Some(unified),
+        false => None,
+    }
+}
+
+fn root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+// Whether the normals of the faces point to the same side at the middle of the common edge.
+fn same_side<C, S>(
+    face0: &Face<Point3, C, S>,
+    face1: &Face<Point3, C, S>,
+    edge: &Edge<Point3, C>,
+) -> bool
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    let curve = edge.curve();
+    let (t0, t1) = curve.range_tuple();
+    let pt = curve.subs((t0 + t1) / 2.0);
+    let normal = |face: &Face<Point3, C, S>| {
+        let surface = face.surface();
+        let (u, v) = surface.search_nearest_parameter(pt, None, 100)?;
+        let normal = surface.normal(u, v);
+        Some(if face.orientation() { normal } else { -normal })
+    };
+    matches!((normal(face0), normal(face1)), (Some(n0), Some(n1)) if n0.dot(n1) > 0.0)
+}
+
+// The face bounded by the boundaries of the faces in `group` except for the removed edges.
+fn merge_faces<C, S>(
+    shell: &Shell<Point3, C, S>,
+    group: &[usize],
+    removed: &HashSet<EdgeID<C>>,
+) -> Option<Face<Point3, C, S>>
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    let mut outgoing = HashMap::<VertexID<Point3>, Vec<Edge<Point3, C>>>::default();
+    group
+        .iter()
+        .flat_map(|i| shell[*i].boundaries())
+        .flatten()
+        .filter(|edge| !removed.contains(&edge.id()))
+        .for_each(|edge| outgoing.entry(edge.front().id()).or_default().push(edge));
+    let mut wires = Vec::new();
+    while let Some(edge) = outgoing.values_mut().find_map(|edges| edges.pop()) {
+        let front = edge.front().id();
+        let mut wire = Wire::from(vec![edge]);
+        while wire.back_vertex()?.id() != front {
+            let edge = outgoing.get_mut(&wire.back_vertex()?.id())?.pop()?;
+            wire.push_back(edge);
+        }
+        wires.push(wire);
+    }
+    let base = &shell[group[0]];
+    if !base.orientation() {
+        wires.iter_mut().for_each(|wire| {
+            wire.invert();
+        });
+    }
+    let mut face = Face::try_new(wires, base.surface()).ok()?;
+    if !base.orientation() {
+        face.invert();
+    }
+    Some(face)
+}
+
+// Whether the area of `face` is the sum of the areas of `faces`.
+fn same_area<C, S>(
+    faces: impl Iterator<Item = Face<Point3, C, S>>,
+    face: &Face<Point3, C, S>,
+    tol: f64,
+) -> bool
+where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    let area = |shell: Shell<Point3, C, S>| {
+        let poly_shell = shell.triangulation(tol);
+        poly_shell.face_iter().try_fold(0.0, |sum, face| {
+            let polygon = face.surface()?;
+            let positions = polygon.positions();
+            let area = polygon
+                .faces()
+                .triangle_iter()
+                .map(|tri| tri.map(|v| positions[v.pos]))
+                .map(|[a, b, c]| (b - a).cross(c - a).magnitude() / 2.0)
+                .sum::<f64>();
+            Some(sum + area)
+        })
+    };
+    match (area(faces.collect()), area(vec![face.clone()].into())) {
+        (Some(area0), Some(area1)) => f64::abs(area0 - area1) <= area0 * 1.0e-2,
+        _ => false,
+    }
+}
+
+// Whether the sample points of the curve of `edge` are on the segment between its end points.
+fn straight<C: ParametricCurve3D + ParameterDivision1D<Point = Point3> + BoundedCurve>(
+    edge: &Edge<Point3, C>,
+    tol: f64,
+) -> bool {
+    let (p, q) = (edge.front().point(), edge.back().point());
+    let curve = edge.curve();
+    let (_, pts) = curve.parameter_division(curve.range_tuple(), tol);
+    let dir = (q - p).normalize();
+    pts.into_iter().all(|pt| {
+        let v = pt - p;
+        (v - v.dot(dir) * dir).magnitude() <= tol
+    })
+}
+
+// The sample points of the curve of `edge`, ordered so that the last one is `vertex`.
+fn samples_to<C>(edge: &Edge<Point3, C>, vertex: &Vertex<Point3>, tol: f64) -> Vec<Point3>
+where C: ParametricCurve3D + ParameterDivision1D<Point = Point3> + BoundedCurve + Invertible {
+    let curve = edge.oriented_curve();
+    let (_, mut pts) = curve.parameter_division(curve.range_tuple(), tol);
+    if edge.front() == vertex {
+        pts.reverse();
+    }
+    pts
+}
+
+// The arc from `v0` to `v1` through `vertex`, if the two edges incident to `vertex` are the arcs of the same circle.
+fn merged_arc<C>(
+    [edge0, edge1]: [&Edge<Point3, C>; 2],
+    vertex: &Vertex<Point3>,
+    tol: f64,
+) -> Option<NurbsCurve<Vector4>>
+where
+    C: ParametricCurve3D + ParameterDivision1D<Point = Point3> + BoundedCurve + Invertible,
+{
+    let samples0 = samples_to(edge0, vertex, tol);
+    let mut samples1 = samples_to(edge1, vertex, tol);
+    samples1.reverse();
+    let (p0, p, p1) = (samples0[0], vertex.point(), *samples1.last()?);
+    // the arc runs counterclockwise around the axis.
+    let axis = (p - p0).cross(p1 - p);
+    if axis.so_small() {
+        return None;
+    }
+    let axis = axis.normalize();
+    let center = circum_center(p0, p, p1);
+    let radius = p0.distance(center);
+    let x = (p0 - center) / radius;
+    let y = axis.cross(x);
+    let eps = tol / radius;
+    let angle = |pt: Point3| {
+        let v = pt - center;
+        let angle = f64::atan2(v.dot(y), v.dot(x));
+        match angle < -eps {
+            true => angle + 2.0 * PI,
+            false => angle,
+        }
+    };
+    let on_circle = |pt: &Point3| {
+        let v = *pt - center;
+        f64::abs(v.magnitude() - radius) <= tol && f64::abs(v.dot(axis)) <= tol
+    };
+    let (angle_p, angle_p1) = (angle(p), angle(p1));
+    let in_range = |pts: &[Point3], (a0, a1): (f64, f64)| {
+        pts.iter()
+            .all(|pt| on_circle(pt) && (a0 - eps..=a1 + eps).contains(&angle(*pt)))
+    };
+    match angle_p < angle_p1
+        && angle_p1 < 2.0 * PI - eps
+        && in_range(&samples0, (0.0, angle_p))
+        && in_range(&samples1, (angle_p, angle_p1))
+    {
+        true => Some(ellipse_arc(center, radius * x, radius * y, (0.0, angle_p1))),
+        false => None,
+    }
+}
+
+// Replaces the two edges incident to `vertex` with one line or one arc, if no other edges are incident to it and the
+// edges are collinear straight ones or arcs of the same circle. Nothing is replaced if a face cannot be rebuilt.
+fn remove_redundant_vertex<C, S>(
+    shell: &mut Shell<Point3, C, S>,
+    vertex: &Vertex<Point3>,
+    tol: f64,
+) where
+    C: ShapeOpsCurve<S>,
+    S: ShapeOpsSurface,
+{
+    let mut ids = HashSet::default();
+    let edges = shell
+        .edge_iter()
+        .filter(|edge| edge.front() == vertex || edge.back() == vertex)
+        .filter(|edge| ids.insert(edge.id()))
+        .collect::<Vec<_>>();
+    let [edge0, edge1] = &edges[..] else {
+        return;
+    };
+    let other = |edge: &Edge<Point3, C>| match edge.front() == vertex {
+        true => edge.back().clone(),
+        false => edge.front().clone(),
+    };
+    let (v0, v1) = (other(edge0), other(edge1));
+    if v0 == v1 {
+        return;
+    }
+    let (dir0, dir1) = (v0.point() - vertex.point(), v1.point() - vertex.point());
+    let collinear = dir0.normalize().dot(dir1.normalize()).near(&-1.0);
+    let curve = if collinear && straight(edge0, tol) && straight(edge1, tol) {
+        C::from(Line(v0.point(), v1.point()))
+    } else if let Some(arc) = merged_arc([edge0, edge1], vertex, tol) {
+        C::from(arc)
+    } else {
+        return;
+    };
+    let merged = Edge::new(&v0, &v1, curve);
+    let faces = shell
+        .face_iter()
+        .map(|face| {
+            if !face.edge_iter().any(|edge| edge.id() == edge0.id()) {
+                return Some(face.clone());
+            }
+            let wires = face
+                .absolute_boundaries()
+                .iter()
+                .map(|wire| {
+                    let replace = |edge: &Edge<Point3, C>| match edge.id() == edge0.id() {
+                        false => edge.clone(),
+                        true if edge.front() == &v0 => merged.clone(),
+                        true => merged.inverse(),
+                    };
+                    wire.iter()
+                        .filter(|edge| edge.id() != edge1.id())
+                        .map(replace)
+                        .collect::<Wire<Point3, C>>()
+                })
+                .collect::<Vec<_>>();
+            let mut new_face = Face::try_new(wires, face.surface()).ok()?;
+            if !face.orientation() {
+                new_face.invert();
+            }
+            Some(new_face)
+        })
+        .collect::<Option<Shell<Point3, C, S>>>();
+    if let Some(faces) = faces {
+        *shell = faces;
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,114 @@
+use super::{merged_arc, unify_same_domain};
+use crate::test_util::{cube, volume};
+use rustc_hash::FxHashSet as HashSet;
+use std::f64::consts::PI;
+use truck_meshalgo::prelude::*;
+use truck_modeling::*;
+
+fn count_vertices(solid: &Solid) -> usize {
+    solid
+        .vertex_iter()
+        .map(|v| v.id())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+#[test]
+fn stacked_cubes() {
+    let mut union = crate::or(
+        &cube(Point3::origin(), 1.0),
+        &cube(Point3::new(0.0, 0.0, 1.0), 1.0),
+        0.05,
+    )
+    .unwrap();
+    unify_same_domain(&mut union, 0.05);
+    assert_eq!(union.face_iter().count(), 6);
+    assert_eq!(count_vertices(&union), 8);
+    assert!(f64::abs(volume(&union, 0.01) - 2.0) < 0.01);
+}
+
+#[test]
+fn shifted_cubes() {
+    // the top and the bottom faces are merged, and the side faces are kept.
+    let mut union = crate::or(
+        &cube(Point3::origin(), 1.0),
+        &cube(Point3::new(0.5, 0.5, 0.0), 1.0),
+        0.05,
+    )
+    .unwrap();
+    unify_same_domain(&mut union, 0.05);
+    assert_eq!(union.face_iter().count(), 10);
+    assert_eq!(count_vertices(&union), 16);
+    assert!(f64::abs(volume(&union, 0.01) - 1.75) < 0.01);
+}
+
+#[test]
+fn half_cylinder() {
+    // the curved side is divided into two quarters.
+    let v =
+        [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0)].map(|(x, y)| builder::vertex(Point3::new(x, y, 0.0)));
+    let s = f64::sqrt(0.5);
+    let arc0 = builder::circle_arc(&v[0], &v[1], Point3::new(s, s, 0.0));
+    let arc1 = builder::circle_arc(&v[1], &v[2], Point3::new(-s, s, 0.0));
+    let line = builder::line(&v[2], &v[0]);
+    let wire: Wire = vec![arc0, arc1, line].into();
+    let f = builder::try_attach_plane(&[wire]).unwrap();
+    let mut solid: Solid = builder::tsweep(&f, Vector3::unit_z());
+    assert_eq!(solid.face_iter().count(), 5);
+
+    unify_same_domain(&mut solid, 0.01);
+    assert_eq!(solid.face_iter().count(), 4);
+    // the quarters of the top and the bottom circles are merged into the half circles.
+    assert_eq!(count_vertices(&solid), 4);
+    assert!(f64::abs(volume(&solid, 0.01) - PI / 2.0) < 0.01);
+}
+
+#[test]
+fn merge_arcs() {
+    // the arcs of 135 degrees are merged into the arc of 270 degrees, which avoids the fourth quadrant.
+    let s = f64::sqrt(0.5);
+    let v =
+        [(1.0, 0.0), (-s, s), (0.0, -1.0)].map(|(x, y)| builder::vertex(Point3::new(x, y, 0.0)));
+    let arc0 = builder::circle_arc(&v[0], &v[1], Point3::new(0.0, 1.0, 0.0));
+    let arc1 = builder::circle_arc(&v[2], &v[1], Point3::new(-1.0, 0.0, 0.0));
+    let arc = merged_arc([&arc0, &arc1], &v[1], 0.01).unwrap();
+    assert_near!(arc.front(), v[0].point());
+    assert_near!(arc.back(), v[2].point());
+    let (_, pts) = arc.parameter_division(arc.range_tuple(), 0.01);
+    pts.iter().for_each(|pt| {
+        assert!(f64::abs(pt.to_vec().magnitude() - 1.0) < 1.0e-6);
+        assert!(pt.x <= 1.0e-6 || pt.y >= -1.0e-6);
+    });
+    assert!(pts.iter().any(|pt| pt.x < -0.99));
+
+    // the arcs of the different circles are not merged.
+    let arc1 = builder::circle_arc(&v[2], &v[1], Point3::new(-0.9, 0.0, 0.0));
+    assert!(merged_arc([&arc0, &arc1], &v[1], 0.01).is_none());
+}
+
+#[test]
+fn whole_cylinder() {
+    // the faces covering the whole cylinder are not merged.
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let w = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let f = builder::try_attach_plane(&[w]).unwrap();
+    let mut cylinder: Solid = builder::tsweep(&f, Vector3::unit_z());
+    let faces = cylinder.face_iter().count();
+    let original = volume(&cylinder, 0.01);
+    unify_same_domain(&mut cylinder, 0.01);
+    assert_eq!(cylinder.face_iter().count(), faces);
+    assert!(f64::abs(volume(&cylinder, 0.01) - original) < 1.0e-6);
+}
+
+#[test]
+fn separated_faces() {
+    // the faces on the same plane are not merged if they are not adjacent.
+    let mut union = crate::or(
+        &cube(Point3::origin(), 1.0),
+        &cube(Point3::new(2.0, 0.0, 0.0), 1.0),
+        0.05,
+    )
+    .unwrap();
+    unify_same_domain(&mut union, 0.05);
+    assert_eq!(union.face_iter().count(), 12);
+}